@@ -1,5 +1,7 @@
 //! General-purpose error and result types returned by public APIs of this crate.
 
+use std::ffi::NulError;
+
 /// The common result type returned by `citro2d` functions.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -9,4 +11,15 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     /// A C2D object or context could not be initialized.
     FailedToInitialize,
+    /// The given string contained a null byte and could not be passed to `citro2d`.
+    InvalidString,
+    /// The render target has no stencil buffer, which is needed for masking.
+    /// See [`Target::new_with_stencil`](crate::render::Target::new_with_stencil).
+    NoStencilBuffer,
+}
+
+impl From<NulError> for Error {
+    fn from(_: NulError) -> Self {
+        Self::InvalidString
+    }
 }
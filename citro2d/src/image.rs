@@ -0,0 +1,154 @@
+//! Safe bindings for images loaded from `.t3x` sprite sheets.
+use std::ffi::CString;
+use std::rc::Rc;
+
+use crate::{Error, Result, Size};
+
+/// A sprite sheet (`.t3x` file) containing one or more [`Image`]s. Cloning a
+/// [`SpriteSheet`] is cheap and shares the underlying texture.
+#[doc(alias = "C2D_SpriteSheet")]
+#[derive(Clone)]
+pub struct SpriteSheet(Rc<SpriteSheetInner>);
+
+struct SpriteSheetInner(citro2d_sys::C2D_SpriteSheet);
+
+impl Drop for SpriteSheetInner {
+    #[doc(alias = "C2D_SpriteSheetFree")]
+    fn drop(&mut self) {
+        unsafe {
+            citro2d_sys::C2D_SpriteSheetFree(self.0);
+        }
+    }
+}
+
+impl SpriteSheet {
+    /// Load a sprite sheet from the bytes of a `.t3x` file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the data is not a valid sprite sheet.
+    #[doc(alias = "C2D_SpriteSheetLoadFromMem")]
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let raw =
+            unsafe { citro2d_sys::C2D_SpriteSheetLoadFromMem(data.as_ptr().cast(), data.len()) };
+        Self::from_raw(raw)
+    }
+
+    /// Load a sprite sheet from a `.t3x` file at the given path (e.g. in the romfs).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file could not be read or is not a valid sprite sheet.
+    #[doc(alias = "C2D_SpriteSheetLoad")]
+    pub fn load(path: &str) -> Result<Self> {
+        let path = CString::new(path)?;
+        let raw = unsafe { citro2d_sys::C2D_SpriteSheetLoad(path.as_ptr()) };
+        Self::from_raw(raw)
+    }
+
+    fn from_raw(raw: citro2d_sys::C2D_SpriteSheet) -> Result<Self> {
+        if raw.is_null() {
+            Err(Error::FailedToInitialize)
+        } else {
+            Ok(Self(Rc::new(SpriteSheetInner(raw))))
+        }
+    }
+
+    /// The number of images in the sheet.
+    #[doc(alias = "C2D_SpriteSheetCount")]
+    pub fn len(&self) -> usize {
+        unsafe { citro2d_sys::C2D_SpriteSheetCount(self.0.0) }
+    }
+
+    /// Whether the sheet contains no images.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the image at `index`, or `None` if it is out of bounds.
+    #[doc(alias = "C2D_SpriteSheetGetImage")]
+    pub fn image(&self, index: usize) -> Option<Image> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let raw = unsafe { citro2d_sys::C2D_SpriteSheetGetImage(self.0.0, index) };
+        Some(Image {
+            tex: raw.tex,
+            subtex: unsafe { *raw.subtex },
+            _sheet: self.clone(),
+        })
+    }
+}
+
+/// A region of a texture which can be drawn, e.g. as a
+/// [`Fill::Image`](crate::shapes::Fill::Image).
+#[doc(alias = "C2D_Image")]
+#[derive(Clone)]
+pub struct Image {
+    tex: *mut citro2d_sys::C3D_Tex,
+    subtex: citro2d_sys::Tex3DS_SubTexture,
+    // Keeps the texture alive for as long as the image is.
+    _sheet: SpriteSheet,
+}
+
+impl Image {
+    /// The size of the image in pixels.
+    pub fn size(&self) -> Size {
+        Size::new(self.subtex.width.into(), self.subtex.height.into())
+    }
+
+    /// The texture coordinates of the image.
+    pub fn uv(&self) -> Uv {
+        Uv {
+            left: self.subtex.left,
+            top: self.subtex.top,
+            right: self.subtex.right,
+            bottom: self.subtex.bottom,
+        }
+    }
+
+    /// Use different texture coordinates for this image, e.g. to draw only
+    /// part of it or to repeat it (with a repeating wrap mode).
+    pub fn with_uv(mut self, uv: Uv) -> Self {
+        self.subtex.left = uv.left;
+        self.subtex.top = uv.top;
+        self.subtex.right = uv.right;
+        self.subtex.bottom = uv.bottom;
+        self
+    }
+
+    /// Draw the image stretched to fill the given rectangle.
+    #[doc(alias = "C2D_DrawImageAt")]
+    pub(crate) fn draw_stretched(&self, x: f32, y: f32, depth: f32, size: Size) -> bool {
+        if self.subtex.width == 0 || self.subtex.height == 0 {
+            return false;
+        }
+
+        let raw = citro2d_sys::C2D_Image {
+            tex: self.tex,
+            subtex: &self.subtex,
+        };
+        unsafe {
+            citro2d_sys::C2D_DrawImageAt(
+                raw,
+                x,
+                y,
+                depth,
+                std::ptr::null(),
+                size.width / f32::from(self.subtex.width),
+                size.height / f32::from(self.subtex.height),
+            )
+        }
+    }
+}
+
+/// Texture coordinates of an [`Image`], in the range `0.0..=1.0`. Note that
+/// `top` is usually greater than `bottom`, since textures are stored upside-down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uv {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
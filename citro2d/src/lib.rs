@@ -16,11 +16,18 @@
 #![doc = document_features::document_features!()]
 
 pub mod error;
+pub mod image;
 pub mod render;
+pub mod scene;
 pub mod shapes;
+pub mod text;
+#[cfg(feature = "ui")]
+pub mod ui;
 use citro2d_sys::C2D_DEFAULT_MAX_OBJECTS;
+use citro3d::render::effect::{TestFunction, WriteMask};
 pub use error::{Error, Result};
 use render::Target;
+use scene::{Scene, View};
 
 /// The single instance for using `citro2d`. This is the base type that an application
 /// should instantiate to use this library.
@@ -69,7 +76,16 @@ impl Instance {
             false => Err(Error::FailedToInitialize),
         };
         unsafe { citro2d_sys::C2D_Prepare() };
-        new_citro_2d
+        new_citro_2d.map(|mut instance| {
+            // Keep the state tracked by citro3d in sync with the depth test
+            // configured by `C2D_Prepare`, so it can be restored after masking.
+            instance.citro3d_instance.set_depth_test(
+                true,
+                TestFunction::GreaterOrEqual,
+                WriteMask::All,
+            );
+            instance
+        })
     }
 
     /// Render 2d graphics to a selected [Target]
@@ -88,6 +104,31 @@ impl Instance {
         }
     }
 
+    /// Render the same [`Scene`] to two targets (usually the top and bottom
+    /// screens) within a single frame. Each target gets its own [`View`], so
+    /// the scene can be offset or scaled differently per screen.
+    #[doc(alias = "C3D_FrameBegin")]
+    #[doc(alias = "C2D_SceneBegin")]
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn render_scene_to_both(
+        &mut self,
+        scene: &Scene,
+        (top, top_view): (&mut Target<'_>, View),
+        (bottom, bottom_view): (&mut Target<'_>, View),
+    ) {
+        unsafe {
+            citro3d_sys::C3D_FrameBegin(citro3d_sys::C3D_FRAME_SYNCDRAW);
+
+            citro2d_sys::C2D_SceneBegin(top.raw);
+            scene.replay(top, top_view);
+
+            citro2d_sys::C2D_SceneBegin(bottom.raw);
+            scene.replay(bottom, bottom_view);
+
+            citro3d_sys::C3D_FrameEnd(0);
+        }
+    }
+
     /// Returns some stats about the 3Ds's graphics
     /// TODO this may be more appropriate in citro3d
     pub fn get_3d_stats(&self) -> Citro3DStats {
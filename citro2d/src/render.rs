@@ -1,9 +1,11 @@
 //! Safe bindings to render 2d graphics to a [Target]
 use std::cell::RefMut;
 
-use ctru::services::gfx::Screen;
+use citro3d::render::transfer;
+use ctru::services::gfx::{RawFrameBuffer, Screen};
 
-use crate::{Error, Result, shapes::Shape};
+use crate::shapes::{Fill, Polygon, Shape};
+use crate::{Error, Result, Size};
 
 /// A color in RGBA format. The color is stored as a 32-bit integer
 #[derive(Debug, Clone, Copy)]
@@ -76,4 +78,162 @@ impl<'screen> Target<'screen> {
     {
         shape.render();
     }
+
+    /// Create a 2D [Target] like [`Target::new`], but with a stencil buffer, so
+    /// it can be used with [`Target::masked`]. The targets created by
+    /// `C2D_CreateScreenTarget` only have a 16-bit depth buffer.
+    #[doc(alias = "C3D_RenderTargetCreate")]
+    #[doc(alias = "C3D_RenderTargetSetOutput")]
+    pub fn new_with_stencil(screen: RefMut<'screen, dyn Screen>) -> Result<Self> {
+        let RawFrameBuffer { width, height, .. } = screen.raw_framebuffer();
+        let raw = unsafe {
+            citro3d_sys::C3D_RenderTargetCreate(
+                width as _,
+                height as _,
+                ctru_sys::GPU_RB_RGBA8,
+                ctru_sys::GPU_RB_DEPTH24_STENCIL8,
+            )
+        };
+        if raw.is_null() {
+            return Err(Error::FailedToInitialize);
+        }
+
+        // The same display transfer `C2D_CreateScreenTarget` sets up.
+        let flags = transfer::Flags::default()
+            .in_format(transfer::Format::RGBA8)
+            .out_format(transfer::Format::RGB8);
+        unsafe {
+            citro3d_sys::C3D_RenderTargetSetOutput(
+                raw,
+                screen.as_raw(),
+                screen.side().into(),
+                flags.bits(),
+            );
+        }
+
+        Ok(Self {
+            raw: raw.cast(),
+            _phantom_screen: screen,
+        })
+    }
+
+    /// Whether the target has a stencil buffer.
+    fn has_stencil(&self) -> bool {
+        let frame_buf = unsafe { &(*self.raw).frameBuf };
+        !frame_buf.depthBuf.is_null() && frame_buf.depthFmt == ctru_sys::GPU_RB_DEPTH24_STENCIL8
+    }
+
+    /// Clip everything drawn in `f` to the area covered by the `mask` shapes,
+    /// e.g. for circular minimaps or shaped avatars. The mask shapes themselves
+    /// are not visible.
+    ///
+    /// This uses the stencil buffer of the target, which is left as it was
+    /// found once `f` returns, so several masked regions can be drawn one after
+    /// another. Masks cannot be nested. The depth and stencil settings made
+    /// through `instance` are restored afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Fails without drawing anything if the target has no stencil buffer; see
+    /// [`Target::new_with_stencil`].
+    #[doc(alias = "C3D_StencilTest")]
+    #[doc(alias = "C3D_StencilOp")]
+    pub fn masked<F>(
+        &mut self,
+        instance: &citro3d::Instance,
+        mask: &[&dyn Shape],
+        f: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&mut Self),
+    {
+        if !self.has_stencil() {
+            return Err(Error::NoStencilBuffer);
+        }
+
+        // Write 1 to the stencil buffer wherever the mask covers.
+        write_stencil(instance, mask, 1);
+
+        unsafe {
+            citro3d_sys::C3D_StencilTest(true, ctru_sys::GPU_EQUAL, 1, 0xFF, 0x00);
+            citro3d_sys::C3D_StencilOp(
+                ctru_sys::GPU_STENCIL_KEEP,
+                ctru_sys::GPU_STENCIL_KEEP,
+                ctru_sys::GPU_STENCIL_KEEP,
+            );
+        }
+
+        f(self);
+
+        // Erase the mask again, so it doesn't affect later draws.
+        write_stencil(instance, mask, 0);
+
+        Ok(())
+    }
+
+    /// Draw a [`Polygon`] with any [`Fill`]. `citro2d` can't draw textured
+    /// triangles, so a [`Fill::Image`] is drawn by stretching the image over
+    /// the polygon's bounding box and [masking](Self::masked) it to the
+    /// polygon. Other fills are drawn the same as with [`Shape::render`].
+    ///
+    /// # Errors
+    ///
+    /// Fails without drawing anything if the polygon has an image fill and
+    /// the target has no stencil buffer.
+    #[doc(alias = "C2D_DrawImageAt")]
+    pub fn render_polygon(
+        &mut self,
+        instance: &citro3d::Instance,
+        polygon: &Polygon,
+    ) -> Result<()> {
+        let Fill::Image(image) = &polygon.fill else {
+            polygon.render();
+            return Ok(());
+        };
+        let Some((min, max)) = polygon.bounds() else {
+            return Ok(());
+        };
+
+        let mask = Polygon {
+            points: polygon.points.clone(),
+            fill: Fill::Solid(Color::new(255, 255, 255)),
+            depth: polygon.depth,
+        };
+        self.masked(instance, &[&mask], |_| {
+            image.draw_stretched(
+                min.x,
+                min.y,
+                polygon.depth,
+                Size::new(max.x - min.x, max.y - min.y),
+            );
+        })
+    }
+}
+
+/// Draw `shapes` into the stencil buffer only, replacing the stencil value with
+/// `value` wherever they cover. Afterwards, the effect settings made through
+/// `instance` are sent again, undoing the changes made here.
+fn write_stencil(instance: &citro3d::Instance, shapes: &[&dyn Shape], value: i32) {
+    unsafe {
+        // State changes only apply to vertices submitted afterwards.
+        citro2d_sys::C2D_Flush();
+
+        citro3d_sys::C3D_StencilTest(true, ctru_sys::GPU_ALWAYS, value, 0xFF, 0xFF);
+        citro3d_sys::C3D_StencilOp(
+            ctru_sys::GPU_STENCIL_KEEP,
+            ctru_sys::GPU_STENCIL_KEEP,
+            ctru_sys::GPU_STENCIL_REPLACE,
+        );
+        // Neither color nor depth is written, so the mask is invisible and
+        // doesn't affect the depth test of anything drawn later. The stencil
+        // buffer is still written, as its writes are controlled separately.
+        citro3d_sys::C3D_DepthTest(false, ctru_sys::GPU_ALWAYS, 0);
+    }
+
+    for shape in shapes {
+        shape.render();
+    }
+
+    unsafe { citro2d_sys::C2D_Flush() };
+    instance.reapply_effects();
 }
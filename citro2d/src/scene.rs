@@ -0,0 +1,110 @@
+//! Record a set of 2D draws once and replay them onto several [`Target`]s.
+//!
+//! A common pattern is to show the same content (or a zoomed/offset view of it)
+//! on both the top and bottom screens. Instead of building the list of shapes
+//! twice per frame, build a [`Scene`] once and hand it to
+//! [`Instance::render_scene_to_both`](crate::Instance::render_scene_to_both).
+use crate::render::{Color, Target};
+use crate::shapes::Shape;
+
+/// A recorded list of 2D draws, which can be replayed onto any number of
+/// render targets within the same frame.
+#[derive(Default)]
+pub struct Scene {
+    clear_color: Option<Color>,
+    shapes: Vec<Box<dyn Shape>>,
+}
+
+impl Scene {
+    /// Create a new, empty scene.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the color each target is cleared to before the scene is drawn.
+    /// If unset, targets are left as they are.
+    pub fn set_clear_color(&mut self, color: Option<Color>) {
+        self.clear_color = color;
+    }
+
+    /// Record a shape to be drawn as part of this scene. Shapes are drawn in
+    /// the order they were added.
+    pub fn push(&mut self, shape: impl Shape + 'static) {
+        self.shapes.push(Box::new(shape));
+    }
+
+    /// Remove all recorded shapes, keeping the allocated storage for reuse.
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    /// The number of recorded shapes.
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    /// Whether the scene has any recorded shapes.
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    /// Draw the scene onto `target` using the given [`View`]. This must be
+    /// called within a frame, after the target has been selected.
+    ///
+    /// Shapes are drawn in the order they were pushed, without any sorting,
+    /// so later shapes are drawn over earlier ones.
+    pub(crate) fn replay(&self, target: &mut Target<'_>, view: View) {
+        if let Some(color) = self.clear_color {
+            target.clear(color);
+        }
+
+        unsafe {
+            citro2d_sys::C2D_ViewReset();
+            citro2d_sys::C2D_ViewTranslate(view.offset.0, view.offset.1);
+            citro2d_sys::C2D_ViewScale(view.scale.0, view.scale.1);
+        }
+
+        for shape in &self.shapes {
+            shape.render();
+        }
+
+        // Flush before resetting, since batched vertices are only transformed
+        // once they are actually submitted.
+        unsafe {
+            citro2d_sys::C2D_Flush();
+            citro2d_sys::C2D_ViewReset();
+        }
+    }
+}
+
+/// The placement of a [`Scene`] on a specific target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    /// Translation applied to every shape in the scene, in pixels.
+    pub offset: (f32, f32),
+    /// Scale applied to every shape in the scene, around the origin, before
+    /// `offset`. The offset itself is not scaled.
+    pub scale: (f32, f32),
+}
+
+impl View {
+    /// A view which draws the scene as recorded.
+    pub const IDENTITY: Self = Self {
+        offset: (0.0, 0.0),
+        scale: (1.0, 1.0),
+    };
+
+    /// Create a view with the given offset and a uniform scale.
+    pub const fn new(offset: (f32, f32), scale: f32) -> Self {
+        Self {
+            offset,
+            scale: (scale, scale),
+        }
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
@@ -1,8 +1,10 @@
 //! Safe bindings to shapes supported by citro2d
+use crate::image::Image;
 use crate::{Point, Size, render::Color};
 
 /// Holds information for rendering multi colored shapes
 /// most shapes have a 'solid'
+#[derive(Debug, Clone, Copy)]
 pub struct MultiColor {
     pub top_left: Color,
     pub top_right: Color,
@@ -222,3 +224,135 @@ impl Shape for Line {
         }
     }
 }
+
+impl MultiColor {
+    /// Bilinearly interpolate between the four corner colors, where `(0, 0)`
+    /// is the top left and `(1, 1)` is the bottom right.
+    fn sample(&self, u: f32, v: f32) -> Color {
+        let top = lerp_color(self.top_left, self.top_right, u);
+        let bottom = lerp_color(self.bottom_left, self.bottom_right, u);
+        lerp_color(top, bottom, v)
+    }
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let [a, b] = [a.inner.to_le_bytes(), b.inner.to_le_bytes()];
+    let channel = |i: usize| (f32::from(a[i]) + (f32::from(b[i]) - f32::from(a[i])) * t) as u8;
+    Color::new_with_alpha(channel(0), channel(1), channel(2), channel(3))
+}
+
+/// How the inside of a [`Panel`] or [`Polygon`] is drawn.
+#[derive(Clone)]
+pub enum Fill {
+    /// A single color.
+    Solid(Color),
+    /// A gradient between four corner colors. For polygons, the corners are
+    /// those of the polygon's bounding box.
+    Gradient(MultiColor),
+    /// An image, stretched to cover the shape. Use [`Image::with_uv`] to choose
+    /// which part of the image is drawn. For polygons, the image covers the
+    /// polygon's bounding box, and they must be drawn with
+    /// [`Target::render_polygon`](crate::render::Target::render_polygon).
+    Image(Image),
+}
+
+/// Holds the information needed to draw a rectangle with any [`Fill`], e.g.
+/// for styled UI panels.
+pub struct Panel {
+    pub point: Point,
+    pub size: Size,
+    pub fill: Fill,
+}
+
+impl Shape for Panel {
+    /// Draws the rectangle with the selected fill
+    #[doc(alias = "C2D_DrawRectSolid")]
+    #[doc(alias = "C2D_DrawRectangle")]
+    #[doc(alias = "C2D_DrawImageAt")]
+    fn render(&self) -> bool {
+        match &self.fill {
+            Fill::Solid(color) => RectangleSolid {
+                point: self.point,
+                size: self.size,
+                color: *color,
+            }
+            .render(),
+            Fill::Gradient(multi_color) => Rectangle {
+                point: self.point,
+                size: self.size,
+                multi_color: *multi_color,
+            }
+            .render(),
+            Fill::Image(image) => {
+                image.draw_stretched(self.point.x, self.point.y, self.point.z, self.size)
+            }
+        }
+    }
+}
+
+/// Holds the information needed to draw a convex polygon.
+pub struct Polygon {
+    /// The vertices of the polygon, in order. The polygon is drawn as a
+    /// triangle fan around the first vertex, so it must be convex.
+    pub points: Vec<Point>,
+    pub fill: Fill,
+    pub depth: f32,
+}
+
+impl Polygon {
+    /// The top left and bottom right corners of the polygon's bounding box, or
+    /// `None` if it has too few points to be drawn.
+    pub(crate) fn bounds(&self) -> Option<(Point, Point)> {
+        if self.points.len() < 3 {
+            return None;
+        }
+
+        let (mut min, mut max) = (self.points[0], self.points[0]);
+        for p in &self.points {
+            min = Point::new_no_z(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new_no_z(max.x.max(p.x), max.y.max(p.y));
+        }
+        Some((min, max))
+    }
+}
+
+impl Shape for Polygon {
+    /// Draws the polygon as a fan of triangles. `citro2d` cannot draw textured
+    /// triangles, so [`Fill::Image`] needs the stencil buffer and is drawn by
+    /// [`Target::render_polygon`](crate::render::Target::render_polygon)
+    /// instead; here it returns `false`.
+    #[doc(alias = "C2D_DrawTriangle")]
+    fn render(&self) -> bool {
+        if let Fill::Image(_) = self.fill {
+            return false;
+        }
+
+        let Some((min, max)) = self.bounds() else {
+            return false;
+        };
+        let width = (max.x - min.x).max(f32::EPSILON);
+        let height = (max.y - min.y).max(f32::EPSILON);
+
+        let color_at = |p: Point| match &self.fill {
+            Fill::Gradient(multi_color) => {
+                multi_color.sample((p.x - min.x) / width, (p.y - min.y) / height)
+            }
+            Fill::Solid(color) => *color,
+            Fill::Image(_) => unreachable!(),
+        };
+
+        let first = self.points[0];
+        self.points[1..].windows(2).all(|edge| {
+            Triangle {
+                top: first,
+                top_color: color_at(first),
+                left: edge[0],
+                left_color: color_at(edge[0]),
+                right: edge[1],
+                right_color: color_at(edge[1]),
+                depth: self.depth,
+            }
+            .render()
+        })
+    }
+}
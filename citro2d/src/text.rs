@@ -0,0 +1,447 @@
+//! Safe bindings for drawing text with the system font or a loaded `.bcfnt` font.
+use std::ffi::CString;
+use std::mem::MaybeUninit;
+use std::rc::Rc;
+
+use ctru::services::cfgu::{Cfgu, Region};
+
+use crate::render::Color;
+use crate::shapes::Shape;
+use crate::{Error, Point, Result, Size};
+
+/// A font which can be used to parse [`Text`]. Cloning a [`Font`] is cheap and
+/// shares the underlying font data.
+#[doc(alias = "C2D_Font")]
+#[derive(Clone)]
+pub struct Font(Rc<FontInner>);
+
+struct FontInner(citro2d_sys::C2D_Font);
+
+impl Drop for FontInner {
+    #[doc(alias = "C2D_FontFree")]
+    fn drop(&mut self) {
+        // A null font is the shared system font, which is never freed.
+        if !self.0.is_null() {
+            unsafe {
+                citro2d_sys::C2D_FontFree(self.0);
+            }
+        }
+    }
+}
+
+impl Font {
+    /// The system font of the console's region, which is also used by [`Text::new`].
+    pub fn system() -> Self {
+        Self(Rc::new(FontInner(std::ptr::null_mut())))
+    }
+
+    /// Load the system font of a specific region, e.g. [`Region::China`] for
+    /// simplified Chinese text, regardless of the console's own region.
+    ///
+    /// If the requested font is the one the console already uses, this is the
+    /// same as [`Font::system`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the console's region could not be read, or the font could not
+    /// be loaded. Callers can fall back to [`Font::system`] if they prefer
+    /// drawing with the wrong font over not drawing at all.
+    #[doc(alias = "C2D_FontLoadSystem")]
+    pub fn system_for_region(cfgu: &Cfgu, region: Region) -> Result<Self> {
+        // `C2D_FontLoadSystem` also returns null for the console's own region,
+        // so check for that first to tell it apart from a failure.
+        if cfgu.region().map_err(|_| Error::FailedToInitialize)? == region {
+            return Ok(Self::system());
+        }
+
+        let raw = unsafe { citro2d_sys::C2D_FontLoadSystem(region as ctru_sys::CFG_Region) };
+        Self::from_raw(raw)
+    }
+
+    /// Load a font from the bytes of a `.bcfnt` file. The data is copied, so
+    /// `data` does not need to outlive the font.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the data is not a valid font.
+    #[doc(alias = "C2D_FontLoadFromMem")]
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let raw = unsafe { citro2d_sys::C2D_FontLoadFromMem(data.as_ptr().cast(), data.len()) };
+        Self::from_raw(raw)
+    }
+
+    /// Load a font from a `.bcfnt` file at the given path (e.g. in the romfs).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file could not be read or is not a valid font.
+    #[doc(alias = "C2D_FontLoad")]
+    pub fn load(path: &str) -> Result<Self> {
+        let path = CString::new(path)?;
+        let raw = unsafe { citro2d_sys::C2D_FontLoad(path.as_ptr()) };
+        Self::from_raw(raw)
+    }
+
+    fn from_raw(raw: citro2d_sys::C2D_Font) -> Result<Self> {
+        if raw.is_null() {
+            Err(Error::FailedToInitialize)
+        } else {
+            Ok(Self(Rc::new(FontInner(raw))))
+        }
+    }
+
+    /// Whether the font contains a glyph for `c`. Characters without a glyph
+    /// are drawn using the font's replacement glyph.
+    pub fn has_glyph(&self, c: char) -> bool {
+        has_glyph(self.as_raw(), c)
+    }
+
+    /// Get the metrics of the font, e.g. for custom text layout. Returns
+    /// [`None`] if the font information isn't available, e.g. because the
+    /// system font isn't loaded.
+    #[doc(alias = "C2D_FontGetInfo")]
+    pub fn metrics(&self) -> Option<FontMetrics> {
+        unsafe {
+            let info = citro2d_sys::C2D_FontGetInfo(self.as_raw()).as_ref()?;
+            let glyphs = info.tglp.as_ref()?;
+            Some(FontMetrics {
+                line_height: info.lineFeed.into(),
+                ascent: info.ascent.into(),
+                baseline: glyphs.baselinePos.into(),
+                cell_size: Size::new(glyphs.cellWidth.into(), glyphs.cellHeight.into()),
+                max_char_width: glyphs.maxCharWidth.into(),
+            })
+        }
+    }
+
+    /// Get the metrics of the glyph used to draw `c`. If the font doesn't
+    /// contain `c`, these are the metrics of the replacement glyph. Returns
+    /// [`None`] if the font has no width information for the glyph.
+    #[doc(alias = "C2D_FontGetCharWidthInfo")]
+    pub fn glyph_metrics(&self, c: char) -> Option<GlyphMetrics> {
+        unsafe {
+            let index = citro2d_sys::C2D_FontGlyphIndexFromCodePoint(self.as_raw(), c.into());
+            let width = citro2d_sys::C2D_FontGetCharWidthInfo(self.as_raw(), index).as_ref()?;
+            Some(GlyphMetrics {
+                left: width.left.into(),
+                width: width.glyphWidth.into(),
+                advance: width.charWidth.into(),
+            })
+        }
+    }
+
+    pub(crate) fn as_raw(&self) -> citro2d_sys::C2D_Font {
+        self.0.0
+    }
+}
+
+/// Font-level metrics, in pixels when drawn with a scale of `1.0`.
+#[doc(alias = "FINF_s")]
+#[doc(alias = "TGLP_s")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// The distance between the tops of consecutive lines.
+    pub line_height: f32,
+    /// The distance from the baseline to the top of the tallest glyphs.
+    pub ascent: f32,
+    /// The distance from the top of a glyph cell to the baseline.
+    pub baseline: f32,
+    /// The size of a single glyph cell.
+    pub cell_size: Size,
+    /// The advance of the widest glyph.
+    pub max_char_width: f32,
+}
+
+/// Metrics of a single glyph, in pixels when drawn with a scale of `1.0`.
+#[doc(alias = "charWidthInfo_s")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphMetrics {
+    /// The horizontal offset from the pen position to the left edge of the glyph.
+    pub left: f32,
+    /// The width of the drawn glyph.
+    pub width: f32,
+    /// How far the pen position moves after drawing the glyph.
+    pub advance: f32,
+}
+
+/// Whether `font` (or the system font, if null) contains a glyph for `c`.
+#[doc(alias = "C2D_FontGlyphIndexFromCodePoint")]
+#[doc(alias = "C2D_FontGetInfo")]
+fn has_glyph(font: citro2d_sys::C2D_Font, c: char) -> bool {
+    unsafe {
+        let info = citro2d_sys::C2D_FontGetInfo(font);
+        if info.is_null() {
+            return false;
+        }
+
+        let index = citro2d_sys::C2D_FontGlyphIndexFromCodePoint(font, c.into());
+        !is_missing_glyph(index, i32::from((*info).alterCharIndex), c)
+    }
+}
+
+/// The code points fonts conventionally draw their replacement glyph for.
+const REPLACEMENT_CHARS: [char; 2] = ['?', char::REPLACEMENT_CHARACTER];
+
+/// Whether looking up `c` in a font, which returned the glyph `index`, means
+/// the font has no glyph for it. Lookups for characters which aren't in the
+/// font return the index of the replacement glyph (`alter_index`) instead of
+/// failing, but the replacement character itself is in the font.
+fn is_missing_glyph(index: i32, alter_index: i32, c: char) -> bool {
+    index < 0 || (index == alter_index && !REPLACEMENT_CHARS.contains(&c))
+}
+
+/// A text buffer holding the glyphs of a single [`Text`].
+struct TextBuf(citro2d_sys::C2D_TextBuf);
+
+impl Drop for TextBuf {
+    #[doc(alias = "C2D_TextBufDelete")]
+    fn drop(&mut self) {
+        unsafe {
+            citro2d_sys::C2D_TextBufDelete(self.0);
+        }
+    }
+}
+
+/// A parsed, ready-to-draw string. Parsing is comparatively expensive, so
+/// static strings should be parsed once and reused across frames.
+#[doc(alias = "C2D_Text")]
+pub struct Text {
+    raw: citro2d_sys::C2D_Text,
+    // The parsed glyphs live in this buffer, and the font has to outlive them too.
+    _buf: TextBuf,
+    _font: Option<Font>,
+    missing_glyphs: Vec<char>,
+}
+
+impl Text {
+    /// Parse a string using the system font.
+    ///
+    /// Line endings are normalized, so `\r\n` and `\r` both start a new line.
+    /// Characters which are not in the font are drawn as the font's replacement
+    /// glyph, and can be found with [`Text::missing_glyphs`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if `text` contains a null byte or the text buffer could not be allocated.
+    #[doc(alias = "C2D_TextParse")]
+    pub fn new(text: &str) -> Result<Self> {
+        Self::parse(None, text)
+    }
+
+    /// Parse a string using the given [`Font`]. See [`Text::new`] for details
+    /// on how the string is handled.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `text` contains a null byte or the text buffer could not be allocated.
+    #[doc(alias = "C2D_TextFontParse")]
+    pub fn with_font(font: &Font, text: &str) -> Result<Self> {
+        Self::parse(Some(font.clone()), text)
+    }
+
+    #[doc(alias = "C2D_TextBufNew")]
+    #[doc(alias = "C2D_TextOptimize")]
+    fn parse(font: Option<Font>, text: &str) -> Result<Self> {
+        // citro2d only breaks lines on `\n`, and would draw `\r` as a glyph.
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let c_text = CString::new(text.as_str())?;
+
+        let buf = unsafe { citro2d_sys::C2D_TextBufNew(text.chars().count().max(1)) };
+        if buf.is_null() {
+            return Err(Error::FailedToInitialize);
+        }
+        let buf = TextBuf(buf);
+
+        let font_raw = font.as_ref().map_or(std::ptr::null_mut(), Font::as_raw);
+
+        let mut missing_glyphs: Vec<char> = text
+            .chars()
+            .filter(|&c| c != '\n' && !has_glyph(font_raw, c))
+            .collect();
+        missing_glyphs.sort_unstable();
+        missing_glyphs.dedup();
+
+        let raw = unsafe {
+            let mut raw = MaybeUninit::zeroed();
+            citro2d_sys::C2D_TextFontParse(raw.as_mut_ptr(), font_raw, buf.0, c_text.as_ptr());
+            citro2d_sys::C2D_TextOptimize(raw.as_ptr());
+            raw.assume_init()
+        };
+
+        Ok(Self {
+            raw,
+            _buf: buf,
+            _font: font,
+            missing_glyphs,
+        })
+    }
+
+    /// The characters of the parsed string which are not in the font, sorted
+    /// and without duplicates. Useful for catching missing glyph coverage in
+    /// localized strings.
+    pub fn missing_glyphs(&self) -> &[char] {
+        &self.missing_glyphs
+    }
+
+    /// Get the size of the text when drawn with the given scale.
+    #[doc(alias = "C2D_TextGetDimensions")]
+    pub fn dimensions(&self, scale_x: f32, scale_y: f32) -> Size {
+        let mut size = Size::new(0.0, 0.0);
+        unsafe {
+            citro2d_sys::C2D_TextGetDimensions(
+                &self.raw,
+                scale_x,
+                scale_y,
+                &mut size.width,
+                &mut size.height,
+            );
+        }
+        size
+    }
+
+    /// Draw the text at `point`. This must be called while rendering to a
+    /// [`Target`](crate::render::Target).
+    #[doc(alias = "C2D_DrawText")]
+    pub fn draw(&self, point: Point, scale: f32, color: Color, alignment: Alignment) {
+        self.draw_with_effects(point, scale, color, alignment, &Effects::default());
+    }
+
+    /// Draw the text at `point` with an outline and/or drop shadow, to keep it
+    /// readable over arbitrary backgrounds. Effects are drawn by re-drawing the
+    /// text at an offset, so each one adds to the cost of drawing the text.
+    #[doc(alias = "C2D_DrawText")]
+    pub fn draw_with_effects(
+        &self,
+        point: Point,
+        scale: f32,
+        color: Color,
+        alignment: Alignment,
+        effects: &Effects,
+    ) {
+        if let Some(shadow) = effects.shadow {
+            let (dx, dy) = shadow.offset;
+            let shadow_point = Point::new(point.x + dx, point.y + dy, point.z);
+            self.draw_raw(shadow_point, scale, shadow.color, alignment);
+        }
+
+        if let Some(outline) = effects.outline {
+            let t = outline.thickness;
+            for (dx, dy) in [
+                (-t, -t),
+                (0.0, -t),
+                (t, -t),
+                (-t, 0.0),
+                (t, 0.0),
+                (-t, t),
+                (0.0, t),
+                (t, t),
+            ] {
+                let outline_point = Point::new(point.x + dx, point.y + dy, point.z);
+                self.draw_raw(outline_point, scale, outline.color, alignment);
+            }
+        }
+
+        self.draw_raw(point, scale, color, alignment);
+    }
+
+    fn draw_raw(&self, point: Point, scale: f32, color: Color, alignment: Alignment) {
+        let flags = citro2d_sys::C2D_WithColor as u32 | alignment as u32;
+        unsafe {
+            citro2d_sys::C2D_DrawText(
+                &self.raw,
+                flags,
+                point.x,
+                point.y,
+                point.z,
+                scale,
+                scale,
+                u32::from(color),
+            );
+        }
+    }
+}
+
+/// Horizontal alignment of drawn text, relative to the point it is drawn at.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// The text starts at the given point.
+    #[default]
+    #[doc(alias = "C2D_AlignLeft")]
+    Left = citro2d_sys::C2D_AlignLeft as u32,
+    /// The text ends at the given point.
+    #[doc(alias = "C2D_AlignRight")]
+    Right = citro2d_sys::C2D_AlignRight as u32,
+    /// The text is centered on the given point.
+    #[doc(alias = "C2D_AlignCenter")]
+    Center = citro2d_sys::C2D_AlignCenter as u32,
+}
+
+/// An outline drawn around text.
+#[derive(Debug, Clone, Copy)]
+pub struct Outline {
+    pub color: Color,
+    /// The width of the outline in pixels.
+    pub thickness: f32,
+}
+
+/// A drop shadow drawn behind text.
+#[derive(Debug, Clone, Copy)]
+pub struct Shadow {
+    pub color: Color,
+    /// The offset of the shadow from the text in pixels.
+    pub offset: (f32, f32),
+}
+
+/// Optional effects applied when drawing [`Text`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Effects {
+    pub outline: Option<Outline>,
+    pub shadow: Option<Shadow>,
+}
+
+/// Holds the information needed to draw a [`Text`] as a [`Shape`].
+pub struct Label {
+    pub text: Text,
+    pub point: Point,
+    pub scale: f32,
+    pub color: Color,
+    pub alignment: Alignment,
+    pub effects: Effects,
+}
+
+impl Shape for Label {
+    /// Draws the text
+    #[doc(alias = "C2D_DrawText")]
+    fn render(&self) -> bool {
+        self.text.draw_with_effects(
+            self.point,
+            self.scale,
+            self.color,
+            self.alignment,
+            &self.effects,
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replacement_glyph_is_not_missing() {
+        let alter_index = 10;
+        assert!(!is_missing_glyph(3, alter_index, 'a'));
+        assert!(is_missing_glyph(alter_index, alter_index, 'a'));
+        assert!(is_missing_glyph(-1, alter_index, 'a'));
+
+        // The replacement glyph is drawn for itself, so it isn't missing.
+        assert!(!is_missing_glyph(alter_index, alter_index, '?'));
+        assert!(!is_missing_glyph(
+            alter_index,
+            alter_index,
+            char::REPLACEMENT_CHARACTER
+        ));
+    }
+}
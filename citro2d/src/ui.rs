@@ -0,0 +1,401 @@
+//! A minimal immediate-mode GUI toolkit, built on the [`shapes`](crate::shapes)
+//! and [`text`](crate::text) modules. Useful for debug menus and settings screens.
+//!
+//! Widgets are drawn as soon as they are declared, so they must be declared
+//! while rendering to a [`Target`](crate::render::Target), usually the bottom
+//! screen. Touch input is read once per frame with [`Ui::begin`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use citro2d::ui::Ui;
+//! # fn example(ui: &mut Ui, volume: &mut f32, muted: &mut bool) {
+//! if ui.button("Reset") {
+//!     *volume = 0.5;
+//! }
+//! ui.horizontal(|ui| {
+//!     ui.slider("Volume", volume, 0.0..=1.0);
+//!     ui.checkbox("Mute", muted);
+//! });
+//! # }
+//! ```
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use ctru::services::hid::{Hid, KeyPad};
+
+use crate::render::Color;
+use crate::shapes::{RectangleSolid, Shape};
+use crate::text::{Alignment, Text};
+use crate::{Error, Point, Size};
+
+/// Colors and metrics used to draw widgets.
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    pub text_color: Color,
+    pub background: Color,
+    pub pressed: Color,
+    pub accent: Color,
+    pub text_scale: f32,
+    /// Space between a widget's border and its content.
+    pub padding: f32,
+    /// Space between consecutive widgets.
+    pub spacing: f32,
+    /// Minimum height of interactive widgets. 3DS touch screens are small, so
+    /// this should stay comfortably large enough to hit with a finger.
+    pub widget_height: f32,
+    pub slider_width: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            text_color: Color::new(255, 255, 255),
+            background: Color::new(64, 64, 72),
+            pressed: Color::new(96, 96, 112),
+            accent: Color::new(104, 176, 216),
+            text_scale: 0.5,
+            padding: 6.0,
+            spacing: 4.0,
+            widget_height: 24.0,
+            slider_width: 120.0,
+        }
+    }
+}
+
+/// An axis-aligned rectangle in screen coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub point: Point,
+    pub size: Size,
+}
+
+impl Rect {
+    pub const fn new(point: Point, size: Size) -> Self {
+        Self { point, size }
+    }
+
+    /// Whether the given screen position lies within the rectangle.
+    pub fn contains(&self, (x, y): (f32, f32)) -> bool {
+        x >= self.point.x
+            && x < self.point.x + self.size.width
+            && y >= self.point.y
+            && y < self.point.y + self.size.height
+    }
+
+    fn draw(&self, color: Color) {
+        RectangleSolid {
+            point: self.point,
+            size: self.size,
+            color,
+        }
+        .render();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Vertical,
+    Horizontal,
+}
+
+/// Immediate-mode UI state. Keep one of these around between frames, since it
+/// tracks touch input across frames and caches parsed widget text.
+///
+/// Only the text of widgets declared in the current or previous frame is kept,
+/// so labels which change every frame (e.g. counters or timers) don't pile up.
+pub struct Ui {
+    style: Style,
+    touch: Option<(f32, f32)>,
+    // A widget only reacts to a touch which started inside of it, so dragging
+    // a finger across the screen doesn't activate everything along the way.
+    press_origin: Option<(f32, f32)>,
+    released_at: Option<(f32, f32)>,
+    origin: Point,
+    cursor: Point,
+    direction: Direction,
+    row_height: f32,
+    // Parsed widget text for this frame, and the one before it to reuse from.
+    texts: HashMap<String, crate::Result<Text>>,
+    previous_texts: HashMap<String, crate::Result<Text>>,
+}
+
+impl Default for Ui {
+    fn default() -> Self {
+        Self::new(Style::default())
+    }
+}
+
+impl Ui {
+    /// Create a new UI which lays out widgets starting at the top-left of the screen.
+    pub fn new(style: Style) -> Self {
+        let origin = Point::new(style.padding, style.padding, 0.0);
+        Self {
+            style,
+            touch: None,
+            press_origin: None,
+            released_at: None,
+            origin,
+            cursor: origin,
+            direction: Direction::Vertical,
+            row_height: 0.0,
+            texts: HashMap::new(),
+            previous_texts: HashMap::new(),
+        }
+    }
+
+    /// The style used to draw widgets.
+    pub fn style_mut(&mut self) -> &mut Style {
+        &mut self.style
+    }
+
+    /// Start a new frame, reading the current touch state from `hid`. The
+    /// layout cursor is reset to the origin.
+    pub fn begin(&mut self, hid: &Hid) {
+        let touch = hid.keys_held().contains(KeyPad::TOUCH).then(|| {
+            let (x, y) = hid.touch_position();
+            (f32::from(x), f32::from(y))
+        });
+        self.begin_with_touch(touch);
+    }
+
+    /// Start a new frame with an explicit touch position, e.g. for input that
+    /// was remapped by the application.
+    pub fn begin_with_touch(&mut self, touch: Option<(f32, f32)>) {
+        match (self.touch, touch) {
+            (None, Some(pos)) => self.press_origin = Some(pos),
+            (None, None) => self.press_origin = None,
+            _ => {}
+        }
+        self.released_at = match (self.touch, touch) {
+            (Some(last), None) => Some(last),
+            _ => None,
+        };
+        self.touch = touch;
+
+        self.cursor = self.origin;
+        self.direction = Direction::Vertical;
+        self.row_height = 0.0;
+
+        // Text which wasn't used last frame is dropped here.
+        self.previous_texts = std::mem::take(&mut self.texts);
+    }
+
+    /// The labels which could not be parsed since the frame began, e.g.
+    /// because they contain a null byte, along with the reason. Widgets with
+    /// such a label are drawn without text.
+    pub fn text_errors(&self) -> impl Iterator<Item = (&str, &Error)> {
+        self.texts
+            .iter()
+            .filter_map(|(text, parsed)| Some((text.as_str(), parsed.as_ref().err()?)))
+    }
+
+    /// Move the layout origin. Subsequent widgets are laid out from this point.
+    pub fn set_origin(&mut self, origin: Point) {
+        self.origin = origin;
+        self.cursor = origin;
+    }
+
+    /// Add empty space before the next widget.
+    pub fn space(&mut self, amount: f32) {
+        match self.direction {
+            Direction::Vertical => self.cursor.y += amount,
+            Direction::Horizontal => self.cursor.x += amount,
+        }
+    }
+
+    /// Lay out the widgets declared in `f` in a row, instead of a column.
+    pub fn horizontal(&mut self, f: impl FnOnce(&mut Self)) {
+        let start = self.cursor;
+        let direction = self.direction;
+        let row_height = self.row_height;
+
+        self.direction = Direction::Horizontal;
+        self.row_height = 0.0;
+        f(self);
+
+        let size = Size::new(self.cursor.x - start.x, self.row_height);
+        self.direction = direction;
+        self.row_height = row_height;
+        self.cursor = start;
+        self.advance(size);
+    }
+
+    /// Reserve space for a widget of the given size, returning its bounds.
+    pub fn allocate(&mut self, size: Size) -> Rect {
+        let rect = Rect::new(self.cursor, size);
+        self.advance(size);
+        rect
+    }
+
+    fn advance(&mut self, size: Size) {
+        match self.direction {
+            Direction::Vertical => {
+                self.cursor.x = self.origin.x;
+                self.cursor.y += size.height + self.style.spacing;
+            }
+            Direction::Horizontal => {
+                self.cursor.x += size.width + self.style.spacing;
+                self.row_height = self.row_height.max(size.height);
+            }
+        }
+    }
+
+    fn pressed_in(&self, rect: &Rect) -> bool {
+        self.press_origin.is_some_and(|pos| rect.contains(pos))
+    }
+
+    fn held(&self, rect: &Rect) -> bool {
+        self.pressed_in(rect) && self.touch.is_some_and(|pos| rect.contains(pos))
+    }
+
+    fn clicked(&self, rect: &Rect) -> bool {
+        self.pressed_in(rect) && self.released_at.is_some_and(|pos| rect.contains(pos))
+    }
+
+    fn text_size(&mut self, text: &str) -> Size {
+        if !self.texts.contains_key(text) {
+            let parsed = self
+                .previous_texts
+                .remove(text)
+                .unwrap_or_else(|| Text::new(text));
+            self.texts.insert(text.to_owned(), parsed);
+        }
+
+        let scale = self.style.text_scale;
+        match &self.texts[text] {
+            Ok(parsed) => parsed.dimensions(scale, scale),
+            Err(_) => Size::new(0.0, 0.0),
+        }
+    }
+
+    fn draw_text(&self, text: &str, point: Point, alignment: Alignment) {
+        if let Some(Ok(parsed)) = self.texts.get(text) {
+            parsed.draw(
+                point,
+                self.style.text_scale,
+                self.style.text_color,
+                alignment,
+            );
+        }
+    }
+
+    /// Draw a line of text.
+    pub fn label(&mut self, text: &str) {
+        let text_size = self.text_size(text);
+        let rect = self.allocate(text_size);
+        self.draw_text(text, rect.point, Alignment::Left);
+    }
+
+    /// Draw a button, returning `true` if it was tapped this frame.
+    pub fn button(&mut self, text: &str) -> bool {
+        let text_size = self.text_size(text);
+        let padding = self.style.padding;
+        let rect = self.allocate(Size::new(
+            text_size.width + 2.0 * padding,
+            self.style
+                .widget_height
+                .max(text_size.height + 2.0 * padding),
+        ));
+
+        let color = if self.held(&rect) {
+            self.style.pressed
+        } else {
+            self.style.background
+        };
+        rect.draw(color);
+
+        let center = Point::new(
+            rect.point.x + rect.size.width / 2.0,
+            rect.point.y + (rect.size.height - text_size.height) / 2.0,
+            0.0,
+        );
+        self.draw_text(text, center, Alignment::Center);
+
+        self.clicked(&rect)
+    }
+
+    /// Draw a checkbox with a label, toggling `value` when tapped. Returns
+    /// `true` if the value changed this frame.
+    pub fn checkbox(&mut self, text: &str, value: &mut bool) -> bool {
+        let text_size = self.text_size(text);
+        let box_size = self.style.widget_height;
+        let rect = self.allocate(Size::new(
+            box_size + self.style.spacing + text_size.width,
+            box_size.max(text_size.height),
+        ));
+
+        let changed = self.clicked(&rect);
+        if changed {
+            *value = !*value;
+        }
+
+        let check = Rect::new(rect.point, Size::new(box_size, box_size));
+        check.draw(if self.held(&rect) {
+            self.style.pressed
+        } else {
+            self.style.background
+        });
+
+        if *value {
+            let inset = box_size / 4.0;
+            Rect::new(
+                Point::new(check.point.x + inset, check.point.y + inset, 0.0),
+                Size::new(box_size - 2.0 * inset, box_size - 2.0 * inset),
+            )
+            .draw(self.style.accent);
+        }
+
+        let label = Point::new(
+            rect.point.x + box_size + self.style.spacing,
+            rect.point.y + (rect.size.height - text_size.height) / 2.0,
+            0.0,
+        );
+        self.draw_text(text, label, Alignment::Left);
+
+        changed
+    }
+
+    /// Draw a horizontal slider with a label, updating `value` while it is
+    /// dragged. Returns `true` if the value changed this frame.
+    pub fn slider(&mut self, text: &str, value: &mut f32, range: RangeInclusive<f32>) -> bool {
+        let text_size = self.text_size(text);
+        let track_width = self.style.slider_width;
+        let height = self.style.widget_height;
+        let rect = self.allocate(Size::new(
+            track_width + self.style.spacing + text_size.width,
+            height.max(text_size.height),
+        ));
+        let track = Rect::new(rect.point, Size::new(track_width, height));
+
+        let (min, max) = (*range.start(), *range.end());
+        let mut changed = false;
+
+        // Keep tracking the finger once the drag has started, even if it
+        // leaves the track.
+        if let (true, Some((x, _))) = (self.pressed_in(&track), self.touch) {
+            let t = ((x - track.point.x) / track_width).clamp(0.0, 1.0);
+            let new_value = min + t * (max - min);
+            changed = new_value != *value;
+            *value = new_value;
+        }
+
+        track.draw(self.style.background);
+
+        let t = if max > min {
+            ((*value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        Rect::new(track.point, Size::new(track_width * t, height)).draw(self.style.accent);
+
+        let label = Point::new(
+            track.point.x + track_width + self.style.spacing,
+            rect.point.y + (rect.size.height - text_size.height) / 2.0,
+            0.0,
+        );
+        self.draw_text(text, label, Alignment::Left);
+
+        changed
+    }
+}
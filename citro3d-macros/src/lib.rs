@@ -11,6 +11,8 @@ use std::{env, process};
 use litrs::StringLit;
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Data, DeriveInput, Fields, Member, Meta, Token};
 
 /// Compiles the given PICA200 shader using [`picasso`](https://github.com/devkitPro/picasso)
 /// and returns the compiled bytes directly as a `&[u8]` slice.
@@ -170,3 +172,127 @@ fn include_shader_impl(input: TokenStream) -> Result<TokenStream, Box<dyn Error>
 
     Ok(result.into())
 }
+
+/// Derives `citro3d::attrib::Vertex` for a `#[repr(C)]` struct, registering one
+/// attribute per field (in declaration order, starting at register `v0`) with
+/// the format and element count of the field's type. The buffer layout (the
+/// stride and the order of the attributes in each vertex) follows the fields.
+///
+/// See `citro3d::attrib::Vertex` for an example.
+///
+/// # Errors
+///
+/// The derive will fail to compile if the type is not a `#[repr(C)]` struct
+/// with between 1 and 12 fields, is generic, or has padding between its
+/// fields. The type of each field must implement `citro3d::attrib::Attribute`.
+#[proc_macro_derive(Vertex)]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match derive_vertex_impl(&input) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn derive_vertex_impl(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`Vertex` can only be derived for structs",
+        ));
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`Vertex` cannot be derived for generic structs",
+        ));
+    }
+
+    let mut repr_c = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        repr_c |= metas.iter().any(|meta| meta.path().is_ident("C"));
+    }
+    if !repr_c {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`Vertex` requires `#[repr(C)]` so the field layout is known",
+        ));
+    }
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unnamed(fields) => &fields.unnamed,
+        Fields::Unit => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`Vertex` requires at least one field",
+            ));
+        }
+    };
+    if fields.is_empty() || fields.len() > 12 {
+        return Err(syn::Error::new_spanned(
+            &data.fields,
+            "`Vertex` requires between 1 and 12 fields (one per attribute)",
+        ));
+    }
+
+    let members: Vec<Member> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        })
+        .collect();
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let registers = 0..fields.len() as u16;
+    let attribute_count = fields.len() as u8;
+    // Each field is loaded into the attribute of the same index, 4 bits per field.
+    let permutation =
+        (0..fields.len() as u64).fold(0u64, |permutation, i| permutation | i << (i * 4));
+
+    let result = quote! {
+        // The GPU reads each attribute right after the previous one, so the
+        // struct must not have any padding between fields.
+        const _: () = {
+            let mut offset = 0;
+            #(
+                assert!(
+                    ::core::mem::offset_of!(#name, #members) == offset,
+                    "vertex fields must not have padding between them",
+                );
+                offset += ::core::mem::size_of::<#types>();
+            )*
+            let _ = offset;
+        };
+
+        impl ::citro3d::attrib::Vertex for #name {
+            const STRIDE: usize = ::core::mem::size_of::<#name>();
+            const ATTRIBUTE_COUNT: u8 = #attribute_count;
+            const PERMUTATION: u64 = #permutation;
+
+            fn attrib_info() -> ::citro3d::Result<::citro3d::attrib::Info> {
+                let mut info = ::citro3d::attrib::Info::new();
+                #(
+                    info.add_loader(
+                        ::citro3d::attrib::Register::new(#registers)?,
+                        <#types as ::citro3d::attrib::Attribute>::FORMAT,
+                        <#types as ::citro3d::attrib::Attribute>::COUNT,
+                    )?;
+                )*
+                Ok(info)
+            }
+        }
+    };
+
+    Ok(result.into())
+}
@@ -32,3 +32,8 @@ pub const fn GX_TRANSFER_OUT_FORMAT(format: GX_TRANSFER_FORMAT) -> u32 {
 pub const fn GX_TRANSFER_SCALING(scale: GX_TRANSFER_SCALE) -> u32 {
     (scale as u32) << 24
 }
+
+#[inline]
+pub const fn GX_BUFFER_DIM(w: u32, h: u32) -> u32 {
+    (h << 16) | (w & 0xFFFF)
+}
@@ -2,6 +2,8 @@
 
 #![feature(allocator_api)]
 
+use std::rc::Rc;
+
 use citro3d::macros::include_shader;
 use citro3d::math::{
     AspectRatio, ClipPlanes, CoordinateOrientation, FVec3, Matrix4, Projection, StereoDisplacement,
@@ -103,9 +105,9 @@ fn main() {
     let shader = shader::Library::from_bytes(SHADER_BYTES).unwrap();
     let vertex_shader = shader.get(0).unwrap();
 
-    let program = shader::Program::new(vertex_shader).unwrap();
+    let program = Rc::new(shader::Program::new(vertex_shader).unwrap());
     instance.bind_program(&program);
-    let mut vbo_data = Vec::with_capacity_in(VERTS.len(), ctru::linear::LinearAllocator);
+    let mut vbo_data = buffer::LinearBuffer::with_capacity(VERTS.len());
     for vert in VERTS.iter().enumerate().map(|(i, v)| Vertex {
         pos: Vec3 {
             x: v[0],
@@ -126,14 +128,6 @@ fn main() {
     let mut buf_info = buffer::Info::new();
     let vbo_slice = buf_info.add(&vbo_data, &attr_info).unwrap();
 
-    // Configure the first fragment shading substage to just pass through the vertex color
-    // See https://www.opengl.org/sdk/docs/man2/xhtml/glTexEnv.xml for more insight
-    let stage0 = texenv::Stage::new(0).unwrap();
-    instance
-        .texenv(stage0)
-        .src(texenv::Mode::BOTH, texenv::Source::PrimaryColor, None, None)
-        .func(texenv::Mode::BOTH, texenv::CombineFunc::Replace);
-
     let projection_uniform_idx = program.get_uniform("projection").unwrap();
     let camera_transform = Matrix4::looking_at(
         FVec3::new(1.8, 1.8, 1.8),
@@ -158,20 +152,26 @@ fn main() {
             break;
         }
 
-        instance.render_frame_with(|instance| {
+        instance.render_frame_with(|frame| {
+            // Configure the first fragment shading substage to just pass through the vertex color
+            // See https://www.opengl.org/sdk/docs/man2/xhtml/glTexEnv.xml for more insight
+            let stage0 = texenv::Stage::new(0).unwrap();
+            frame
+                .texenv(stage0)
+                .src(texenv::Mode::BOTH, texenv::Source::PrimaryColor, None, None)
+                .func(texenv::Mode::BOTH, texenv::CombineFunc::Replace);
+
             let mut render_to = |target: &mut render::Target, projection| {
                 target.clear(ClearFlags::ALL, CLEAR_COLOR, 0);
 
-                instance
+                frame
                     .select_render_target(target)
                     .expect("failed to set render target");
 
-                instance.bind_vertex_uniform(projection_uniform_idx, projection * camera_transform);
+                frame.bind_vertex_uniform(projection_uniform_idx, projection * camera_transform);
 
-                instance.set_attr_info(&attr_info);
-                unsafe {
-                    instance.draw_elements(buffer::Primitive::Triangles, vbo_slice, &index_buffer);
-                }
+                frame.set_attr_info(&attr_info);
+                frame.draw_elements(buffer::Primitive::Triangles, vbo_slice, &index_buffer);
             };
 
             let Projections {
@@ -215,7 +215,6 @@ fn calculate_projections() -> Projections {
     // TODO: it would be cool to allow playing around with these parameters on
     // the fly with D-pad, etc.
     let slider_val = ctru::os::current_3d_slider_state();
-    let interocular_distance = slider_val / 2.0;
 
     let vertical_fov = 40.0_f32.to_radians();
     let screen_depth = 2.0;
@@ -225,7 +224,7 @@ fn calculate_projections() -> Projections {
         far: 100.0,
     };
 
-    let (left, right) = StereoDisplacement::new(interocular_distance, screen_depth);
+    let (left, right) = StereoDisplacement::from_slider(slider_val, 0.5, screen_depth);
 
     let (left_eye, right_eye) =
         Projection::perspective(vertical_fov, AspectRatio::TopScreen, clip_planes)
@@ -1,5 +1,6 @@
 #![feature(allocator_api)]
 use std::f32::consts::PI;
+use std::rc::Rc;
 
 use citro3d::{
     attrib, buffer,
@@ -293,11 +294,10 @@ fn main() {
     let shader = shader::Library::from_bytes(SHADER_BYTES).unwrap();
     let vertex_shader = shader.get(0).unwrap();
 
-    let program = shader::Program::new(vertex_shader).unwrap();
+    let program = Rc::new(shader::Program::new(vertex_shader).unwrap());
     instance.bind_program(&program);
 
-    let mut vbo_data = Vec::with_capacity_in(VERTICES.len(), ctru::linear::LinearAllocator);
-    vbo_data.extend_from_slice(VERTICES);
+    let vbo_data = buffer::LinearBuffer::from(VERTICES);
     let mut buf_info = buffer::Info::new();
     let (attr_info, vbo_data) = prepare_vbos(&mut buf_info, &vbo_data);
 
@@ -351,18 +351,6 @@ fn main() {
     let mut view = Matrix4::identity();
     let model_idx = program.get_uniform("modelView").unwrap();
     view.translate(0.0, 0.0, -2.0);
-    instance.bind_vertex_uniform(model_idx, view);
-
-    let stage0 = texenv::Stage::new(0).unwrap();
-    instance
-        .texenv(stage0)
-        .src(
-            texenv::Mode::BOTH,
-            texenv::Source::FragmentPrimaryColor,
-            Some(texenv::Source::FragmentSecondaryColor),
-            None,
-        )
-        .func(texenv::Mode::BOTH, texenv::CombineFunc::Add);
 
     let projection_uniform_idx = program.get_uniform("projection").unwrap();
 
@@ -373,20 +361,31 @@ fn main() {
             break;
         }
 
-        instance.render_frame_with(|instance| {
+        instance.render_frame_with(|frame| {
+            let stage0 = texenv::Stage::new(0).unwrap();
+            frame
+                .texenv(stage0)
+                .src(
+                    texenv::Mode::BOTH,
+                    texenv::Source::FragmentPrimaryColor,
+                    Some(texenv::Source::FragmentSecondaryColor),
+                    None,
+                )
+                .func(texenv::Mode::BOTH, texenv::CombineFunc::Add);
+
             let mut render_to = |target: &mut render::Target, projection| {
                 target.clear(ClearFlags::ALL, 0, 0);
 
-                instance
+                frame
                     .select_render_target(target)
                     .expect("failed to set render target");
 
-                instance.bind_vertex_uniform(projection_uniform_idx, projection);
-                instance.bind_vertex_uniform(model_idx, view);
+                frame.bind_vertex_uniform(projection_uniform_idx, projection);
+                frame.bind_vertex_uniform(model_idx, view);
 
-                instance.set_attr_info(&attr_info);
+                frame.set_attr_info(&attr_info);
 
-                instance.draw_arrays(buffer::Primitive::Triangles, vbo_data);
+                frame.draw_arrays(buffer::Primitive::Triangles, vbo_data);
             };
 
             let Projections {
@@ -409,7 +408,7 @@ fn main() {
 
 fn prepare_vbos<'a>(
     buf_info: &'a mut buffer::Info,
-    vbo_data: &'a [Vertex],
+    vbo_data: &'a buffer::LinearBuffer<Vertex>,
 ) -> (attrib::Info, buffer::Slice<'a>) {
     // Configure attributes for use with the vertex shader
     let mut attr_info = attrib::Info::new();
@@ -443,7 +442,6 @@ struct Projections {
 
 fn calculate_projections() -> Projections {
     let slider_val = ctru::os::current_3d_slider_state();
-    let interocular_distance = slider_val / 2.0;
 
     let vertical_fov = 40.0_f32.to_radians();
     let screen_depth = 2.0;
@@ -453,7 +451,7 @@ fn calculate_projections() -> Projections {
         far: 100.0,
     };
 
-    let (left, right) = StereoDisplacement::new(interocular_distance, screen_depth);
+    let (left, right) = StereoDisplacement::from_slider(slider_val, 0.5, screen_depth);
 
     let (left_eye, right_eye) =
         Projection::perspective(vertical_fov, AspectRatio::TopScreen, clip_planes)
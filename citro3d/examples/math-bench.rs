@@ -0,0 +1,129 @@
+//! This example benchmarks the pure-Rust implementations of the hot matrix and
+//! vector operations in [`citro3d::math`] against the `citro3d` functions they
+//! replace, which are called through FFI (and, for `static inline` functions,
+//! through the wrappers generated by `bindgen`).
+//!
+//! Build it in release mode for meaningful numbers.
+
+use std::hint::black_box;
+use std::mem::MaybeUninit;
+use std::time::Instant;
+
+use citro3d::math::{FVec3, FVec4, Matrix4};
+use ctru::prelude::*;
+
+const ITERATIONS: u32 = 100_000;
+
+/// Print the average time per iteration of `rust` and `ffi`.
+fn bench(name: &str, mut rust: impl FnMut(), mut ffi: impl FnMut()) {
+    let time = |f: &mut dyn FnMut()| {
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            f();
+        }
+        start.elapsed().as_nanos() as f64 / f64::from(ITERATIONS)
+    };
+
+    let rust = time(&mut rust);
+    let ffi = time(&mut ffi);
+    println!("{name:<16} rust {rust:>7.1}ns  ffi {ffi:>7.1}ns");
+}
+
+fn main() {
+    let gfx = Gfx::new().expect("Couldn't obtain GFX controller");
+    let mut hid = Hid::new().expect("Couldn't obtain HID controller");
+    let apt = Apt::new().expect("Couldn't obtain APT controller");
+    let _console = Console::new(gfx.top_screen.borrow_mut());
+
+    let l = Matrix4::identity().rotated_y(1.0).translated(1.0, 2.0, 3.0);
+    let r = Matrix4::identity().rotated_x(-0.5).scaled(2.0, 2.0, 2.0);
+    let (v, w) = (FVec4::new(1.0, 2.0, 3.0, 4.0), FVec4::splat(0.5));
+    let (a, b) = (FVec3::new(1.0, 2.0, 3.0), FVec3::new(-3.0, 0.5, 2.0));
+
+    println!("{ITERATIONS} iterations each:\n");
+
+    bench(
+        "Mtx_Multiply",
+        || {
+            black_box(black_box(l) * black_box(r));
+        },
+        || {
+            let mut out = MaybeUninit::uninit();
+            unsafe {
+                citro3d_sys::Mtx_Multiply(
+                    out.as_mut_ptr(),
+                    black_box(&l).as_raw(),
+                    black_box(&r).as_raw(),
+                );
+            }
+            black_box(out);
+        },
+    );
+
+    bench(
+        "Mtx_Translate",
+        || {
+            black_box(black_box(l).translated(1.0, 2.0, 3.0));
+        },
+        || {
+            let mut m = black_box(l);
+            unsafe { citro3d_sys::Mtx_Translate(m.as_raw_mut(), 1.0, 2.0, 3.0, false) };
+            black_box(m);
+        },
+    );
+
+    bench(
+        "FVec4_Add",
+        || {
+            black_box(black_box(v) + black_box(w));
+        },
+        || {
+            let (v, w) = (black_box(v).into_raw(), black_box(w).into_raw());
+            black_box(unsafe { citro3d_sys::FVec4_Add(v, w) });
+        },
+    );
+
+    bench(
+        "FVec4_Dot",
+        || {
+            black_box(black_box(v).dot(black_box(w)));
+        },
+        || {
+            let (v, w) = (black_box(v).into_raw(), black_box(w).into_raw());
+            black_box(unsafe { citro3d_sys::FVec4_Dot(v, w) });
+        },
+    );
+
+    bench(
+        "FVec3_Cross",
+        || {
+            black_box(black_box(a).cross(black_box(b)));
+        },
+        || {
+            let (a, b) = (black_box(a).into_raw(), black_box(b).into_raw());
+            black_box(unsafe { citro3d_sys::FVec3_Cross(a, b) });
+        },
+    );
+
+    bench(
+        "FVec3_Normalize",
+        || {
+            black_box(black_box(a).normalize());
+        },
+        || {
+            let a = black_box(a).into_raw();
+            black_box(unsafe { citro3d_sys::FVec3_Normalize(a) });
+        },
+    );
+
+    println!("\nPress Start to exit");
+
+    while apt.main_loop() {
+        hid.scan_input();
+        if hid.keys_down().contains(KeyPad::START) {
+            break;
+        }
+
+        gfx.wait_for_vblank();
+    }
+}
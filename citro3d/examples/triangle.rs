@@ -3,47 +3,36 @@
 
 #![feature(allocator_api)]
 
+use std::rc::Rc;
+
+use citro3d::attrib::{self, Vertex as _};
 use citro3d::macros::include_shader;
 use citro3d::math::{AspectRatio, ClipPlanes, Matrix4, Projection, StereoDisplacement};
 use citro3d::render::ClearFlags;
 use citro3d::texenv;
-use citro3d::{attrib, buffer, render, shader};
+use citro3d::{buffer, render, shader};
 use ctru::prelude::*;
 use ctru::services::gfx::{RawFrameBuffer, Screen, TopScreen3D};
 
 #[repr(C)]
-#[derive(Copy, Clone)]
-struct Vec3 {
-    x: f32,
-    y: f32,
-    z: f32,
-}
-
-impl Vec3 {
-    const fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { x, y, z }
-    }
-}
-
-#[repr(C)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, attrib::Vertex)]
 struct Vertex {
-    pos: Vec3,
-    color: Vec3,
+    pos: [f32; 3],
+    color: [f32; 3],
 }
 
 static VERTICES: &[Vertex] = &[
     Vertex {
-        pos: Vec3::new(0.0, 0.5, -3.0),
-        color: Vec3::new(1.0, 0.0, 0.0),
+        pos: [0.0, 0.5, -3.0],
+        color: [1.0, 0.0, 0.0],
     },
     Vertex {
-        pos: Vec3::new(-0.5, -0.5, -3.0),
-        color: Vec3::new(0.0, 1.0, 0.0),
+        pos: [-0.5, -0.5, -3.0],
+        color: [0.0, 1.0, 0.0],
     },
     Vertex {
-        pos: Vec3::new(0.5, -0.5, -3.0),
-        color: Vec3::new(0.0, 0.0, 1.0),
+        pos: [0.5, -0.5, -3.0],
+        color: [0.0, 0.0, 1.0],
     },
 ];
 
@@ -84,23 +73,14 @@ fn main() {
     let shader = shader::Library::from_bytes(SHADER_BYTES).unwrap();
     let vertex_shader = shader.get(0).unwrap();
 
-    let program = shader::Program::new(vertex_shader).unwrap();
+    let program = Rc::new(shader::Program::new(vertex_shader).unwrap());
     instance.bind_program(&program);
 
-    let mut vbo_data = Vec::with_capacity_in(VERTICES.len(), ctru::linear::LinearAllocator);
-    vbo_data.extend_from_slice(VERTICES);
+    let vbo_data = buffer::LinearBuffer::from(VERTICES);
 
     let mut buf_info = buffer::Info::new();
     let (attr_info, vbo_data) = prepare_vbos(&mut buf_info, &vbo_data);
 
-    // Configure the first fragment shading substage to just pass through the vertex color
-    // See https://www.opengl.org/sdk/docs/man2/xhtml/glTexEnv.xml for more insight
-    let stage0 = texenv::Stage::new(0).unwrap();
-    instance
-        .texenv(stage0)
-        .src(texenv::Mode::BOTH, texenv::Source::PrimaryColor, None, None)
-        .func(texenv::Mode::BOTH, texenv::CombineFunc::Replace);
-
     let projection_uniform_idx = program.get_uniform("projection").unwrap();
 
     while apt.main_loop() {
@@ -110,19 +90,27 @@ fn main() {
             break;
         }
 
-        instance.render_frame_with(|instance| {
+        instance.render_frame_with(|frame| {
+            // Configure the first fragment shading substage to just pass through the vertex color
+            // See https://www.opengl.org/sdk/docs/man2/xhtml/glTexEnv.xml for more insight
+            let stage0 = texenv::Stage::new(0).unwrap();
+            frame
+                .texenv(stage0)
+                .src(texenv::Mode::BOTH, texenv::Source::PrimaryColor, None, None)
+                .func(texenv::Mode::BOTH, texenv::CombineFunc::Replace);
+
             let mut render_to = |target: &mut render::Target, projection| {
                 target.clear(ClearFlags::ALL, CLEAR_COLOR, 0);
 
-                instance
+                frame
                     .select_render_target(target)
                     .expect("failed to set render target");
 
-                instance.bind_vertex_uniform(projection_uniform_idx, projection);
+                frame.bind_vertex_uniform(projection_uniform_idx, projection);
 
-                instance.set_attr_info(&attr_info);
+                frame.set_attr_info(&attr_info);
 
-                instance.draw_arrays(buffer::Primitive::Triangles, vbo_data);
+                frame.draw_arrays(buffer::Primitive::Triangles, vbo_data);
             };
 
             let Projections {
@@ -140,23 +128,12 @@ fn main() {
 
 fn prepare_vbos<'a>(
     buf_info: &'a mut buffer::Info,
-    vbo_data: &'a [Vertex],
+    vbo_data: &'a buffer::LinearBuffer<Vertex>,
 ) -> (attrib::Info, buffer::Slice<'a>) {
-    // Configure attributes for use with the vertex shader
-    let mut attr_info = attrib::Info::new();
-
-    let reg0 = attrib::Register::new(0).unwrap();
-    let reg1 = attrib::Register::new(1).unwrap();
-
-    attr_info
-        .add_loader(reg0, attrib::Format::Float, 3)
-        .unwrap();
-
-    attr_info
-        .add_loader(reg1, attrib::Format::Float, 3)
-        .unwrap();
+    // Configure attributes for use with the vertex shader, one per field of `Vertex`
+    let attr_info = Vertex::attrib_info().unwrap();
 
-    let buf_idx = buf_info.add(vbo_data, &attr_info).unwrap();
+    let buf_idx = buf_info.add_vertices(vbo_data).unwrap();
 
     (attr_info, buf_idx)
 }
@@ -171,7 +148,6 @@ fn calculate_projections() -> Projections {
     // TODO: it would be cool to allow playing around with these parameters on
     // the fly with D-pad, etc.
     let slider_val = ctru::os::current_3d_slider_state();
-    let interocular_distance = slider_val / 2.0;
 
     let vertical_fov = 40.0_f32.to_radians();
     let screen_depth = 2.0;
@@ -181,7 +157,7 @@ fn calculate_projections() -> Projections {
         far: 100.0,
     };
 
-    let (left, right) = StereoDisplacement::new(interocular_distance, screen_depth);
+    let (left, right) = StereoDisplacement::from_slider(slider_val, 0.5, screen_depth);
 
     let (left_eye, right_eye) =
         Projection::perspective(vertical_fov, AspectRatio::TopScreen, clip_planes)
@@ -0,0 +1,287 @@
+//! Easing functions and tweening, for animating values over time.
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use crate::color::Color;
+use crate::math::{FQuat, FVec3, FVec4};
+
+/// Types which can be linearly interpolated.
+pub trait Lerp: Copy {
+    /// Interpolate between `self` (at `t = 0.0`) and `other` (at `t = 1.0`).
+    /// Values of `t` outside of `0.0..=1.0` extrapolate, which some easing
+    /// functions (e.g. [`Easing::BackOut`]) rely on.
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for FVec3 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for FVec4 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self::new(
+            self.r.lerp(other.r, t),
+            self.g.lerp(other.g, t),
+            self.b.lerp(other.b, t),
+        )
+    }
+}
+
+impl Lerp for FQuat {
+    /// Spherically interpolate between two rotations, taking the shortest path.
+    /// This is the same as [`FQuat::slerp`].
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.slerp(other, t)
+    }
+}
+
+/// Standard easing curves, mapping linear progress in `0.0..=1.0` to eased progress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    /// Overshoots slightly backwards before moving forwards.
+    BackIn,
+    /// Overshoots slightly past the end before settling.
+    BackOut,
+    BackInOut,
+    ElasticOut,
+    BounceOut,
+}
+
+impl Easing {
+    /// Apply the easing curve to `t`, which is clamped to `0.0..=1.0`.
+    pub fn apply(self, t: f32) -> f32 {
+        const BACK: f32 = 1.70158;
+
+        let t = t.clamp(0.0, 1.0);
+        let in_out = |f: fn(f32) -> f32| {
+            if t < 0.5 {
+                f(2.0 * t) / 2.0
+            } else {
+                1.0 - f(2.0 - 2.0 * t) / 2.0
+            }
+        };
+
+        match self {
+            Self::Linear => t,
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::QuadInOut => in_out(|t| t * t),
+            Self::CubicIn => t * t * t,
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut => in_out(|t| t * t * t),
+            Self::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Self::SineOut => (t * PI / 2.0).sin(),
+            Self::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+            Self::ExpoIn => expo_in(t),
+            Self::ExpoOut => 1.0 - expo_in(1.0 - t),
+            Self::ExpoInOut => in_out(expo_in),
+            Self::BackIn => back_in(t, BACK),
+            Self::BackOut => 1.0 - back_in(1.0 - t, BACK),
+            Self::BackInOut => in_out(|t| back_in(t, BACK * 1.525)),
+            Self::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                }
+            }
+            Self::BounceOut => bounce_out(t),
+        }
+    }
+}
+
+fn expo_in(t: f32) -> f32 {
+    if t == 0.0 {
+        0.0
+    } else {
+        2f32.powf(10.0 * t - 10.0)
+    }
+}
+
+fn back_in(t: f32, overshoot: f32) -> f32 {
+    (overshoot + 1.0) * t * t * t - overshoot * t * t
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// An animation of a value from one state to another over a fixed duration.
+#[derive(Clone, Copy, Debug)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: Duration,
+    // The time along the curve from `from` to `to`, which goes down while the
+    // tween is reversed.
+    elapsed: Duration,
+    easing: Easing,
+    reversed: bool,
+}
+
+impl<T: Lerp> Tween<T> {
+    /// Create a new tween, starting at `from`.
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: Duration::ZERO,
+            easing,
+            reversed: false,
+        }
+    }
+
+    /// Advance the tween by `delta` (usually the frame time), returning the new value.
+    pub fn advance(&mut self, delta: Duration) -> T {
+        self.elapsed = if self.reversed {
+            self.elapsed.saturating_sub(delta)
+        } else {
+            (self.elapsed + delta).min(self.duration)
+        };
+        self.value()
+    }
+
+    /// The current value of the tween.
+    pub fn value(&self) -> T {
+        self.from.lerp(self.to, self.easing.apply(self.position()))
+    }
+
+    /// The linear progress of the tween in the direction it is playing, from
+    /// `0.0` to `1.0`.
+    pub fn progress(&self) -> f32 {
+        if self.reversed {
+            1.0 - self.position()
+        } else {
+            self.position()
+        }
+    }
+
+    /// The linear position along the curve from `from` to `to`.
+    fn position(&self) -> f32 {
+        if self.duration.is_zero() {
+            1.0
+        } else {
+            self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+        }
+    }
+
+    /// Whether the tween has reached its end value (or its start value, while
+    /// reversed).
+    pub fn is_finished(&self) -> bool {
+        if self.reversed {
+            self.elapsed.is_zero()
+        } else {
+            self.elapsed >= self.duration
+        }
+    }
+
+    /// Restart the tween from the beginning, in the direction it is playing.
+    pub fn reset(&mut self) {
+        self.elapsed = if self.reversed {
+            self.duration
+        } else {
+            Duration::ZERO
+        };
+    }
+
+    /// Play the tween in the opposite direction from its current value, so
+    /// there is no visible jump. The value retraces the same curve backwards,
+    /// so e.g. an [`Easing::QuadIn`] tween starts fast and slows down towards
+    /// its start value.
+    pub fn reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::QuadInOut,
+            Easing::CubicOut,
+            Easing::SineInOut,
+            Easing::ExpoInOut,
+            Easing::BackInOut,
+            Easing::ElasticOut,
+            Easing::BounceOut,
+        ] {
+            assert_abs_diff_eq!(easing.apply(0.0), 0.0, epsilon = 1e-5);
+            assert_abs_diff_eq!(easing.apply(1.0), 1.0, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn tween_advances() {
+        let mut tween = Tween::new(0.0, 10.0, Duration::from_secs(2), Easing::Linear);
+        assert_abs_diff_eq!(tween.advance(Duration::from_secs(1)), 5.0);
+        assert!(!tween.is_finished());
+
+        assert_abs_diff_eq!(tween.advance(Duration::from_secs(5)), 10.0);
+        assert!(tween.is_finished());
+    }
+
+    #[test]
+    fn tween_reverses() {
+        let mut tween = Tween::new(0.0, 10.0, Duration::from_secs(2), Easing::QuadIn);
+        let value = tween.advance(Duration::from_millis(500));
+        assert_abs_diff_eq!(value, 0.625);
+
+        tween.reverse();
+        assert_abs_diff_eq!(tween.value(), value);
+        assert_abs_diff_eq!(tween.progress(), 0.75);
+        assert_abs_diff_eq!(tween.advance(Duration::from_millis(250)), 0.15625);
+
+        assert_abs_diff_eq!(tween.advance(Duration::from_secs(1)), 0.0);
+        assert!(tween.is_finished());
+    }
+}
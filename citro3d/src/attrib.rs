@@ -7,6 +7,8 @@
 
 use std::mem::MaybeUninit;
 
+pub use citro3d_macros::Vertex;
+
 /// Vertex attribute info. This struct describes how vertex buffers are
 /// layed out and used (i.e. the shape of the vertex data).
 #[derive(Debug)]
@@ -17,7 +19,7 @@ pub struct Info(pub(crate) citro3d_sys::C3D_AttrInfo);
 /// (e.g. position or color). These are called `v0`, `v1`, ... `v15` in the
 /// [picasso](https://github.com/devkitPro/picasso/blob/master/Manual.md)
 /// shader language.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Register(libc::c_int);
 
 impl Register {
@@ -33,18 +35,28 @@ impl Register {
             Err(crate::Error::TooManyAttributes)
         }
     }
+
+    /// Get the index of this register, i.e. `n` for `vn`.
+    pub fn index(&self) -> u16 {
+        self.0 as u16
+    }
 }
 
 /// An attribute index. This is the attribute's actual index in the input buffer,
 /// and may correspond to any [`Register`] (or multiple) as input in the shader
 /// program.
-#[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
-pub struct Index(u8);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Index(pub(crate) u8);
+
+impl From<Index> for u8 {
+    fn from(value: Index) -> Self {
+        value.0
+    }
+}
 
 /// The data format of an attribute.
 #[repr(u8)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[doc(alias = "GPU_FORMATS")]
 pub enum Format {
     /// A signed byte, i.e. [`i8`].
@@ -57,12 +69,140 @@ pub enum Format {
     Short = ctru_sys::GPU_SHORT,
 }
 
+/// A vertex type whose layout can be described with an [`Info`], and which can
+/// be registered as a vertex buffer with
+/// [`buffer::Info::add_vertices`](crate::buffer::Info::add_vertices). This is
+/// usually implemented with `#[derive(Vertex)]`, which registers one attribute
+/// per field, loaded into consecutive registers starting at `v0`.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use citro3d::attrib::{self, Vertex};
+///
+/// #[derive(Vertex, Clone, Copy)]
+/// #[repr(C)]
+/// struct MyVertex {
+///     pos: [f32; 3],
+///     uv: [f32; 2],
+///     color: [u8; 4],
+/// }
+///
+/// let attr_info: attrib::Info = MyVertex::attrib_info().unwrap();
+/// assert_eq!(attr_info.attr_count(), 3);
+/// assert_eq!(MyVertex::STRIDE, 24);
+/// ```
+pub trait Vertex {
+    /// The number of bytes between consecutive vertices in a vertex buffer.
+    const STRIDE: usize;
+
+    /// The number of attributes in each vertex, as registered by
+    /// [`attrib_info`](Self::attrib_info).
+    const ATTRIBUTE_COUNT: u8;
+
+    /// The order the attributes are laid out in each vertex, as passed to
+    /// `BufInfo_Add`: the index of the attribute for each component of the
+    /// vertex, 4 bits per component.
+    const PERMUTATION: u64;
+
+    /// Build attribute info describing the layout of this vertex type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attributes can't be registered, e.g. if there
+    /// are more than 12 of them.
+    fn attrib_info() -> crate::Result<Info>;
+}
+
+/// A type which can be used as a vertex attribute, i.e. up to 4 consecutive
+/// elements of the same [`Format`].
+///
+/// # Safety
+///
+/// The type must be laid out as exactly [`COUNT`](Self::COUNT) consecutive
+/// values of [`FORMAT`](Self::FORMAT), otherwise the GPU may read past the end
+/// of a vertex buffer.
+pub unsafe trait Attribute {
+    /// The data format of each element.
+    const FORMAT: Format;
+    /// The number of elements, from 1 to 4.
+    const COUNT: u8;
+}
+
+macro_rules! impl_attribute {
+    (@count $ty:ty, $format:ident, $count:literal) => {
+        unsafe impl Attribute for $ty {
+            const FORMAT: Format = Format::$format;
+            const COUNT: u8 = $count;
+        }
+    };
+    ($($ty:ty => $format:ident),* $(,)?) => {
+        $(
+            impl_attribute!(@count $ty, $format, 1);
+            impl_attribute!(@count [$ty; 1], $format, 1);
+            impl_attribute!(@count [$ty; 2], $format, 2);
+            impl_attribute!(@count [$ty; 3], $format, 3);
+            impl_attribute!(@count [$ty; 4], $format, 4);
+        )*
+    };
+}
+
+impl_attribute! {
+    f32 => Float,
+    u8 => UnsignedByte,
+    i8 => Byte,
+    i16 => Short,
+}
+
 impl From<Format> for u8 {
     fn from(value: Format) -> Self {
         value as u8
     }
 }
 
+impl TryFrom<u8> for Format {
+    type Error = String;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            ctru_sys::GPU_BYTE => Ok(Self::Byte),
+            ctru_sys::GPU_UNSIGNED_BYTE => Ok(Self::UnsignedByte),
+            ctru_sys::GPU_FLOAT => Ok(Self::Float),
+            ctru_sys::GPU_SHORT => Ok(Self::Short),
+            _ => Err("invalid value for Format".to_string()),
+        }
+    }
+}
+
+impl Format {
+    /// The size in bytes of a single element of this format.
+    pub fn size(self) -> usize {
+        match self {
+            Self::Byte | Self::UnsignedByte => 1,
+            Self::Short => 2,
+            Self::Float => 4,
+        }
+    }
+}
+
+/// A registered attribute loader, as described by [`Info::loaders`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Loader {
+    /// The shader input register the attribute is loaded into.
+    pub register: Register,
+    /// The data format of the attribute.
+    pub format: Format,
+    /// The number of elements in the attribute, from 1 to 4.
+    pub count: u8,
+}
+
+impl Loader {
+    /// The size in bytes of the attribute.
+    pub fn size(&self) -> usize {
+        self.format.size() * usize::from(self.count)
+    }
+}
+
 // SAFETY: the RWLock ensures unique access when mutating the global struct, and
 // we trust citro3d to Do The Right Thing™ and not mutate it otherwise.
 unsafe impl Sync for Info {}
@@ -142,4 +282,105 @@ impl Info {
     pub fn attr_count(&self) -> libc::c_int {
         self.0.attrCount
     }
+
+    /// Get the register, format and element count of each registered
+    /// attribute, in registration order (i.e. by [`Index`]). This is mostly
+    /// useful for debugging or displaying the current vertex layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use citro3d::attrib::{Format, Info, Loader, Register};
+    ///
+    /// let mut info = Info::new();
+    /// let position = Register::new(0).unwrap();
+    /// info.add_loader(position, Format::Float, 3).unwrap();
+    ///
+    /// let loaders: Vec<Loader> = info.loaders().collect();
+    /// assert_eq!(
+    ///     loaders,
+    ///     [Loader {
+    ///         register: position,
+    ///         format: Format::Float,
+    ///         count: 3
+    ///     }]
+    /// );
+    /// assert_eq!(loaders[0].size(), 12);
+    /// ```
+    pub fn loaders(&self) -> impl Iterator<Item = Loader> + '_ {
+        (0..self.attr_count() as usize).map(|i| {
+            // Each attribute has 4 bits of flags: the format, then the count - 1.
+            let flags = (self.0.flags[i / 8] >> ((i % 8) * 4)) as u8;
+            let register = (self.permutation() >> (i * 4)) & 0xF;
+            Loader {
+                register: Register(register as libc::c_int),
+                // Any 2-bit value is a valid format.
+                format: Format::try_from(flags & 0b11).unwrap(),
+                count: ((flags >> 2) & 0b11) + 1,
+            }
+        })
+    }
+
+    /// Get the input register each registered attribute is loaded into, in
+    /// registration order.
+    pub(crate) fn registers(&self) -> impl Iterator<Item = u8> + '_ {
+        self.loaders().map(|loader| loader.register.index() as u8)
+    }
+
+    /// Get the number of elements in each registered attribute, in
+    /// registration order.
+    pub(crate) fn element_counts(&self) -> impl Iterator<Item = u8> + '_ {
+        self.loaders().map(|loader| loader.count)
+    }
+
+    /// Get the size in bytes of each registered attribute, in registration order.
+    pub(crate) fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.loaders().map(|loader| loader.size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Vertex, Clone, Copy)]
+    #[repr(C)]
+    struct TestVertex {
+        pos: [f32; 3],
+        color: [u8; 4],
+        uv: [i16; 2],
+        weight: f32,
+    }
+
+    #[test]
+    fn derive_vertex() {
+        let info = TestVertex::attrib_info().unwrap();
+        assert_eq!(info.attr_count(), 4);
+        assert_eq!(info.element_counts().collect::<Vec<_>>(), [3, 4, 2, 1]);
+        assert_eq!(info.sizes().collect::<Vec<_>>(), [12, 4, 4, 4]);
+
+        let formats: Vec<_> = info.loaders().map(|loader| loader.format).collect();
+        assert_eq!(
+            formats,
+            [
+                Format::Float,
+                Format::UnsignedByte,
+                Format::Short,
+                Format::Float
+            ]
+        );
+        let registers: Vec<_> = info
+            .loaders()
+            .map(|loader| loader.register.index())
+            .collect();
+        assert_eq!(registers, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn derive_vertex_layout() {
+        assert_eq!(TestVertex::STRIDE, 24);
+        assert_eq!(TestVertex::ATTRIBUTE_COUNT, 4);
+        assert_eq!(TestVertex::PERMUTATION, 0x3210);
+    }
 }
@@ -3,18 +3,34 @@
 //! See the [`attrib`] module for details on how to describe the shape and type
 //! of the VBO data.
 
+use std::any::Any;
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
+use std::ops::{Bound, RangeBounds};
+use std::rc::Rc;
 
 use ctru::linear::LinearAllocator;
 
 use crate::Error;
 use crate::attrib;
 
+mod cache;
+mod dynamic;
+mod linear;
+
+pub use cache::{DEFAULT_CACHE_SIZE, average_cache_miss_ratio, optimize_vertex_cache};
+pub use dynamic::{DynamicAllocator, DynamicBuffer, DynamicSlice};
+pub use linear::LinearBuffer;
+
 /// Vertex buffer info. This struct is used to describe the shape of the buffer
 /// data to be sent to the GPU for rendering.
 #[derive(Debug)]
 #[doc(alias = "C3D_BufInfo")]
-pub struct Info(pub(crate) citro3d_sys::C3D_BufInfo);
+pub struct Info {
+    pub(crate) raw: citro3d_sys::C3D_BufInfo,
+    /// The number of vertices every registered buffer has, if known.
+    vertex_count: Option<libc::c_int>,
+}
 
 /// A slice of buffer data. This borrows the buffer data and can be thought of
 /// as similar to `&[T]` obtained by slicing a `Vec<T>`.
@@ -28,12 +44,60 @@ pub struct Slice<'buf> {
     // using the same backing data???
 }
 
-impl Slice<'_> {
-    /// Get the index into the buffer for this slice.
+impl<'buf> Slice<'buf> {
+    /// Get the index into the buffer for this slice, i.e. its first vertex.
     pub fn index(&self) -> libc::c_int {
         self.index
     }
 
+    /// Get a sub-range of this slice, e.g. to draw one section of a large
+    /// shared vertex buffer. The range is relative to the start of this slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{attrib, buffer};
+    /// # let attr_info = attrib::Info::new();
+    /// let vertices = buffer::LinearBuffer::from([0.0_f32; 12].as_slice());
+    /// let mut buf_info = buffer::Info::new();
+    /// let slice = buf_info.add(&vertices, &attr_info).unwrap();
+    ///
+    /// let second_half = slice.subslice(6..).unwrap();
+    /// assert_eq!((second_half.index(), second_half.len()), (6, 6));
+    /// assert!(slice.subslice(6..13).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if the range is not within this slice.
+    pub fn subslice(&self, range: impl RangeBounds<libc::c_int>) -> Result<Slice<'buf>, Error> {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+
+        if end > len {
+            return Err(Error::IndexOutOfBounds { idx: end, len });
+        }
+        if start < 0 || start > end {
+            return Err(Error::IndexOutOfBounds { idx: start, len });
+        }
+
+        Ok(Slice {
+            index: self.index + start,
+            size: end - start,
+            buf_info: self.buf_info,
+        })
+    }
+
     /// Get the length of the slice.
     #[must_use]
     pub fn len(&self) -> libc::c_int {
@@ -61,19 +125,7 @@ impl Slice<'_> {
     where
         I: Index + Copy + Into<libc::c_int>,
     {
-        if libc::c_int::try_from(indices.len()).is_err() {
-            return Err(Error::InvalidSize);
-        }
-
-        for &idx in indices {
-            let idx = idx.into();
-            let len = self.len();
-            if idx >= len {
-                return Err(Error::IndexOutOfBounds { idx, len });
-            }
-        }
-
-        Ok(unsafe { self.index_buffer_unchecked(indices) })
+        Indices::new(self, indices)
     }
 
     /// Get an index buffer for this slice using the given indices without
@@ -87,20 +139,233 @@ impl Slice<'_> {
         let mut buffer = Vec::with_capacity_in(indices.len(), LinearAllocator);
         buffer.extend_from_slice(indices);
         Indices {
-            buffer,
+            ptr: buffer.as_ptr(),
+            len: buffer.len(),
+            owner: Rc::new(buffer),
             _slice: *self,
         }
     }
+
+    fn check_indices<I>(&self, indices: &[I]) -> Result<(), Error>
+    where
+        I: Copy + Into<libc::c_int>,
+    {
+        if libc::c_int::try_from(indices.len()).is_err() {
+            return Err(Error::InvalidSize);
+        }
+
+        // Indices are relative to the start of the buffer, not the slice.
+        let valid = self.index..self.index + self.len();
+        for &idx in indices {
+            let idx = idx.into();
+            if !valid.contains(&idx) {
+                return Err(Error::IndexOutOfBounds {
+                    idx,
+                    len: valid.end,
+                });
+            }
+        }
+
+        Ok(())
+    }
 }
 
-/// An index buffer for indexed drawing. See [`Slice::index_buffer`] to obtain one.
+/// An index buffer for indexed drawing. See [`Slice::index_buffer`] or
+/// [`DynamicAllocator::index_buffer`] to obtain one.
+///
+/// The buffer is shared with any frame it is drawn in, so it stays alive until
+/// the GPU has finished using it.
 pub struct Indices<'buf, I> {
-    pub(crate) buffer: Vec<I, LinearAllocator>,
+    pub(crate) ptr: *const I,
+    pub(crate) len: usize,
+    /// The linear memory `ptr` points into.
+    pub(crate) owner: Rc<dyn Any>,
     _slice: Slice<'buf>,
 }
 
+impl<'buf, I> Indices<'buf, I>
+where
+    I: Index + Copy + Into<libc::c_int>,
+{
+    /// Copy `indices` into linear memory for drawing `slice`. This is the same
+    /// as [`Slice::index_buffer`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - any of the given indices are out of bounds.
+    /// - the given slice is too long for its length to fit in a `libc::c_int`.
+    pub fn new(slice: &Slice<'buf>, indices: &[I]) -> Result<Self, Error> {
+        slice.check_indices(indices)?;
+
+        Ok(unsafe { slice.index_buffer_unchecked(indices) })
+    }
+
+    /// Use indices which are already in linear memory for drawing `slice`,
+    /// without copying them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{attrib, buffer};
+    /// # let attr_info = attrib::Info::new();
+    /// let vertices = buffer::LinearBuffer::from([0.0_f32; 4].as_slice());
+    /// let mut buf_info = buffer::Info::new();
+    /// let slice = buf_info.add(&vertices, &attr_info).unwrap();
+    ///
+    /// let quad = buffer::LinearBuffer::from([0_u8, 1, 2, 2, 1, 3].as_slice());
+    /// let indices = buffer::Indices::from_buffer(&slice, quad).unwrap();
+    /// assert_eq!(indices.len(), 6);
+    ///
+    /// let out_of_bounds = buffer::LinearBuffer::from([0_u8, 1, 4].as_slice());
+    /// assert!(buffer::Indices::from_buffer(&slice, out_of_bounds).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - any of the given indices are out of bounds.
+    /// - the given slice is too long for its length to fit in a `libc::c_int`.
+    /// - the buffer can't be accessed by the GPU.
+    pub fn from_buffer(slice: &Slice<'buf>, indices: LinearBuffer<I>) -> Result<Self, Error> {
+        slice.check_indices(&indices)?;
+
+        let ptr = indices.as_ptr();
+        if !indices.is_empty() && unsafe { ctru_sys::osConvertVirtToPhys(ptr.cast()) } == 0 {
+            return Err(Error::InvalidMemoryLocation);
+        }
+
+        Ok(Self {
+            ptr,
+            len: indices.len(),
+            owner: Rc::new(indices),
+            _slice: *slice,
+        })
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+#[doc(cfg(feature = "bytemuck"))]
+impl<'buf, I> Indices<'buf, I>
+where
+    I: Index + bytemuck::Pod + Into<libc::c_int>,
+{
+    /// Copy the bytes of any [`Pod`](bytemuck::Pod) data into linear memory as
+    /// indices for drawing `slice`, e.g. a list of `[u16; 3]` triangles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{attrib, buffer};
+    /// # let attr_info = attrib::Info::new();
+    /// let vertices = buffer::LinearBuffer::from([0.0_f32; 4].as_slice());
+    /// let mut buf_info = buffer::Info::new();
+    /// let slice = buf_info.add(&vertices, &attr_info).unwrap();
+    ///
+    /// let triangles: [[u16; 3]; 2] = [[0, 1, 2], [2, 1, 3]];
+    /// let indices = buffer::Indices::<u16>::from_pod(&slice, &triangles).unwrap();
+    /// assert_eq!(indices.len(), 6);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - the size of `data` isn't a multiple of the size of `I`.
+    /// - any of the indices are out of bounds.
+    /// - the given slice is too long for its length to fit in a `libc::c_int`.
+    pub fn from_pod<P: bytemuck::Pod>(slice: &Slice<'buf>, data: &[P]) -> Result<Self, Error> {
+        Self::from_buffer(slice, LinearBuffer::from_pod(data)?)
+    }
+}
+
+impl<I> Indices<'_, I> {
+    /// Get the number of indices.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no indices.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// One of several vertex buffers making up the same vertices, e.g. positions
+/// in one buffer and colors in another. See [`Info::add_streams`].
+#[derive(Debug, Clone)]
+pub struct Stream<'vbo> {
+    data: *const libc::c_void,
+    stride: isize,
+    len: usize,
+    attributes: Vec<attrib::Index>,
+    _data: PhantomData<&'vbo ()>,
+}
+
+impl<'vbo> Stream<'vbo> {
+    /// Describe a vertex buffer holding the given attributes for each vertex,
+    /// in order. The attributes are those returned by
+    /// [`attrib::Info::add_loader`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TooManyAttributes`] if more than 12 attributes are
+    /// given, or [`Error::InvalidSize`] if none are.
+    pub fn new<D>(vbo_data: &'vbo D, attributes: &[attrib::Index]) -> Result<Self, Error>
+    where
+        D: VertexData + ?Sized,
+    {
+        if attributes.is_empty() {
+            return Err(Error::InvalidSize);
+        }
+        if attributes.len() > 12 {
+            return Err(Error::TooManyAttributes);
+        }
+
+        let vbo_data = vbo_data.data();
+        Ok(Self {
+            data: vbo_data.as_ptr().cast(),
+            stride: std::mem::size_of::<D::Vertex>().try_into()?,
+            len: vbo_data.len(),
+            attributes: attributes.to_vec(),
+            _data: PhantomData,
+        })
+    }
+
+    /// The order the attributes are loaded from the buffer, as used by `BufInfo_Add`.
+    fn permutation(&self) -> u64 {
+        self.attributes
+            .iter()
+            .enumerate()
+            .fold(0, |permutation, (i, attribute)| {
+                permutation | u64::from(attribute.0) << (i * 4)
+            })
+    }
+}
+
+/// Vertex data in linear memory, which can be registered with [`Info::add`].
+/// This is implemented for [`LinearBuffer`] and [`DynamicSlice`].
+pub trait VertexData: crate::private::Sealed {
+    /// The type of each vertex.
+    type Vertex;
+
+    /// Get the vertices as a slice.
+    fn data(&self) -> &[Self::Vertex];
+}
+
+impl<T> crate::private::Sealed for LinearBuffer<T> {}
+
+impl<T> VertexData for LinearBuffer<T> {
+    type Vertex = T;
+
+    fn data(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
 /// A type that can be used as an index for indexed drawing.
-pub trait Index: crate::private::Sealed {
+pub trait Index: crate::private::Sealed + 'static {
     /// The data type of the index, as used by [`citro3d_sys::C3D_DrawElements`]'s `type_` parameter.
     const TYPE: libc::c_int;
 }
@@ -115,7 +380,7 @@ impl Index for u16 {
 
 /// The geometric primitive to draw (i.e. what shapes the buffer data describes).
 #[repr(u16)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[doc(alias = "GPU_Primitive_t")]
 pub enum Primitive {
     /// Draw triangles (3 vertices per triangle).
@@ -126,9 +391,27 @@ pub enum Primitive {
     TriangleFan = ctru_sys::GPU_TRIANGLE_FAN,
     /// Geometry primitive. Can be used for more complex use cases like geometry
     /// shaders that output custom primitives.
+    ///
+    /// This must be used whenever the bound [`shader::Program`](crate::shader::Program)
+    /// has a [geometry shader](crate::shader::Program::set_geometry_shader).
+    /// The vertices are passed to the geometry shader in groups of the stride
+    /// it was set up with, and it decides which primitives to emit.
     GeometryPrim = ctru_sys::GPU_GEOMETRY_PRIM,
 }
 
+impl TryFrom<u16> for Primitive {
+    type Error = String;
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            ctru_sys::GPU_TRIANGLES => Ok(Self::Triangles),
+            ctru_sys::GPU_TRIANGLE_STRIP => Ok(Self::TriangleStrip),
+            ctru_sys::GPU_TRIANGLE_FAN => Ok(Self::TriangleFan),
+            ctru_sys::GPU_GEOMETRY_PRIM => Ok(Self::GeometryPrim),
+            _ => Err("invalid value for Primitive".to_string()),
+        }
+    }
+}
+
 impl Default for Info {
     #[doc(alias = "BufInfo_Init")]
     fn default() -> Self {
@@ -137,7 +420,10 @@ impl Default for Info {
             citro3d_sys::BufInfo_Init(info.as_mut_ptr());
             info.assume_init()
         };
-        Self(info)
+        Self {
+            raw: info,
+            vertex_count: None,
+        }
     }
 }
 
@@ -152,57 +438,274 @@ impl Info {
             None
         } else {
             // This is less efficient than returning a pointer or something, but it's
-            // safer since we don't know the lifetime of the pointee
-            Some(Self(unsafe { *raw }))
+            // safer since we don't know the lifetime of the pointee. The length
+            // of the buffers isn't part of the raw info, so it is unknown.
+            Some(Self {
+                raw: unsafe { *raw },
+                vertex_count: None,
+            })
         }
     }
 
+    /// Get the number of registered vertex buffers.
+    pub fn buffer_count(&self) -> usize {
+        self.raw.bufCount as usize
+    }
+
+    /// Get the number of vertices which can be drawn from the registered
+    /// buffers, i.e. the length of the shortest one. This is `None` if no
+    /// buffers are registered, or if the info was read back from `citro3d`
+    /// (e.g. with [`Instance::buffer_info`](crate::Instance::buffer_info)),
+    /// which doesn't record the length of the buffers.
+    pub fn vertex_count(&self) -> Option<libc::c_int> {
+        self.vertex_count
+    }
+
+    /// Record that buffers of `len` vertices were registered, and return a
+    /// slice over them.
+    fn registered(&mut self, len: usize) -> crate::Result<Slice<'_>> {
+        let len: libc::c_int = len.try_into()?;
+        self.vertex_count = Some(self.vertex_count.map_or(len, |count| count.min(len)));
+
+        Ok(Slice {
+            index: 0,
+            size: len,
+            buf_info: self,
+        })
+    }
+
+    /// Get the layout of each registered vertex buffer, in registration order.
+    /// This is mostly useful for debugging or displaying the current vertex
+    /// layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{attrib, buffer};
+    /// let mut attr_info = attrib::Info::new();
+    /// let position = attr_info
+    ///     .add_loader(attrib::Register::new(0).unwrap(), attrib::Format::Float, 3)
+    ///     .unwrap();
+    ///
+    /// let vertices = buffer::LinearBuffer::from([[0.0_f32; 3]; 3].as_slice());
+    /// let mut buf_info = buffer::Info::new();
+    /// buf_info.add(&vertices, &attr_info).unwrap();
+    ///
+    /// let config = buf_info.buffers().next().unwrap();
+    /// assert_eq!(buf_info.buffer_count(), 1);
+    /// assert_eq!(config.stride(), 12);
+    /// assert_eq!(config.attributes().collect::<Vec<_>>(), [position]);
+    /// ```
+    pub fn buffers(&self) -> impl Iterator<Item = BufferConfig> + '_ {
+        self.raw.buffers[..self.buffer_count()]
+            .iter()
+            .map(|&config| BufferConfig(config))
+    }
+
     /// Register vertex buffer object data. The resulting [`Slice`] will have its
     /// lifetime tied to both this [`Info`] and the passed-in VBO. `vbo_data` is
-    /// assumed to use one `T` per drawn primitive, and its layout is assumed to
-    /// match the given `attrib_info`
+    /// assumed to use one [`D::Vertex`](VertexData::Vertex) per drawn primitive,
+    /// and its layout is assumed to match the given `attrib_info`
     ///
-    /// # Errors
+    /// Since `vbo_data` is [`VertexData`] (e.g. a [`LinearBuffer`]), it is
+    /// always in memory the GPU can read.
     ///
-    /// Registering VBO data may fail:
+    /// # Errors
     ///
-    /// * if `vbo_data` is not allocated with the [`ctru::linear`] allocator
-    /// * if the maximum number (12) of VBOs are already registered
+    /// Registering VBO data may fail if the maximum number (12) of VBOs are
+    /// already registered.
     #[doc(alias = "BufInfo_Add")]
-    pub fn add<'this, 'vbo, 'idx, T>(
+    pub fn add<'this, 'vbo, 'idx, D>(
         &'this mut self,
-        vbo_data: &'vbo [T],
+        vbo_data: &'vbo D,
         attrib_info: &attrib::Info,
     ) -> crate::Result<Slice<'idx>>
     where
         'this: 'idx,
         'vbo: 'idx,
+        D: VertexData + ?Sized,
     {
-        let stride = std::mem::size_of::<T>().try_into()?;
+        let vbo_data = vbo_data.data();
+        let stride = std::mem::size_of::<D::Vertex>().try_into()?;
 
         // SAFETY: the lifetime of the VBO data is encapsulated in the return value's
-        // 'vbo lifetime, and the pointer to &mut self.0 is used to access values
+        // 'vbo lifetime, and the pointer to &mut self.raw is used to access values
         // in the BufInfo, not copied to be used later.
         let res = unsafe {
             citro3d_sys::BufInfo_Add(
-                &mut self.0,
+                &mut self.raw,
                 vbo_data.as_ptr().cast(),
                 stride,
                 attrib_info.attr_count(),
-                attrib_info.permutation(),
+                // The buffer holds every attribute, in the order they were added.
+                (0..attrib_info.attr_count() as u64)
+                    .fold(0, |permutation, i| permutation | i << (i * 4)),
+            )
+        };
+
+        check_buf_id(res)?;
+        self.registered(vbo_data.len())
+    }
+
+    /// Register a vertex buffer whose layout is described by its
+    /// [`attrib::Vertex`] implementation (usually `#[derive(Vertex)]`), using
+    /// the vertex type's stride and attribute order.
+    ///
+    /// # Errors
+    ///
+    /// Registering VBO data may fail if the maximum number (12) of VBOs are
+    /// already registered.
+    #[doc(alias = "BufInfo_Add")]
+    pub fn add_vertices<'this, 'vbo, 'idx, D>(
+        &'this mut self,
+        vbo_data: &'vbo D,
+    ) -> crate::Result<Slice<'idx>>
+    where
+        'this: 'idx,
+        'vbo: 'idx,
+        D: VertexData + ?Sized,
+        D::Vertex: attrib::Vertex,
+    {
+        let vbo_data = vbo_data.data();
+
+        // SAFETY: as in `add`.
+        let res = unsafe {
+            citro3d_sys::BufInfo_Add(
+                &mut self.raw,
+                vbo_data.as_ptr().cast(),
+                <D::Vertex as attrib::Vertex>::STRIDE.try_into()?,
+                <D::Vertex as attrib::Vertex>::ATTRIBUTE_COUNT.into(),
+                <D::Vertex as attrib::Vertex>::PERMUTATION,
             )
         };
 
-        // Error codes from <https://github.com/devkitPro/citro3d/blob/master/source/buffers.c#L11>
-        match res {
-            ..=-3 => Err(crate::Error::System(res)),
-            -2 => Err(crate::Error::InvalidMemoryLocation),
-            -1 => Err(crate::Error::TooManyBuffers),
-            _ => Ok(Slice {
-                index: res,
-                size: vbo_data.len().try_into()?,
-                buf_info: self,
-            }),
+        check_buf_id(res)?;
+        self.registered(vbo_data.len())
+    }
+
+    /// Register several vertex buffers which together make up the same
+    /// vertices, e.g. positions in one buffer and colors in another. The
+    /// resulting [`Slice`] draws from all of them, and its length is that of
+    /// the shortest stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use citro3d::attrib;
+    /// use citro3d::buffer::{self, LinearBuffer, Stream};
+    ///
+    /// let mut attr_info = attrib::Info::new();
+    /// let position = attr_info
+    ///     .add_loader(attrib::Register::new(0).unwrap(), attrib::Format::Float, 3)
+    ///     .unwrap();
+    /// let color = attr_info
+    ///     .add_loader(
+    ///         attrib::Register::new(1).unwrap(),
+    ///         attrib::Format::UnsignedByte,
+    ///         4,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let positions =
+    ///     LinearBuffer::from([[0.0_f32, 0.5, 0.0], [-0.5, -0.5, 0.0], [0.5, -0.5, 0.0]].as_slice());
+    /// let colors =
+    ///     LinearBuffer::from([[255_u8, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]].as_slice());
+    ///
+    /// let mut buf_info = buffer::Info::new();
+    /// let slice = buf_info
+    ///     .add_streams(&[
+    ///         Stream::new(&positions, &[position]).unwrap(),
+    ///         Stream::new(&colors, &[color]).unwrap(),
+    ///     ])
+    ///     .unwrap();
+    /// assert_eq!(slice.len(), 3);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TooManyBuffers`] if registering the streams would
+    /// exceed the maximum number (12) of VBOs, in which case nothing is
+    /// registered, or [`Error::InvalidSize`] if no streams are given.
+    #[doc(alias = "BufInfo_Add")]
+    pub fn add_streams<'this, 'vbo, 'idx>(
+        &'this mut self,
+        streams: &[Stream<'vbo>],
+    ) -> crate::Result<Slice<'idx>>
+    where
+        'this: 'idx,
+        'vbo: 'idx,
+    {
+        if streams.is_empty() {
+            return Err(Error::InvalidSize);
+        }
+        if self.raw.bufCount as usize + streams.len() > 12 {
+            return Err(Error::TooManyBuffers);
         }
+
+        for stream in streams {
+            // SAFETY: the lifetime of the stream data is encapsulated in the
+            // return value's 'vbo lifetime, as in `add`.
+            let res = unsafe {
+                citro3d_sys::BufInfo_Add(
+                    &mut self.raw,
+                    stream.data,
+                    stream.stride,
+                    stream.attributes.len() as libc::c_int,
+                    stream.permutation(),
+                )
+            };
+            check_buf_id(res)?;
+        }
+
+        let len = streams.iter().map(|stream| stream.len).min().unwrap_or(0);
+        self.registered(len)
+    }
+}
+
+/// The layout of a single vertex buffer registered in an [`Info`], as
+/// described by [`Info::buffers`].
+#[derive(Clone, Copy)]
+#[doc(alias = "C3D_BufCfg")]
+pub struct BufferConfig(citro3d_sys::C3D_BufCfg);
+
+impl BufferConfig {
+    /// The number of bytes between consecutive vertices in the buffer.
+    pub fn stride(&self) -> u16 {
+        ((self.0.flags[1] >> 16) & 0xFFF) as u16
+    }
+
+    /// The attributes loaded from each vertex in the buffer, in the order they
+    /// are laid out.
+    pub fn attributes(&self) -> impl Iterator<Item = attrib::Index> + use<> {
+        let permutation = u64::from(self.0.flags[0]) | u64::from(self.0.flags[1] & 0xFFFF) << 32;
+        let count = self.0.flags[1] >> 28;
+
+        (0..count)
+            .map(move |component| ((permutation >> (component * 4)) & 0xF) as u8)
+            // Components from 12 up are padding rather than attributes.
+            .filter(|&attribute| attribute < 12)
+            .map(attrib::Index)
+    }
+}
+
+impl std::fmt::Debug for BufferConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferConfig")
+            .field("stride", &self.stride())
+            .field("attributes", &self.attributes().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Check the buffer ID returned by `BufInfo_Add` for errors.
+fn check_buf_id(res: libc::c_int) -> crate::Result<()> {
+    // Error codes from <https://github.com/devkitPro/citro3d/blob/master/source/buffers.c#L11>
+    match res {
+        ..=-3 => Err(crate::Error::System(res)),
+        -2 => Err(crate::Error::InvalidMemoryLocation),
+        -1 => Err(crate::Error::TooManyBuffers),
+        _ => Ok(()),
     }
 }
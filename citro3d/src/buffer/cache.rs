@@ -0,0 +1,247 @@
+//! Triangle reordering for the GPU's post-transform vertex cache.
+
+use std::collections::VecDeque;
+
+use super::Index;
+use crate::Error;
+
+/// A reasonable cache size to optimize for when the exact size of the vertex
+/// cache is unknown. Orderings optimized for a slightly wrong size still
+/// perform well.
+pub const DEFAULT_CACHE_SIZE: usize = 16;
+
+/// Reorder the triangles of an indexed [`Triangles`](super::Primitive::Triangles)
+/// list so vertices are reused while they are still in the post-transform
+/// vertex cache, reducing the number of times each vertex is shaded.
+///
+/// This uses the "Tipsify" algorithm from Sander et al., *Fast
+/// Triangle Reordering for Vertex Locality and Reduced Overdraw* (2007),
+/// which runs in linear time. Only the order of triangles changes, and the
+/// vertices of each triangle keep their winding order.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidSize`] if the length of `indices` is not a
+/// multiple of 3.
+pub fn optimize_vertex_cache<I>(indices: &mut [I], cache_size: usize) -> Result<(), Error>
+where
+    I: Index + Copy + Into<usize>,
+{
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::InvalidSize);
+    }
+
+    let triangle_count = indices.len() / 3;
+    let vertex_count = indices.iter().map(|&i| i.into() + 1).max().unwrap_or(0);
+    let vertex_of = |n: usize| indices[n].into();
+
+    // Triangles using each vertex, stored contiguously per vertex.
+    let mut live = vec![0usize; vertex_count];
+    for n in 0..indices.len() {
+        live[vertex_of(n)] += 1;
+    }
+    let mut offsets = Vec::with_capacity(vertex_count + 1);
+    offsets.push(0);
+    for &count in &live {
+        offsets.push(offsets.last().unwrap() + count);
+    }
+    let mut adjacency = vec![0; indices.len()];
+    let mut fill = offsets.clone();
+    for n in 0..indices.len() {
+        let vertex = vertex_of(n);
+        adjacency[fill[vertex]] = n / 3;
+        fill[vertex] += 1;
+    }
+
+    let mut cache_time = vec![0; vertex_count];
+    let mut emitted = vec![false; triangle_count];
+    let mut order = Vec::with_capacity(triangle_count);
+    let mut dead_end = Vec::new();
+    let mut candidates = Vec::new();
+    let mut timestamp = cache_size + 1;
+    let mut cursor = 0;
+
+    let mut fanning = next_live(&live, &mut dead_end, &mut cursor);
+    while let Some(vertex) = fanning {
+        candidates.clear();
+
+        for &triangle in &adjacency[offsets[vertex]..offsets[vertex + 1]] {
+            if emitted[triangle] {
+                continue;
+            }
+
+            for n in triangle * 3..triangle * 3 + 3 {
+                let v = vertex_of(n);
+                dead_end.push(v);
+                candidates.push(v);
+                live[v] -= 1;
+
+                if timestamp - cache_time[v] > cache_size {
+                    cache_time[v] = timestamp;
+                    timestamp += 1;
+                }
+            }
+
+            emitted[triangle] = true;
+            order.push(triangle);
+        }
+
+        // Prefer the candidate which entered the cache earliest, as long as
+        // all of its remaining triangles can be emitted before it is evicted.
+        // If none of them fit, this is a dead end and fanning continues from
+        // the dead-end stack instead.
+        let mut best = None;
+        let mut best_priority = 0;
+        for &v in &candidates {
+            if live[v] == 0 {
+                continue;
+            }
+
+            let age = timestamp - cache_time[v];
+            let priority = if age + 2 * live[v] <= cache_size {
+                age
+            } else {
+                0
+            };
+
+            if priority > best_priority {
+                best = Some(v);
+                best_priority = priority;
+            }
+        }
+
+        fanning = best.or_else(|| next_live(&live, &mut dead_end, &mut cursor));
+    }
+
+    let reordered: Vec<I> = order
+        .iter()
+        .flat_map(|&triangle| &indices[triangle * 3..triangle * 3 + 3])
+        .copied()
+        .collect();
+    indices.copy_from_slice(&reordered);
+
+    Ok(())
+}
+
+/// Find a vertex which still has triangles left to emit, preferring recently
+/// used vertices and falling back to the next one in input order.
+fn next_live(live: &[usize], dead_end: &mut Vec<usize>, cursor: &mut usize) -> Option<usize> {
+    while let Some(v) = dead_end.pop() {
+        if live[v] > 0 {
+            return Some(v);
+        }
+    }
+
+    while *cursor < live.len() {
+        if live[*cursor] > 0 {
+            return Some(*cursor);
+        }
+        *cursor += 1;
+    }
+
+    None
+}
+
+/// Measure the average cache miss ratio (ACMR) of an indexed triangle list, i.e.
+/// the average number of vertices which need to be shaded per triangle, by
+/// simulating a FIFO vertex cache of the given size.
+///
+/// The result ranges from `3.0` (no reuse at all) down to about `0.5` for
+/// large, well-optimized meshes. Returns `0.0` if there are no triangles.
+pub fn average_cache_miss_ratio<I>(indices: &[I], cache_size: usize) -> f32
+where
+    I: Index + Copy + Into<usize>,
+{
+    let triangle_count = indices.len() / 3;
+    if triangle_count == 0 {
+        return 0.0;
+    }
+
+    let mut cache = VecDeque::with_capacity(cache_size);
+    let mut misses = 0;
+
+    for &index in &indices[..triangle_count * 3] {
+        let vertex: usize = index.into();
+        if cache.contains(&vertex) {
+            continue;
+        }
+
+        misses += 1;
+        if cache.len() == cache_size {
+            cache.pop_front();
+        }
+        if cache_size > 0 {
+            cache.push_back(vertex);
+        }
+    }
+
+    misses as f32 / triangle_count as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An `n` by `n` grid of quads, with triangles in a cache-unfriendly order.
+    fn grid(n: u16) -> Vec<u16> {
+        let mut indices = Vec::new();
+        for half in 0..2 {
+            for y in 0..n {
+                for x in 0..n {
+                    let corner = y * (n + 1) + x;
+                    if half == 0 {
+                        indices.extend([corner, corner + 1, corner + n + 1]);
+                    } else {
+                        indices.extend([corner + 1, corner + n + 2, corner + n + 1]);
+                    }
+                }
+            }
+        }
+        indices
+    }
+
+    fn sorted_triangles(indices: &[u16]) -> Vec<[u16; 3]> {
+        let mut triangles: Vec<_> = indices
+            .chunks(3)
+            .map(|t| {
+                // Rotate so the smallest index is first, keeping the winding.
+                let min = (0..3).min_by_key(|&i| t[i]).unwrap();
+                [t[min], t[(min + 1) % 3], t[(min + 2) % 3]]
+            })
+            .collect();
+        triangles.sort_unstable();
+        triangles
+    }
+
+    #[test]
+    fn optimize_improves_acmr() {
+        let original = grid(16);
+        let mut optimized = original.clone();
+        optimize_vertex_cache(&mut optimized, DEFAULT_CACHE_SIZE).unwrap();
+
+        assert_eq!(sorted_triangles(&original), sorted_triangles(&optimized));
+        assert!(
+            average_cache_miss_ratio(&optimized, DEFAULT_CACHE_SIZE)
+                < average_cache_miss_ratio(&original, DEFAULT_CACHE_SIZE)
+        );
+    }
+
+    #[test]
+    fn optimize_skips_dead_ends() {
+        // With a cache this small, most candidates would be evicted before
+        // their triangles are emitted, so fanning often restarts from the
+        // dead-end stack.
+        let mut indices = grid(16);
+        optimize_vertex_cache(&mut indices, 4).unwrap();
+        assert!(average_cache_miss_ratio(&indices, 4) < 1.2);
+    }
+
+    #[test]
+    fn optimize_rejects_partial_triangles() {
+        let mut indices = [0u8, 1, 2, 3];
+        assert!(matches!(
+            optimize_vertex_cache(&mut indices, DEFAULT_CACHE_SIZE),
+            Err(Error::InvalidSize)
+        ));
+    }
+}
@@ -0,0 +1,265 @@
+//! Per-frame arena for streaming vertex and index data.
+
+use std::alloc::{Allocator, Layout};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+use super::linear::GpuAllocator;
+use super::{Index, Indices, Slice, VertexData};
+use crate::render::Frame;
+use crate::{Error, Result};
+
+/// The minimum alignment of each allocation, which is enough for any vertex or
+/// index data.
+const MIN_ALIGNMENT: usize = 16;
+
+/// A block of linear memory, freed once neither the buffer nor a frame in
+/// flight is using it.
+struct Memory {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl Drop for Memory {
+    fn drop(&mut self) {
+        unsafe { GpuAllocator.deallocate(self.ptr, self.layout) }
+    }
+}
+
+/// A fixed-size arena of linear memory for vertex and index data which only
+/// needs to live for a single frame, such as UI or debug geometry that is
+/// regenerated every frame.
+///
+/// Allocations are made through a [`DynamicAllocator`], obtained with
+/// [`for_frame`](Self::for_frame). The memory is recycled once the GPU has
+/// finished with it (i.e. when the next frame begins), so streaming geometry
+/// doesn't allocate every frame.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::{attrib, buffer, render};
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// # let attr_info = attrib::Info::new();
+/// let mut dynamic = buffer::DynamicBuffer::new(16 * 1024).unwrap();
+///
+/// let mut frame = instance.begin_frame(render::FrameFlags::SYNC_DRAW).unwrap();
+/// let alloc = dynamic.for_frame(&mut frame);
+///
+/// let vertices = alloc
+///     .alloc(&[[0.0_f32, 0.5], [-0.5, -0.5], [0.5, -0.5]])
+///     .unwrap();
+/// let mut buf_info = buffer::Info::new();
+/// let slice = buf_info.add(&vertices, &attr_info).unwrap();
+/// let indices = alloc.index_buffer(&slice, &[0_u8, 1, 2]).unwrap();
+///
+/// // Draw with `frame.draw_elements(buffer::Primitive::Triangles, slice, &indices)`
+/// ```
+pub struct DynamicBuffer {
+    memory: Rc<Memory>,
+    offset: Cell<usize>,
+}
+
+impl DynamicBuffer {
+    /// Allocate a buffer of `capacity` bytes in linear memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfMemory`] if there isn't enough linear memory, or
+    /// [`Error::InvalidSize`] if `capacity` is too large.
+    pub fn new(capacity: usize) -> Result<Self> {
+        let layout =
+            Layout::from_size_align(capacity, MIN_ALIGNMENT).map_err(|_| Error::InvalidSize)?;
+        let ptr = GpuAllocator
+            .allocate(layout)
+            .map_err(|_| Error::OutOfMemory)?;
+
+        Ok(Self {
+            memory: Rc::new(Memory {
+                ptr: ptr.cast(),
+                layout,
+            }),
+            offset: Cell::new(0),
+        })
+    }
+
+    /// The size of the buffer in bytes.
+    pub fn capacity(&self) -> usize {
+        self.memory.layout.size()
+    }
+
+    /// The number of bytes allocated so far this frame.
+    pub fn used(&self) -> usize {
+        self.offset.get()
+    }
+
+    /// Start allocating from the buffer for the given frame.
+    ///
+    /// If the buffer isn't in use by a frame on the GPU, its previous
+    /// allocations are recycled first. Otherwise (e.g. if this is called twice
+    /// in the same frame), new allocations follow the existing ones.
+    pub fn for_frame(&mut self, frame: &mut Frame<'_>) -> DynamicAllocator<'_> {
+        // The frame keeps a reference to the memory until the GPU is done with
+        // it, so if this is the only one left nothing can still be reading it.
+        if Rc::strong_count(&self.memory) == 1 {
+            self.offset.set(0);
+        }
+        frame.keep_alive(self.memory.clone());
+
+        DynamicAllocator { buffer: self }
+    }
+}
+
+impl std::fmt::Debug for DynamicBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicBuffer")
+            .field("capacity", &self.capacity())
+            .field("used", &self.used())
+            .finish()
+    }
+}
+
+/// Allocates vertex and index data from a [`DynamicBuffer`] for the current
+/// frame. See [`DynamicBuffer::for_frame`] to obtain one.
+#[derive(Debug)]
+pub struct DynamicAllocator<'buf> {
+    buffer: &'buf DynamicBuffer,
+}
+
+impl<'buf> DynamicAllocator<'buf> {
+    /// Copy `data` into the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfMemory`] if there isn't enough room left in the
+    /// buffer this frame.
+    pub fn alloc<T: Copy>(&self, data: &[T]) -> Result<DynamicSlice<'buf, T>> {
+        let ptr = self.reserve::<T>(data.len())?;
+
+        unsafe {
+            ptr.copy_from_nonoverlapping(NonNull::from(data).cast(), data.len());
+        }
+
+        Ok(DynamicSlice {
+            ptr,
+            len: data.len(),
+            _buffer: PhantomData,
+        })
+    }
+
+    /// Get an index buffer for `slice` using the given indices, copying them
+    /// into the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - any of the given indices are out of bounds.
+    /// - the given slice is too long for its length to fit in a `libc::c_int`.
+    /// - there isn't enough room left in the buffer this frame.
+    pub fn index_buffer<'idx, I>(
+        &self,
+        slice: &Slice<'idx>,
+        indices: &[I],
+    ) -> Result<Indices<'idx, I>>
+    where
+        'buf: 'idx,
+        I: Index + Copy + Into<libc::c_int>,
+    {
+        slice.check_indices(indices)?;
+        let indices = self.alloc(indices)?;
+
+        Ok(Indices {
+            ptr: indices.ptr.as_ptr(),
+            len: indices.len,
+            owner: self.buffer.memory.clone(),
+            _slice: *slice,
+        })
+    }
+
+    /// The number of bytes left in the buffer this frame.
+    pub fn remaining(&self) -> usize {
+        self.buffer.capacity() - self.buffer.used()
+    }
+
+    fn reserve<T>(&self, len: usize) -> Result<NonNull<T>> {
+        let layout = Layout::array::<T>(len).map_err(|_| Error::InvalidSize)?;
+        let start = self
+            .buffer
+            .used()
+            .next_multiple_of(layout.align().max(MIN_ALIGNMENT));
+        let end = start
+            .checked_add(layout.size())
+            .filter(|&end| end <= self.buffer.capacity())
+            .ok_or(Error::OutOfMemory)?;
+
+        self.buffer.offset.set(end);
+        Ok(unsafe { self.buffer.memory.ptr.add(start).cast() })
+    }
+}
+
+/// Vertex or index data allocated from a [`DynamicBuffer`] for the current
+/// frame. This dereferences to `[T]`, and can be registered with
+/// [`Info::add`](super::Info::add).
+pub struct DynamicSlice<'buf, T> {
+    ptr: NonNull<T>,
+    len: usize,
+    _buffer: PhantomData<&'buf [T]>,
+}
+
+impl<T> Deref for DynamicSlice<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { NonNull::slice_from_raw_parts(self.ptr, self.len).as_ref() }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for DynamicSlice<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> crate::private::Sealed for DynamicSlice<'_, T> {}
+
+impl<T> VertexData for DynamicSlice<'_, T> {
+    type Vertex = T;
+
+    fn data(&self) -> &[T] {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Instance;
+    use crate::render::FrameFlags;
+
+    #[test]
+    fn recycled_each_frame() {
+        let mut instance = Instance::new().unwrap();
+        let mut buffer = DynamicBuffer::new(64).unwrap();
+
+        {
+            let mut frame = instance.begin_frame(FrameFlags::SYNC_DRAW).unwrap();
+            let alloc = buffer.for_frame(&mut frame);
+
+            let a = alloc.alloc(&[1_u8, 2, 3]).unwrap();
+            let b = alloc.alloc(&[4_u16, 5]).unwrap();
+            assert_eq!(*a, [1, 2, 3]);
+            assert_eq!(*b, [4, 5]);
+            assert!(b.as_ptr().addr().is_multiple_of(MIN_ALIGNMENT));
+            assert!(matches!(alloc.alloc(&[0_u8; 64]), Err(Error::OutOfMemory)));
+        }
+        assert_eq!(buffer.used(), MIN_ALIGNMENT + 4);
+
+        let mut frame = instance.begin_frame(FrameFlags::SYNC_DRAW).unwrap();
+        let alloc = buffer.for_frame(&mut frame);
+        assert_eq!(alloc.remaining(), 64);
+    }
+}
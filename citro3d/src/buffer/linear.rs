@@ -0,0 +1,244 @@
+//! Growable buffers in linear memory, which the GPU can read directly.
+
+use std::alloc::{AllocError, Allocator, Layout};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+use ctru::linear::LinearAllocator;
+
+#[cfg(feature = "bytemuck")]
+use crate::{Error, Result};
+
+/// The alignment of every [`LinearBuffer`] allocation. This matches the
+/// default alignment of `linearAlloc`, which satisfies all GPU buffers.
+const ALIGNMENT: usize = 0x80;
+
+/// [`LinearAllocator`], but always aligning allocations for the GPU.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct GpuAllocator;
+
+unsafe impl Allocator for GpuAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let layout = layout.align_to(ALIGNMENT).map_err(|_| AllocError)?;
+        LinearAllocator.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // `allocate` already succeeded with this layout, so it can't fail here.
+        let layout = layout.align_to(ALIGNMENT).unwrap();
+        unsafe { LinearAllocator.deallocate(ptr, layout) }
+    }
+}
+
+/// A growable buffer of `T`, like `Vec<T>`, which is always allocated in
+/// linear memory and aligned for the GPU. This is the type of vertex data
+/// accepted by [`Info::add`](super::Info::add).
+///
+/// The buffer dereferences to `[T]`, so it can be indexed and iterated like a
+/// slice.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use citro3d::buffer::LinearBuffer;
+///
+/// let mut vertices = LinearBuffer::from([[0.0_f32, 0.5], [-0.5, -0.5]].as_slice());
+/// vertices.push([0.5, -0.5]);
+///
+/// vertices.as_mut_slice()[0] = [0.0, 1.0];
+/// assert_eq!(vertices.as_slice(), [[0.0, 1.0], [-0.5, -0.5], [0.5, -0.5]]);
+/// ```
+#[derive(Clone)]
+pub struct LinearBuffer<T>(Vec<T, GpuAllocator>);
+
+impl<T> LinearBuffer<T> {
+    /// Create an empty buffer. This does not allocate until elements are added.
+    pub fn new() -> Self {
+        Self(Vec::new_in(GpuAllocator))
+    }
+
+    /// Create an empty buffer with room for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity_in(capacity, GpuAllocator))
+    }
+
+    /// Append an element to the end of the buffer.
+    pub fn push(&mut self, value: T) {
+        self.0.push(value);
+    }
+
+    /// Remove all elements from the buffer, keeping its allocation.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Reserve room for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
+    /// The number of elements the buffer can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Get the contents of the buffer as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Get the contents of the buffer as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T: Clone> LinearBuffer<T> {
+    /// Append all elements of `values` to the end of the buffer.
+    pub fn extend_from_slice(&mut self, values: &[T]) {
+        self.0.extend_from_slice(values);
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+#[doc(cfg(feature = "bytemuck"))]
+impl<T: bytemuck::Pod> LinearBuffer<T> {
+    /// Copy the bytes of any [`Pod`](bytemuck::Pod) data into a new buffer of
+    /// `T`, e.g. to upload your own vertex structs as `[f32; N]` or packed
+    /// indices as `u16`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use citro3d::buffer::LinearBuffer;
+    ///
+    /// #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+    /// #[repr(C)]
+    /// struct Vertex {
+    ///     pos: [f32; 3],
+    ///     color: [f32; 3],
+    /// }
+    ///
+    /// let triangle = [Vertex {
+    ///     pos: [0.0, 0.5, 0.5],
+    ///     color: [1.0, 0.0, 0.0],
+    /// }];
+    /// let vertices = LinearBuffer::<Vertex>::from_pod(&triangle).unwrap();
+    /// assert_eq!(
+    ///     vertices.as_bytes(),
+    ///     bytemuck::cast_slice::<_, u8>(&triangle)
+    /// );
+    ///
+    /// let faces = [[0_u16, 1, 2], [2, 1, 3]];
+    /// let indices = LinearBuffer::<u16>::from_pod(&faces).unwrap();
+    /// assert_eq!(indices.as_slice(), [0, 1, 2, 2, 1, 3]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if the size of `data` isn't a multiple of
+    /// the size of `T`.
+    pub fn from_pod<U: bytemuck::Pod>(data: &[U]) -> Result<Self> {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let size = size_of::<T>();
+        if size == 0 || !bytes.len().is_multiple_of(size) {
+            return Err(Error::InvalidSize);
+        }
+
+        let mut buffer = Self::new();
+        buffer.0.resize(bytes.len() / size, T::zeroed());
+        bytemuck::cast_slice_mut(buffer.as_mut_slice()).copy_from_slice(bytes);
+        Ok(buffer)
+    }
+
+    /// Get the contents of the buffer as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(self.as_slice())
+    }
+}
+
+impl<T> Default for LinearBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for LinearBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for LinearBuffer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Clone> From<&[T]> for LinearBuffer<T> {
+    fn from(values: &[T]) -> Self {
+        let mut buffer = Self::with_capacity(values.len());
+        buffer.extend_from_slice(values);
+        buffer
+    }
+}
+
+impl<T> FromIterator<T> for LinearBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut buffer = Self::new();
+        buffer.extend(iter);
+        buffer
+    }
+}
+
+impl<T> Extend<T> for LinearBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinearBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_in_linear_memory() {
+        let buffer: LinearBuffer<u8> = (0..3).collect();
+        let ptr = buffer.as_ptr();
+
+        assert_eq!(buffer.as_slice(), [0, 1, 2]);
+        assert!(ptr.addr().is_multiple_of(ALIGNMENT));
+        assert_ne!(unsafe { ctru_sys::osConvertVirtToPhys(ptr.cast()) }, 0);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn from_pod() {
+        let buffer = LinearBuffer::<[u8; 2]>::from_pod(&[0x0102_u16, 0x0304]).unwrap();
+        assert_eq!(
+            buffer.as_bytes(),
+            bytemuck::cast_slice::<_, u8>(&[0x0102_u16, 0x0304])
+        );
+        assert_eq!(buffer.len(), 2);
+
+        assert!(matches!(
+            LinearBuffer::<u32>::from_pod(&[0_u16; 3]),
+            Err(Error::InvalidSize)
+        ));
+        assert!(matches!(
+            LinearBuffer::<()>::from_pod(&[0_u8]),
+            Err(Error::InvalidSize)
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 //! Color manipulation module.
 
+use crate::math::{FVec4, IVec};
+
 /// RGB color in linear space ([0, 1]).
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Color {
@@ -22,4 +24,42 @@ impl Color {
     pub fn to_parts_bgr(self) -> [f32; 3] {
         [self.b, self.g, self.r]
     }
+
+    /// Packs the color into a 32-bit RGBA value with full alpha, as used e.g.
+    /// for clearing render targets. Components are clamped to [0, 1].
+    pub fn to_rgba8(self) -> u32 {
+        let [r, g, b] = self
+            .to_parts_rgb()
+            .map(|c| (c.clamp(0.0, 1.0) * 255.0) as u32);
+        (r << 24) | (g << 16) | (b << 8) | 0xFF
+    }
+}
+
+impl From<Color> for FVec4 {
+    /// Convert the color to an RGBA vector with full alpha.
+    fn from(color: Color) -> Self {
+        FVec4::new(color.r, color.g, color.b, 1.0)
+    }
+}
+
+impl From<FVec4> for Color {
+    /// Convert an RGBA vector to a color, ignoring its alpha.
+    fn from(v: FVec4) -> Self {
+        Self::new(v.x(), v.y(), v.z())
+    }
+}
+
+impl From<Color> for IVec {
+    /// Pack the color into RGBA bytes with full alpha, e.g. for per-vertex
+    /// colors. Components are clamped to [0, 1].
+    fn from(color: Color) -> Self {
+        IVec::from_normalized(color.into())
+    }
+}
+
+impl From<IVec> for Color {
+    /// Unpack a color from RGBA bytes, ignoring its alpha.
+    fn from(v: IVec) -> Self {
+        v.to_normalized().into()
+    }
 }
@@ -0,0 +1,125 @@
+//! Write raw GPU register commands, for hardware features the safe API doesn't
+//! wrap yet. See [`Instance::raw_commands`](crate::Instance::raw_commands).
+
+use crate::texenv;
+
+/// The number of GPU registers which can be written with commands.
+const REGISTER_COUNT: u16 = 0x400;
+
+/// The most parameters a single command can carry.
+const MAX_PARAMS: usize = 256;
+
+/// A handle for writing raw commands into the current GPU command buffer.
+/// Only obtainable within [`Instance::raw_commands`](crate::Instance::raw_commands).
+#[doc(alias = "GPUCMD_Add")]
+pub struct RawCommands {
+    _private: (),
+}
+
+impl RawCommands {
+    pub(crate) fn new() -> Self {
+        Self { _private: () }
+    }
+
+    /// Write `value` to `register`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `register` is not a valid register (`0x000..0x400`).
+    #[doc(alias = "GPUCMD_AddWrite")]
+    pub fn write(&mut self, register: u16, value: u32) {
+        self.write_masked(register, 0xF, value);
+    }
+
+    /// Write `value` to `register`, only changing the bytes selected by the
+    /// low 4 bits of `mask` (bit 0 is the least significant byte).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `register` is not a valid register (`0x000..0x400`).
+    #[doc(alias = "GPUCMD_AddMaskedWrite")]
+    pub fn write_masked(&mut self, register: u16, mask: u8, value: u32) {
+        self.add(false, register, mask, &[value]);
+    }
+
+    /// Write each of `values` to `register` in turn, e.g. to upload data
+    /// through a register which acts as a port.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `register` is not a valid register (`0x000..0x400`), or if
+    /// `values` is empty or has more than 256 elements.
+    #[doc(alias = "GPUCMD_AddWrites")]
+    pub fn write_repeated(&mut self, register: u16, values: &[u32]) {
+        self.add(false, register, 0xF, values);
+    }
+
+    /// Write `values` to consecutive registers starting at `register`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the registers written are not valid (`0x000..0x400`),
+    /// or if `values` is empty or has more than 256 elements.
+    #[doc(alias = "GPUCMD_AddIncrementalWrites")]
+    pub fn write_incremental(&mut self, register: u16, values: &[u32]) {
+        assert!(
+            usize::from(register) + values.len() <= usize::from(REGISTER_COUNT),
+            "incremental write past the last GPU register"
+        );
+        self.add(true, register, 0xF, values);
+    }
+
+    fn add(&mut self, incremental: bool, register: u16, mask: u8, values: &[u32]) {
+        assert!(
+            register < REGISTER_COUNT,
+            "invalid GPU register {register:#x}"
+        );
+        assert!(
+            (1..=MAX_PARAMS).contains(&values.len()),
+            "a GPU command must have between 1 and {MAX_PARAMS} parameters"
+        );
+
+        let header =
+            (u32::from(incremental) << 31) | (u32::from(mask & 0xF) << 16) | u32::from(register);
+
+        // The length was checked above, so it fits in a u32.
+        unsafe { ctru_sys::GPUCMD_Add(header, values.as_ptr(), values.len() as u32) };
+    }
+}
+
+bitflags::bitflags! {
+    /// State tracked by `citro3d` which raw commands may have overwritten, and
+    /// which should be sent to the GPU again before the next draw call.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Invalidate: u8 {
+        /// The texture combiner stages.
+        const TEXENV = 1 << 0;
+        /// The vertex attribute configuration.
+        const ATTRIBUTES = 1 << 1;
+        /// The vertex buffer configuration.
+        const BUFFERS = 1 << 2;
+    }
+}
+
+impl Invalidate {
+    /// Mark the selected state as dirty, so `citro3d` writes it again.
+    #[doc(alias = "C3D_GetTexEnv")]
+    #[doc(alias = "C3D_SetAttrInfo")]
+    #[doc(alias = "C3D_SetBufInfo")]
+    pub(crate) fn apply(self) {
+        unsafe {
+            if self.contains(Self::TEXENV) {
+                // Getting a stage for mutation is what marks it as dirty.
+                for stage in 0..texenv::TEXENV_COUNT {
+                    citro3d_sys::C3D_GetTexEnv(stage as _);
+                }
+            }
+            if self.contains(Self::ATTRIBUTES) {
+                citro3d_sys::C3D_SetAttrInfo(citro3d_sys::C3D_GetAttrInfo());
+            }
+            if self.contains(Self::BUFFERS) {
+                citro3d_sys::C3D_SetBufInfo(citro3d_sys::C3D_GetBufInfo());
+            }
+        }
+    }
+}
@@ -0,0 +1,520 @@
+//! A simplified API for small projects, which sets up everything needed to
+//! start drawing in a couple of calls.
+//!
+//! An [`App`] owns the [`Instance`], a render target for each screen, and a
+//! built-in shader which draws colored or textured vertices. Each frame,
+//! geometry is added to a [`Canvas`] per screen, and drawn using the canvas's
+//! [`Camera`]. The full API remains available through [`App::instance`] for
+//! anything this module doesn't cover.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # let _runner = test_runner::GdbRunner::default();
+//! use citro3d::easy::prelude::*;
+//! use ctru::prelude::*;
+//!
+//! let gfx = Gfx::new().unwrap();
+//! let mut hid = Hid::new().unwrap();
+//! let apt = Apt::new().unwrap();
+//!
+//! let mut app = App::new(&gfx).unwrap();
+//!
+//! while apt.main_loop() {
+//!     hid.scan_input();
+//!
+//!     app.frame(|top, bottom| {
+//!         top.triangle(
+//!             Vertex::new([200.0, 40.0, 0.0], Color::new(1.0, 0.0, 0.0)),
+//!             Vertex::new([120.0, 200.0, 0.0], Color::new(0.0, 1.0, 0.0)),
+//!             Vertex::new([280.0, 200.0, 0.0], Color::new(0.0, 0.0, 1.0)),
+//!         );
+//!         bottom.rect(10.0, 10.0, 100.0, 50.0, Color::new(1.0, 1.0, 1.0));
+//!     });
+//! #   break;
+//! }
+//! ```
+
+use std::cell::RefMut;
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+use ctru::services::gfx::{Gfx, RawFrameBuffer, Screen};
+
+use crate::buffer::LinearBuffer;
+use crate::color::Color;
+use crate::macros::include_shader;
+use crate::math::{AspectRatio, ClipPlanes, CoordinateOrientation, FVec3, Matrix4, Projection};
+use crate::render::effect::{TestFunction, WriteMask};
+use crate::render::{ClearFlags, DepthFormat, Frame, Target};
+use crate::texture::{self, Texture};
+use crate::{Error, Instance, Result, attrib, buffer, shader, texenv, uniform};
+
+pub mod prelude {
+    //! Everything needed to get started with the [`easy`](super) module.
+    pub use super::{App, Camera, Canvas, Vertex};
+    pub use crate::color::Color;
+    pub use crate::math::FVec3;
+}
+
+static SHADER_BYTES: &[u8] = include_shader!("easy/shader.pica");
+
+/// A vertex drawn by a [`Canvas`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex {
+    /// The position of the vertex, interpreted by the canvas's [`Camera`].
+    pub position: [f32; 3],
+    /// The RGBA color of the vertex, which is interpolated across triangles.
+    /// For textured triangles, this tints the texture.
+    pub color: [f32; 4],
+    /// The texture coordinates of the vertex, with `(0, 0)` at the bottom-left
+    /// of the texture and `(1, 1)` at the top-right. Only used by textured
+    /// triangles.
+    pub uv: [f32; 2],
+}
+
+impl Vertex {
+    /// Create a new, fully opaque vertex.
+    pub fn new(position: [f32; 3], color: Color) -> Self {
+        Self::with_alpha(position, color, 1.0)
+    }
+
+    /// Create a new vertex with the given opacity, from `0.0` to `1.0`.
+    pub fn with_alpha(position: [f32; 3], color: Color, alpha: f32) -> Self {
+        Self {
+            position,
+            color: [color.r, color.g, color.b, alpha],
+            uv: [0.0, 0.0],
+        }
+    }
+
+    /// Create a new vertex for a textured triangle, which shows the texture
+    /// as-is at the texture coordinates `uv`.
+    pub fn textured(position: [f32; 3], uv: [f32; 2]) -> Self {
+        Self {
+            uv,
+            ..Self::new(position, Color::new(1.0, 1.0, 1.0))
+        }
+    }
+}
+
+/// How vertex positions drawn to a [`Canvas`] are mapped to the screen.
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    kind: CameraKind,
+    view: Matrix4,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CameraKind {
+    Screen,
+    Perspective { vertical_fov_radians: f32 },
+}
+
+impl Camera {
+    /// A 2D camera using screen pixels, with `(0, 0)` at the top-left and +Y
+    /// pointing down. Vertices are drawn in order without depth testing, and
+    /// Z must be between `-1.0` and `1.0`. This is the default camera.
+    pub fn screen() -> Self {
+        Self {
+            kind: CameraKind::Screen,
+            view: Matrix4::identity(),
+        }
+    }
+
+    /// A 3D perspective camera, initially at the origin looking towards -Z.
+    /// Vertices are depth tested, and anything from `0.1` to `100.0` units
+    /// away from the camera is visible.
+    pub fn perspective(vertical_fov_radians: f32) -> Self {
+        Self {
+            kind: CameraKind::Perspective {
+                vertical_fov_radians,
+            },
+            view: Matrix4::identity(),
+        }
+    }
+
+    /// Move the camera to `position`, looking at `target` with +Y up.
+    #[must_use]
+    pub fn looking_at(mut self, position: FVec3, target: FVec3) -> Self {
+        self.view = Matrix4::looking_at(
+            position,
+            target,
+            FVec3::new(0.0, 1.0, 0.0),
+            CoordinateOrientation::RightHanded,
+        );
+        self
+    }
+
+    fn matrix(&self, (width, height): (f32, f32), aspect_ratio: AspectRatio) -> Matrix4 {
+        let projection: Matrix4 = match self.kind {
+            CameraKind::Screen => Projection::orthographic(
+                0.0..width,
+                height..0.0,
+                ClipPlanes {
+                    near: -1.0,
+                    far: 1.0,
+                },
+            )
+            .into(),
+            CameraKind::Perspective {
+                vertical_fov_radians,
+            } => Projection::perspective(
+                vertical_fov_radians,
+                aspect_ratio,
+                ClipPlanes {
+                    near: 0.1,
+                    far: 100.0,
+                },
+            )
+            .into(),
+        };
+
+        projection * self.view
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::screen()
+    }
+}
+
+/// Geometry to draw on one of the screens during a frame.
+/// See [`App::frame`].
+pub struct Canvas {
+    vertices: LinearBuffer<Vertex>,
+    // The first vertex drawn with each texture, in order.
+    batches: Vec<(usize, Option<Rc<Texture>>)>,
+    camera: Camera,
+    clear_color: Color,
+    size: (f32, f32),
+    aspect_ratio: AspectRatio,
+}
+
+impl Canvas {
+    fn new(size: (f32, f32), aspect_ratio: AspectRatio) -> Self {
+        Self {
+            vertices: LinearBuffer::new(),
+            batches: Vec::new(),
+            camera: Camera::default(),
+            clear_color: Color::default(),
+            size,
+            aspect_ratio,
+        }
+    }
+
+    /// The width of the screen, in pixels.
+    pub fn width(&self) -> f32 {
+        self.size.0
+    }
+
+    /// The height of the screen, in pixels.
+    pub fn height(&self) -> f32 {
+        self.size.1
+    }
+
+    /// Set the camera used to draw this canvas. This persists across frames.
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
+
+    /// Set the color the screen is cleared to before drawing. This persists
+    /// across frames, and defaults to black.
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.clear_color = color;
+    }
+
+    /// Draw a single triangle.
+    pub fn triangle(&mut self, a: Vertex, b: Vertex, c: Vertex) {
+        self.push(None, &[a, b, c]);
+    }
+
+    /// Draw a list of triangles, three vertices at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `vertices` is not a multiple of 3.
+    pub fn triangles(&mut self, vertices: &[Vertex]) {
+        self.push(None, vertices);
+    }
+
+    /// Draw a single triangle with a texture, which is multiplied by the
+    /// vertex colors. See [`Vertex::textured`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::rc::Rc;
+    /// # use citro3d::easy::prelude::*;
+    /// # use citro3d::texture::{ColorFormat, Texture};
+    /// # let gfx = ctru::services::gfx::Gfx::new().unwrap();
+    /// # let mut app = App::new(&gfx).unwrap();
+    /// let texture = Rc::new(Texture::new(64, 64, ColorFormat::Rgba8).unwrap());
+    ///
+    /// app.frame(|top, _bottom| {
+    ///     top.textured_triangle(
+    ///         &texture,
+    ///         Vertex::textured([200.0, 40.0, 0.0], [0.5, 1.0]),
+    ///         Vertex::textured([120.0, 200.0, 0.0], [0.0, 0.0]),
+    ///         Vertex::textured([280.0, 200.0, 0.0], [1.0, 0.0]),
+    ///     );
+    /// });
+    /// ```
+    pub fn textured_triangle(&mut self, texture: &Rc<Texture>, a: Vertex, b: Vertex, c: Vertex) {
+        self.push(Some(texture), &[a, b, c]);
+    }
+
+    /// Draw a list of triangles with a texture, three vertices at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `vertices` is not a multiple of 3.
+    pub fn textured_triangles(&mut self, texture: &Rc<Texture>, vertices: &[Vertex]) {
+        self.push(Some(texture), vertices);
+    }
+
+    fn push(&mut self, texture: Option<&Rc<Texture>>, vertices: &[Vertex]) {
+        assert!(
+            vertices.len().is_multiple_of(3),
+            "triangle lists must have a multiple of 3 vertices"
+        );
+
+        // Consecutive triangles with the same texture are drawn together.
+        let same_texture = match (self.batches.last(), texture) {
+            (Some((_, Some(last))), Some(texture)) => Rc::ptr_eq(last, texture),
+            (Some((_, None)), None) => true,
+            _ => false,
+        };
+        if !same_texture {
+            self.batches
+                .push((self.vertices.len(), texture.map(Rc::clone)));
+        }
+
+        self.vertices.extend_from_slice(vertices);
+    }
+
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.batches.clear();
+    }
+
+    /// Draw a solid rectangle, with its top-left corner at `(x, y)` when using
+    /// the [screen camera](Camera::screen).
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let [top_left, top_right, bottom_left, bottom_right] = [
+            [x, y, 0.0],
+            [x + width, y, 0.0],
+            [x, y + height, 0.0],
+            [x + width, y + height, 0.0],
+        ]
+        .map(|position| Vertex::new(position, color));
+
+        self.triangles(&[
+            top_left,
+            bottom_left,
+            top_right,
+            top_right,
+            bottom_left,
+            bottom_right,
+        ]);
+    }
+
+    /// Draw a solid circle made of `segments` triangles.
+    pub fn circle(&mut self, center: [f32; 2], radius: f32, segments: u16, color: Color) {
+        let [x, y] = center;
+        let point = |i: u16| {
+            let angle = f32::from(i) / f32::from(segments) * 2.0 * PI;
+            Vertex::new(
+                [x + radius * angle.cos(), y + radius * angle.sin(), 0.0],
+                color,
+            )
+        };
+
+        for i in 0..segments {
+            self.triangle(Vertex::new([x, y, 0.0], color), point(i), point(i + 1));
+        }
+    }
+}
+
+/// A ready-to-use `citro3d` setup for both screens. See the
+/// [module documentation](self) for an example.
+pub struct App<'gfx> {
+    // Fields are dropped in declaration order, so the targets are deleted
+    // before the instance, and the program (which the instance keeps bound)
+    // before the library it uses.
+    top: Target<'gfx>,
+    bottom: Target<'gfx>,
+    top_canvas: Canvas,
+    bottom_canvas: Canvas,
+    program: Rc<shader::Program>,
+    projection: uniform::Index,
+    attr_info: attrib::Info,
+    instance: Instance,
+    _library: shader::Library,
+}
+
+impl<'gfx> App<'gfx> {
+    /// Initialize `citro3d` and create render targets for both screens.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `citro3d` could not be initialized, or if the render targets
+    /// could not be created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either screen is already borrowed.
+    pub fn new(gfx: &'gfx Gfx) -> Result<Self> {
+        let mut instance = Instance::new()?;
+
+        let (top, top_size) = screen_target(&instance, gfx.top_screen.borrow_mut())?;
+        let (bottom, bottom_size) = screen_target(&instance, gfx.bottom_screen.borrow_mut())?;
+
+        let library =
+            shader::Library::from_bytes(SHADER_BYTES).map_err(|_| Error::FailedToInitialize)?;
+        let vertex_shader = library.get(0).ok_or(Error::NotFound)?;
+        let program = Rc::new(shader::Program::new(vertex_shader)?);
+        let projection = program.get_uniform("projection")?;
+        instance.bind_program(&program);
+
+        let mut attr_info = attrib::Info::new();
+        attr_info.add_loader(attrib::Register::new(0)?, attrib::Format::Float, 3)?;
+        attr_info.add_loader(attrib::Register::new(1)?, attrib::Format::Float, 4)?;
+        attr_info.add_loader(attrib::Register::new(2)?, attrib::Format::Float, 2)?;
+
+        // Draw triangles regardless of their winding, since flipping the Y
+        // axis for the screen camera reverses it.
+        unsafe { citro3d_sys::C3D_CullFace(ctru_sys::GPU_CULL_NONE) };
+
+        Ok(Self {
+            top,
+            bottom,
+            top_canvas: Canvas::new(top_size, AspectRatio::TopScreen),
+            bottom_canvas: Canvas::new(bottom_size, AspectRatio::BottomScreen),
+            program,
+            projection,
+            attr_info,
+            instance,
+            _library: library,
+        })
+    }
+
+    /// Render a frame. `f` receives a [`Canvas`] for the top and bottom
+    /// screens respectively, to add the geometry to draw this frame.
+    #[doc(alias = "C3D_FrameBegin")]
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn frame(&mut self, f: impl FnOnce(&mut Canvas, &mut Canvas)) {
+        let Self {
+            top,
+            bottom,
+            top_canvas,
+            bottom_canvas,
+            projection,
+            attr_info,
+            instance,
+            ..
+        } = self;
+
+        instance.render_frame_with(|frame| {
+            // The previous frame is done with the vertex data and textures
+            // once the new frame has begun, so they can be released now.
+            top_canvas.clear();
+            bottom_canvas.clear();
+            f(top_canvas, bottom_canvas);
+
+            for (target, canvas) in [(top, &*top_canvas), (bottom, &*bottom_canvas)] {
+                draw_canvas(frame, target, canvas, *projection, attr_info);
+            }
+        });
+    }
+
+    /// The underlying [`Instance`], for features not covered by this module.
+    /// Note that the built-in shader program, the first texture combiner stage,
+    /// texture unit 0 and face culling are configured by the [`App`], so
+    /// changing them affects how canvases are drawn.
+    pub fn instance(&mut self) -> &mut Instance {
+        &mut self.instance
+    }
+
+    /// The built-in shader program.
+    pub fn program(&self) -> &Rc<shader::Program> {
+        &self.program
+    }
+}
+
+fn draw_canvas(
+    frame: &mut Frame<'_>,
+    target: &mut Target<'_>,
+    canvas: &Canvas,
+    projection: uniform::Index,
+    attr_info: &attrib::Info,
+) {
+    target.clear_with(ClearFlags::ALL, canvas.clear_color, 0.0, 0);
+
+    frame
+        .select_render_target(target)
+        .expect("failed to select render target");
+
+    if canvas.vertices.is_empty() {
+        return;
+    }
+
+    let depth_test = matches!(canvas.camera.kind, CameraKind::Perspective { .. });
+    frame.set_depth_test(depth_test, TestFunction::Greater, WriteMask::All);
+
+    let matrix = canvas.camera.matrix(canvas.size, canvas.aspect_ratio);
+    frame.bind_vertex_uniform(projection, matrix);
+    frame.set_attr_info(attr_info);
+
+    let mut buf_info = buffer::Info::new();
+    let slice = buf_info
+        .add(&canvas.vertices, attr_info)
+        .expect("a new buffer info has room for the canvas vertices");
+
+    let stage0 = texenv::Stage::new(0).unwrap();
+    for (i, (start, texture)) in canvas.batches.iter().enumerate() {
+        let end = canvas
+            .batches
+            .get(i + 1)
+            .map_or(canvas.vertices.len(), |(end, _)| *end);
+
+        let texenv = frame.texenv(stage0);
+        if let Some(texture) = texture {
+            texenv
+                .src(
+                    texenv::Mode::BOTH,
+                    texenv::Source::Texture0,
+                    Some(texenv::Source::PrimaryColor),
+                    None,
+                )
+                .func(texenv::Mode::BOTH, texenv::CombineFunc::Modulate);
+            frame.bind_texture(texture::Unit::Unit0, texture);
+        } else {
+            texenv
+                .src(texenv::Mode::BOTH, texenv::Source::PrimaryColor, None, None)
+                .func(texenv::Mode::BOTH, texenv::CombineFunc::Replace);
+        }
+
+        let batch = slice
+            .subslice(*start as libc::c_int..end as libc::c_int)
+            .expect("batches are within the canvas vertices");
+        frame.draw_arrays(buffer::Primitive::Triangles, batch);
+    }
+}
+
+/// Create a render target for `screen`, returning it along with the size of
+/// the screen in pixels as seen by the user.
+fn screen_target<'gfx>(
+    instance: &Instance,
+    mut screen: RefMut<'gfx, dyn Screen>,
+) -> Result<(Target<'gfx>, (f32, f32))> {
+    let RawFrameBuffer { width, height, .. } = screen.raw_framebuffer();
+    let target =
+        instance.render_target(width, height, screen, Some(DepthFormat::Depth24Stencil8))?;
+
+    // Framebuffers are rotated, so their width is the screen's height.
+    Ok((target, (height as f32, width as f32)))
+}
@@ -1,15 +1,13 @@
 //! General-purpose error and result types returned by public APIs of this crate.
 
 use std::ffi::NulError;
+use std::fmt;
 use std::num::TryFromIntError;
 use std::sync::TryLockError;
 
 /// The common result type returned by `citro3d` functions.
 pub type Result<T> = std::result::Result<T, Error>;
 
-// TODO probably want a similar type to ctru::Result to make it easier to convert
-// nonzero result codes to errors.
-
 /// The common error type that may be returned by `citro3d` functions.
 #[non_exhaustive]
 #[derive(Debug)]
@@ -18,6 +16,10 @@ pub enum Error {
     System(libc::c_int),
     /// A C3D object or context could not be initialized.
     FailedToInitialize,
+    /// A `libctru` call (e.g. a GX or GSP command) failed with the given result code.
+    Ctru(ctru::Error),
+    /// There was not enough VRAM or linear memory to allocate a GPU resource.
+    OutOfMemory,
     /// A size parameter was specified that cannot be converted to the proper type.
     InvalidSize,
     /// Failed to select the given render target for drawing to.
@@ -36,6 +38,17 @@ pub enum Error {
     InvalidName,
     /// The requested resource could not be found.
     NotFound,
+    /// The operation could not be completed without blocking, e.g. a frame was
+    /// begun with [`FrameFlags::NON_BLOCK`](crate::render::FrameFlags::NON_BLOCK)
+    /// while the GPU was still busy.
+    WouldBlock,
+    /// The vertex attributes did not match the inputs of a shader: the given
+    /// input register is either not loaded by any attribute, or loaded by an
+    /// attribute without being a shader input.
+    AttributeMismatch {
+        /// The input register (i.e. `v0` to `v15`).
+        register: u8,
+    },
     /// Attempted to use an index that was out of bounds.
     IndexOutOfBounds {
         /// The index used.
@@ -43,6 +56,15 @@ pub enum Error {
         /// The length of the collection.
         len: libc::c_int,
     },
+    /// A uniform could not be bound at the given index, because the registers
+    /// it would take up are out of range for its kind of uniform, or reserved
+    /// by the bound geometry shader for its input vertices.
+    InvalidUniformIndex {
+        /// The index of the first register.
+        index: u8,
+        /// The number of registers the uniform takes up.
+        len: usize,
+    },
 }
 
 impl From<TryFromIntError> for Error {
@@ -62,3 +84,59 @@ impl From<NulError> for Error {
         Self::InvalidName
     }
 }
+
+impl From<ctru::Error> for Error {
+    fn from(err: ctru::Error) -> Self {
+        Self::Ctru(err)
+    }
+}
+
+/// Convert a `libctru` result code to an [`Error::Ctru`] if it indicates failure.
+pub(crate) fn ctru_result(result: ctru_sys::Result) -> Result<()> {
+    if result < 0 {
+        Err(Error::Ctru(ctru::Error::from(result)))
+    } else {
+        Ok(())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::System(code) => write!(f, "citro3d error code {code}"),
+            Self::FailedToInitialize => f.write_str("failed to initialize citro3d object"),
+            Self::Ctru(err) => write!(f, "libctru error: {err}"),
+            Self::OutOfMemory => f.write_str("not enough GPU-accessible memory"),
+            Self::InvalidSize => f.write_str("invalid size"),
+            Self::InvalidRenderTarget => f.write_str("invalid render target"),
+            Self::LockHeld => f.write_str("lock is already held"),
+            Self::TooManyAttributes => f.write_str("too many vertex attributes"),
+            Self::TooManyBuffers => f.write_str("too many vertex buffers"),
+            Self::InvalidMemoryLocation => f.write_str("memory is not in the linear heap"),
+            Self::InvalidName => f.write_str("invalid name"),
+            Self::NotFound => f.write_str("not found"),
+            Self::WouldBlock => f.write_str("operation would block"),
+            Self::AttributeMismatch { register } => {
+                write!(f, "vertex attributes do not match shader input v{register}")
+            }
+            Self::IndexOutOfBounds { idx, len } => {
+                write!(f, "index {idx} out of bounds for length {len}")
+            }
+            Self::InvalidUniformIndex { index, len } => {
+                write!(
+                    f,
+                    "cannot bind {len} uniform register(s) at index {index:#x}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Ctru(err) => Some(err),
+            _ => None,
+        }
+    }
+}
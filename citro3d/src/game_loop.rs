@@ -0,0 +1,136 @@
+//! A fixed-timestep game loop, so simulation stability doesn't depend on the
+//! frame rate.
+//!
+//! The simulation is advanced in steps of a fixed length, as many times as
+//! needed to catch up with real time. Rendering happens once per frame, with an
+//! interpolation factor which can be used to blend between the previous and
+//! current simulation states.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # let _runner = test_runner::GdbRunner::default();
+//! # use std::time::Duration;
+//! # use citro3d::game_loop::GameLoop;
+//! # let mut instance = citro3d::Instance::new().unwrap();
+//! struct State {
+//!     previous: f32,
+//!     position: f32,
+//! }
+//!
+//! let mut state = State {
+//!     previous: 0.0,
+//!     position: 0.0,
+//! };
+//! let mut game_loop = GameLoop::with_updates_per_second(60);
+//!
+//! loop {
+//!     game_loop.frame(
+//!         &mut instance,
+//!         &mut state,
+//!         |state, step| {
+//!             state.previous = state.position;
+//!             state.position += 10.0 * step.as_secs_f32();
+//!         },
+//!         |_frame, state, alpha| {
+//!             let _drawn_at = state.previous + (state.position - state.previous) * alpha;
+//!             // ... draw using `_frame` ...
+//!         },
+//!     );
+//! #   break;
+//! }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::Instance;
+use crate::render::Frame;
+
+/// Drives fixed-timestep updates and per-frame rendering. See the
+/// [module documentation](self) for an example.
+#[derive(Debug, Clone)]
+pub struct GameLoop {
+    timestep: Duration,
+    max_frame_time: Duration,
+    accumulator: Duration,
+    last_frame: Option<Instant>,
+}
+
+impl GameLoop {
+    /// Create a new game loop which updates the simulation in steps of `timestep`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timestep` is zero.
+    pub fn new(timestep: Duration) -> Self {
+        assert!(!timestep.is_zero(), "timestep must not be zero");
+
+        Self {
+            timestep,
+            // Enough to ride out a hiccup (e.g. loading something), while
+            // still avoiding a spiral of ever-longer catch-up frames.
+            max_frame_time: Duration::from_millis(250),
+            accumulator: Duration::ZERO,
+            last_frame: None,
+        }
+    }
+
+    /// Create a new game loop which updates the simulation `rate` times per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is zero.
+    pub fn with_updates_per_second(rate: u32) -> Self {
+        assert!(rate > 0, "update rate must not be zero");
+        Self::new(Duration::from_secs(1) / rate)
+    }
+
+    /// The length of a single simulation step.
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    /// Set the longest real time a single frame can account for. If a frame
+    /// takes longer than this, the simulation slows down instead of running
+    /// many updates to catch up. Defaults to 250ms.
+    pub fn set_max_frame_time(&mut self, max_frame_time: Duration) {
+        self.max_frame_time = max_frame_time;
+    }
+
+    /// Forget about time which has passed since the last frame, e.g. after
+    /// the application was suspended. The next frame will not run any updates.
+    pub fn reset(&mut self) {
+        self.accumulator = Duration::ZERO;
+        self.last_frame = None;
+    }
+
+    /// Run a single frame: call `update` once for each timestep that has
+    /// passed since the previous frame, then render a frame with `render`.
+    ///
+    /// `render` receives the interpolation factor between the previous and
+    /// current simulation states, from `0.0` to `1.0`.
+    #[doc(alias = "C3D_FrameBegin")]
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn frame<S>(
+        &mut self,
+        instance: &mut Instance,
+        state: &mut S,
+        mut update: impl FnMut(&mut S, Duration),
+        render: impl FnOnce(&mut Frame<'_>, &S, f32),
+    ) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_frame
+            .map_or(Duration::ZERO, |last| now.duration_since(last));
+        self.last_frame = Some(now);
+
+        self.accumulator += elapsed.min(self.max_frame_time);
+        while self.accumulator >= self.timestep {
+            update(state, self.timestep);
+            self.accumulator -= self.timestep;
+        }
+
+        let alpha = self.accumulator.as_secs_f32() / self.timestep.as_secs_f32();
+        instance.render_frame_with(|frame| render(frame, state, alpha));
+    }
+}
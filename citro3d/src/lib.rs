@@ -16,37 +16,48 @@
 //! ## Feature flags
 #![doc = document_features::document_features!()]
 
+pub mod animation;
 pub mod attrib;
 pub mod buffer;
 pub mod color;
+pub mod command;
+#[cfg(feature = "easy")]
+pub mod easy;
 pub mod error;
 pub mod fog;
+pub mod game_loop;
 pub mod light;
 pub mod math;
+pub mod mesh;
+pub mod prepare;
 pub mod render;
 pub mod shader;
 pub mod texenv;
 pub mod texture;
 pub mod uniform;
 
+use std::any::Any;
 use std::cell::{OnceCell, RefMut};
 use std::fmt;
 use std::pin::Pin;
 use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::Duration;
 
-use ctru::services::gfx::Screen;
+use ctru::services::gfx::{Screen, TopScreen, TopScreen3D};
 pub use error::{Error, Result};
 
-use self::buffer::{Index, Indices};
 use self::light::LightEnv;
 use self::texenv::TexEnv;
-use self::uniform::Uniform;
 
 pub mod macros {
-    //! Helper macros for working with shaders.
+    //! Helper macros for working with shaders and vertex types.
     pub use citro3d_macros::*;
 }
 
+// Lets `#[derive(Vertex)]` refer to `::citro3d` from within this crate too.
+extern crate self as citro3d;
+
 mod private {
     pub trait Sealed {}
     impl Sealed for u8 {}
@@ -61,8 +72,19 @@ pub struct Instance {
     texenvs: [OnceCell<TexEnv>; texenv::TEXENV_COUNT],
     queue: Rc<RenderQueue>,
     light_env: Option<Pin<Box<LightEnv>>>,
+    textures: [Option<Rc<texture::Texture>>; 3],
+    program: Option<Rc<shader::Program>>,
+    effect: render::effect::State,
+    /// Memory used by the frame in flight (e.g. index buffers), kept alive
+    /// until the GPU is done with it (i.e. the next frame begins).
+    frame_resources: Vec<Rc<dyn Any>>,
 }
 
+/// The callback set with [`Instance::set_frame_end_callback`]. `citro3d` calls
+/// it from the GSP event thread, so it's kept behind a lock rather than in the
+/// instance itself, where it could be replaced while it is running.
+static FRAME_END_CALLBACK: Mutex<Option<Box<dyn FnMut() + Send>>> = Mutex::new(None);
+
 /// Representation of `citro3d`'s internal render queue. This is something that
 /// lives in the global context, but it keeps references to resources that are
 /// used for rendering, so it's useful for us to have something to represent its
@@ -105,6 +127,10 @@ impl Instance {
                 ],
                 queue: Rc::new(RenderQueue),
                 light_env: None,
+                textures: [None, None, None],
+                program: None,
+                effect: render::effect::State::default(),
+                frame_resources: Vec::new(),
             })
         } else {
             Err(Error::FailedToInitialize)
@@ -114,9 +140,18 @@ impl Instance {
     /// Create a new render target with the specified size, color format,
     /// and depth format.
     ///
+    /// Render targets use the native layout of the screens' framebuffers, which
+    /// are in portrait orientation (e.g. 240x400 for the top screen), and are
+    /// displayed without any rotation. Projections account for the orientation
+    /// of the rendered image instead, see [`math::ScreenOrientation`] and
+    /// [`Target::set_orientation`](render::Target::set_orientation) to build a
+    /// matching projection for the target.
+    ///
     /// # Errors
     ///
-    /// Fails if the target could not be created with the given parameters.
+    /// * [`Error::InvalidSize`] if `width` or `height` is zero or larger than 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the color and
+    ///   depth buffers.
     #[doc(alias = "C3D_RenderTargetCreate")]
     #[doc(alias = "C3D_RenderTargetSetOutput")]
     pub fn render_target<'screen>(
@@ -129,16 +164,285 @@ impl Instance {
         render::Target::new(width, height, screen, depth_format, Rc::clone(&self.queue))
     }
 
+    /// Create a new render target for the top screen in wide mode, which
+    /// displays a single 800x240 image instead of a separate image for each
+    /// eye, e.g. for text-heavy apps. Wide mode is enabled on the `screen`, and
+    /// the target is created with the matching 240x800 size (see
+    /// [`render_target`](Self::render_target) for the orientation).
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidRenderTarget`] if stereoscopic 3D is enabled, since
+    ///   it can't be used together with wide mode.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the color and
+    ///   depth buffers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new().unwrap();
+    /// # let instance = citro3d::Instance::new().unwrap();
+    /// let target = instance
+    ///     .render_target_wide(gfx.top_screen.borrow_mut(), None)
+    ///     .unwrap();
+    /// ```
+    #[doc(alias = "gfxSetWide")]
+    #[doc(alias = "C3D_RenderTargetCreate")]
+    pub fn render_target_wide<'screen>(
+        &self,
+        mut screen: RefMut<'screen, TopScreen>,
+        depth_format: Option<render::DepthFormat>,
+    ) -> Result<render::Target<'screen>> {
+        if unsafe { ctru_sys::gfxIs3D() } {
+            return Err(Error::InvalidRenderTarget);
+        }
+
+        screen.set_wide_mode(true);
+        let screen = RefMut::map(screen, |screen| screen as &mut dyn Screen);
+
+        render::Target::new(240, 800, screen, depth_format, Rc::clone(&self.queue))
+    }
+
+    /// Create a new render target which is rendered at a higher resolution
+    /// and downscaled when displayed, for anti-aliasing. `width` and `height`
+    /// are the size of the displayed image, as for
+    /// [`render_target`](Self::render_target).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::Gfx;
+    /// # use citro3d::render::{AaMode, DepthFormat};
+    /// # let gfx = Gfx::new().unwrap();
+    /// # let instance = citro3d::Instance::new().unwrap();
+    /// let target = instance
+    ///     .render_target_antialiased(
+    ///         240,
+    ///         400,
+    ///         gfx.top_screen.borrow_mut(),
+    ///         Some(DepthFormat::Depth24),
+    ///         AaMode::X4,
+    ///     )
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if the scaled `width` or `height` is zero or
+    ///   larger than 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the color and
+    ///   depth buffers.
+    #[doc(alias = "GX_TRANSFER_SCALING")]
+    pub fn render_target_antialiased<'screen>(
+        &self,
+        width: usize,
+        height: usize,
+        screen: RefMut<'screen, dyn Screen>,
+        depth_format: Option<render::DepthFormat>,
+        aa: render::AaMode,
+    ) -> Result<render::Target<'screen>> {
+        let (width, height) = aa.scaled_size(width, height);
+        let mut target =
+            render::Target::new(width, height, screen, depth_format, Rc::clone(&self.queue))?;
+        target.set_transfer_flags(render::transfer::Flags::default().scaling(aa.scaling()));
+        Ok(target)
+    }
+
+    /// Create a new render target which uses a shared depth buffer instead of
+    /// allocating its own. See [`render::DepthBuffer`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::{Gfx, TopScreen3D};
+    /// # use citro3d::render::{DepthBuffer, DepthFormat};
+    /// # let gfx = Gfx::new().unwrap();
+    /// # let instance = citro3d::Instance::new().unwrap();
+    /// let top_screen = TopScreen3D::from(&gfx.top_screen);
+    /// let (left, right) = top_screen.split_mut();
+    ///
+    /// let depth = DepthBuffer::new(240, 400, DepthFormat::Depth24Stencil8).unwrap();
+    /// let left = instance
+    ///     .render_target_with_depth(240, 400, left, &depth)
+    ///     .unwrap();
+    /// let right = instance
+    ///     .render_target_with_depth(240, 400, right, &depth)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if `width` and `height` don't match the size
+    ///   of `depth`.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the color buffer.
+    #[doc(alias = "C3D_FrameBufDepth")]
+    pub fn render_target_with_depth<'screen>(
+        &self,
+        width: usize,
+        height: usize,
+        screen: RefMut<'screen, dyn Screen>,
+        depth: &Rc<render::DepthBuffer>,
+    ) -> Result<render::Target<'screen>> {
+        render::Target::with_shared_depth(width, height, screen, depth, Rc::clone(&self.queue))
+    }
+
+    /// Create a pair of 240x400 render targets for the left and right eye of
+    /// the top screen, for stereoscopic 3D. If a `depth_format` is given, both
+    /// targets share a single [depth buffer](render::DepthBuffer), so it should
+    /// be cleared before drawing each eye.
+    ///
+    /// To target only one eye, pass one of the screens from
+    /// [`TopScreen3D::split_mut`] to [`render_target`](Self::render_target)
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the color and
+    ///   depth buffers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::{Gfx, TopScreen3D};
+    /// # use citro3d::render::DepthFormat;
+    /// # let gfx = Gfx::new().unwrap();
+    /// # let instance = citro3d::Instance::new().unwrap();
+    /// let top_screen = TopScreen3D::from(&gfx.top_screen);
+    /// let (left, right) = instance
+    ///     .render_targets_stereo(&top_screen, Some(DepthFormat::Depth24))
+    ///     .unwrap();
+    /// ```
+    pub fn render_targets_stereo<'screen>(
+        &self,
+        top_screen: &'screen TopScreen3D<'_>,
+        depth_format: Option<render::DepthFormat>,
+    ) -> Result<(render::Target<'screen>, render::Target<'screen>)> {
+        let (width, height) = (240, 400);
+        let (left, right) = top_screen.split_mut();
+
+        match depth_format {
+            Some(format) => {
+                let depth = render::DepthBuffer::new(width, height, format)?;
+                Ok((
+                    self.render_target_with_depth(width, height, left, &depth)?,
+                    self.render_target_with_depth(width, height, right, &depth)?,
+                ))
+            }
+            None => Ok((
+                self.render_target(width, height, left, None)?,
+                self.render_target(width, height, right, None)?,
+            )),
+        }
+    }
+
+    /// Create a new offscreen render target with no color buffer, which only
+    /// draws into the given shared `depth` buffer. See [`render::DepthTarget`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::Gfx;
+    /// # use citro3d::render::{DepthBuffer, DepthFormat};
+    /// # let gfx = Gfx::new().unwrap();
+    /// # let instance = citro3d::Instance::new().unwrap();
+    /// let depth = DepthBuffer::new(240, 400, DepthFormat::Depth24).unwrap();
+    ///
+    /// // Fill the depth buffer in a pre-pass, then draw colors with it.
+    /// let pre_pass = instance.depth_target(&depth).unwrap();
+    /// let target = instance
+    ///     .render_target_with_depth(240, 400, gfx.top_screen.borrow_mut(), &depth)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM to create the target.
+    #[doc(alias = "C3D_RenderTargetCreate")]
+    pub fn depth_target(&self, depth: &Rc<render::DepthBuffer>) -> Result<render::DepthTarget> {
+        render::DepthTarget::new(depth, Rc::clone(&self.queue))
+    }
+
+    /// Create a new offscreen render target which draws into a texture in VRAM.
+    /// The texture can then be sampled in a later pass, see
+    /// [`render::TextureTarget`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if `width` or `height` is not a power of two
+    ///   from 8 to 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the texture
+    ///   and depth buffer.
+    #[doc(alias = "C3D_RenderTargetCreateFromTex")]
+    pub fn texture_target(
+        &self,
+        width: usize,
+        height: usize,
+        color_format: render::ColorFormat,
+        depth_format: Option<render::DepthFormat>,
+    ) -> Result<render::TextureTarget> {
+        render::TextureTarget::new(
+            width,
+            height,
+            color_format,
+            depth_format,
+            Rc::clone(&self.queue),
+        )
+    }
+
+    /// Create a new offscreen render target which draws into the given face of
+    /// an existing texture, e.g. one of the faces of a
+    /// [cube shadow map](texture::Texture::new_shadow_cube). Use
+    /// [`texture::Face::Bidimensional`] for 2D textures.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::rc::Rc;
+    /// # use citro3d::texture::{Face, Texture};
+    /// # let instance = citro3d::Instance::new().unwrap();
+    /// let shadow_map = Rc::new(Texture::new_shadow(256, 256).unwrap());
+    /// let target = instance
+    ///     .texture_target_from(&shadow_map, Face::Bidimensional, None)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidRenderTarget`] if `face` is not
+    ///   [`Bidimensional`](texture::Face::Bidimensional) and the texture is
+    ///   not a cube map.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the depth buffer.
+    #[doc(alias = "C3D_RenderTargetCreateFromTex")]
+    pub fn texture_target_from(
+        &self,
+        texture: &Rc<texture::Texture>,
+        face: texture::Face,
+        depth_format: Option<render::DepthFormat>,
+    ) -> Result<render::TextureTarget> {
+        render::TextureTarget::from_texture(texture, face, depth_format, Rc::clone(&self.queue))
+    }
+
     /// Select the given render target for drawing the frame. This must be called
     /// as pare of a render call (i.e. within the call to
-    /// [`render_frame_with`](Self::render_frame_with)).
+    /// [`render_frame_with`](Self::render_frame_with), or while a frame from
+    /// [`begin_frame`](Self::begin_frame) is alive).
     ///
     /// # Errors
     ///
     /// Fails if the given target cannot be used for drawing, or called outside
     /// the context of a frame render.
     #[doc(alias = "C3D_FrameDrawOn")]
-    pub fn select_render_target(&mut self, target: &render::Target<'_>) -> Result<()> {
+    pub fn select_render_target(
+        &mut self,
+        target: &(impl render::RenderTarget + ?Sized),
+    ) -> Result<()> {
         let _ = self;
         if unsafe { citro3d_sys::C3D_FrameDrawOn(target.as_raw()) } {
             Ok(())
@@ -147,26 +451,521 @@ impl Instance {
         }
     }
 
-    /// Render a frame. The passed in function/closure can mutate the instance,
-    /// such as to [select a render target](Self::select_render_target)
+    /// Clear the target of the given render `pass` as configured, and select it
+    /// for drawing. Like [`select_render_target`](Self::select_render_target),
+    /// this must be called as part of a render call.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the pass's target cannot be used for drawing, or called outside
+    /// the context of a frame render.
+    #[doc(alias = "C3D_RenderTargetClear")]
+    #[doc(alias = "C3D_FrameDrawOn")]
+    pub fn select_render_pass(&mut self, pass: &render::RenderPass<'_>) -> Result<()> {
+        let _ = self;
+        pass.begin()
+    }
+
+    /// Render a frame. The passed in function/closure receives the
+    /// [`Frame`](render::Frame) to draw with, which can also mutate the
+    /// instance, such as to [select a render target](Self::select_render_target)
     /// or [bind a new shader program](Self::bind_program).
     #[doc(alias = "C3D_FrameBegin")]
     #[doc(alias = "C3D_FrameEnd")]
-    pub fn render_frame_with(&mut self, f: impl FnOnce(&mut Self)) {
+    pub fn render_frame_with(&mut self, f: impl FnOnce(&mut render::Frame<'_>)) {
         unsafe {
             citro3d_sys::C3D_FrameBegin(
-                // TODO: begin + end flags should be configurable
+                // TODO: end flags should be configurable
                 citro3d_sys::C3D_FRAME_SYNCDRAW,
             );
         }
 
-        f(self);
+        // The frame is ended once it is dropped.
+        f(&mut render::Frame::new(self));
+    }
+
+    /// Begin rendering a frame. This is an alternative to
+    /// [`render_frame_with`](Self::render_frame_with) for code which can't easily
+    /// be structured as a single closure, e.g. a game loop made up of several
+    /// systems which each draw part of the frame.
+    ///
+    /// The returned [`Frame`](render::Frame) forwards the per-frame methods of
+    /// the instance, e.g. [`select_render_target`](render::Frame::select_render_target),
+    /// [`bind_program`](render::Frame::bind_program) and the effect setters,
+    /// and ends the frame when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::WouldBlock`] if `flags` contains
+    /// [`NON_BLOCK`](render::FrameFlags::NON_BLOCK) and the GPU is still busy
+    /// with the previous frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::Gfx;
+    /// # use citro3d::render::FrameFlags;
+    /// # let gfx = Gfx::new().unwrap();
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let target = instance
+    ///     .render_target(240, 400, gfx.top_screen.borrow_mut(), None)
+    ///     .unwrap();
+    ///
+    /// let mut frame = instance.begin_frame(FrameFlags::SYNC_DRAW).unwrap();
+    /// frame.select_render_target(&target).unwrap();
+    /// // Draw calls go here...
+    /// frame.end();
+    /// ```
+    #[doc(alias = "C3D_FrameBegin")]
+    pub fn begin_frame(&mut self, flags: render::FrameFlags) -> Result<render::Frame<'_>> {
+        if unsafe { citro3d_sys::C3D_FrameBegin(flags.bits()) } {
+            Ok(render::Frame::new(self))
+        } else {
+            Err(Error::WouldBlock)
+        }
+    }
+
+    /// Render a frame to several targets, e.g. the top and bottom screen. The
+    /// passed in function/closure is called once for each target, with the
+    /// [`Frame`](render::Frame) to draw with (the target already selected) and
+    /// the target's index in `targets`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any of the targets cannot be used for drawing. The frame is
+    /// still ended, but the remaining targets are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new().unwrap();
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let top = instance
+    ///     .render_target(240, 400, gfx.top_screen.borrow_mut(), None)
+    ///     .unwrap();
+    /// let bottom = instance
+    ///     .render_target(240, 320, gfx.bottom_screen.borrow_mut(), None)
+    ///     .unwrap();
+    ///
+    /// instance
+    ///     .render_to_targets(&[&top, &bottom], |frame, index| {
+    ///         // Draw the top screen when `index` is 0, the bottom screen when it's 1...
+    ///     })
+    ///     .unwrap();
+    /// ```
+    #[doc(alias = "C3D_FrameBegin")]
+    #[doc(alias = "C3D_FrameDrawOn")]
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn render_to_targets(
+        &mut self,
+        targets: &[&dyn render::RenderTarget],
+        mut f: impl FnMut(&mut render::Frame<'_>, usize),
+    ) -> Result<()> {
+        let mut frame = self.begin_frame(render::FrameFlags::SYNC_DRAW)?;
+
+        for (index, target) in targets.iter().enumerate() {
+            frame.select_render_target(*target)?;
+            f(&mut frame, index);
+        }
+
+        Ok(())
+    }
+
+    /// Set how fragments are written to the render target. Use
+    /// [`FragmentOperationMode::Shadow`](render::effect::FragmentOperationMode::Shadow)
+    /// to render depth into a [shadow map](texture::Texture::new_shadow), and
+    /// switch back to [`Gl`](render::effect::FragmentOperationMode::Gl) afterwards.
+    #[doc(alias = "C3D_FragOpMode")]
+    pub fn set_fragment_operation_mode(&mut self, mode: render::effect::FragmentOperationMode) {
+        self.effect.fragment_operation_mode = mode;
+        unsafe { citro3d_sys::C3D_FragOpMode(mode as ctru_sys::GPU_FRAGOPMODE) };
+    }
+
+    /// Set the scale and bias applied to the penumbra (soft shadow edge)
+    /// values written while rendering in
+    /// [`Shadow`](render::effect::FragmentOperationMode::Shadow) mode.
+    #[doc(alias = "C3D_FragOpShadow")]
+    pub fn set_shadow_penumbra(&mut self, scale: f32, bias: f32) {
+        self.effect.shadow_penumbra = (scale, bias);
+        unsafe { citro3d_sys::C3D_FragOpShadow(scale, bias) };
+    }
+
+    /// Set how bound shadow maps are sampled. `perspective` selects whether
+    /// texture coordinates are divided by their `w` component (for perspective
+    /// light projections), and `bias` is subtracted from fragment depths before
+    /// comparing them with the shadow map, to avoid shadow acne.
+    #[doc(alias = "C3D_TexShadowParams")]
+    pub fn set_shadow_sampling(&mut self, perspective: bool, bias: f32) {
+        self.effect.shadow_sampling = (perspective, bias);
+        unsafe { citro3d_sys::C3D_TexShadowParams(perspective, bias) };
+    }
+
+    /// Set how clip space depths are mapped to values in the depth buffer:
+    /// `depth = z * scale + offset`, where `z` is divided by `w` when
+    /// `z_buffer` is true, and used as-is (for w-buffering) otherwise.
+    ///
+    /// The default is `set_depth_map(true, -1.0, 0.0)`, so closer fragments have
+    /// greater depth values. Changing `offset` slightly for decals or other
+    /// coplanar geometry (e.g. to `0.0001`) moves it in front of the surface
+    /// it's drawn on, preventing z-fighting.
+    #[doc(alias = "C3D_DepthMap")]
+    pub fn set_depth_map(&mut self, z_buffer: bool, scale: f32, offset: f32) {
+        self.effect.depth_map = (z_buffer, scale, offset);
+        unsafe { citro3d_sys::C3D_DepthMap(z_buffer, scale, offset) };
+    }
+
+    /// Configure the depth test. If `enabled`, fragments are only drawn if
+    /// their depth passes the comparison `function` with the value in the depth
+    /// buffer. `write_mask` selects which components of passing fragments are
+    /// written, even if the test is disabled.
+    ///
+    /// The default is `set_depth_test(true, TestFunction::Greater, WriteMask::All)`,
+    /// matching the default [depth map](Self::set_depth_map). Transparent
+    /// geometry is usually drawn with [`WriteMask::Color`](render::effect::WriteMask::Color),
+    /// so it's still hidden by opaque geometry but doesn't hide anything itself.
+    #[doc(alias = "C3D_DepthTest")]
+    pub fn set_depth_test(
+        &mut self,
+        enabled: bool,
+        function: render::effect::TestFunction,
+        write_mask: render::effect::WriteMask,
+    ) {
+        self.effect.depth_test = (enabled, function, write_mask);
+        unsafe {
+            citro3d_sys::C3D_DepthTest(
+                enabled,
+                function as ctru_sys::GPU_TESTFUNC,
+                write_mask as ctru_sys::GPU_WRITEMASK,
+            );
+        }
+    }
+
+    /// Configure alpha blending, which combines the colors of drawn fragments
+    /// (the source) with the colors already in the render target (the
+    /// destination). The color and alpha components are each computed as
+    /// `src * src_factor <equation> dst * dst_factor`.
+    ///
+    /// The default is regular alpha blending:
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::render::effect::{BlendEquation, BlendFactor};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// instance.set_blend(
+    ///     BlendEquation::Add,
+    ///     BlendEquation::Add,
+    ///     BlendFactor::SrcAlpha,
+    ///     BlendFactor::OneMinusSrcAlpha,
+    ///     BlendFactor::SrcAlpha,
+    ///     BlendFactor::OneMinusSrcAlpha,
+    /// );
+    /// ```
+    ///
+    /// For premultiplied alpha use [`One`](render::effect::BlendFactor::One)
+    /// as the source factors instead, and for additive blending use `One` as
+    /// the destination factors.
+    ///
+    /// This replaces any [logic operation](Self::set_color_logic_op).
+    #[doc(alias = "C3D_AlphaBlend")]
+    pub fn set_blend(
+        &mut self,
+        color_equation: render::effect::BlendEquation,
+        alpha_equation: render::effect::BlendEquation,
+        src_color: render::effect::BlendFactor,
+        dst_color: render::effect::BlendFactor,
+        src_alpha: render::effect::BlendFactor,
+        dst_alpha: render::effect::BlendFactor,
+    ) {
+        self.effect.color_operation = render::effect::ColorOperation::Blend {
+            color_equation,
+            alpha_equation,
+            src_color,
+            dst_color,
+            src_alpha,
+            dst_alpha,
+        };
+        self.effect.color_operation.apply();
+    }
+
+    /// Combine the colors of drawn fragments with the colors in the render
+    /// target using a bitwise logic operation instead of [blending](Self::set_blend),
+    /// e.g. [`Xor`](render::effect::LogicOperation::Xor) for a cursor which is
+    /// visible on any background and can be erased by drawing it again.
+    ///
+    /// Blending is re-enabled by calling [`set_blend`](Self::set_blend).
+    #[doc(alias = "C3D_ColorLogicOp")]
+    pub fn set_color_logic_op(&mut self, operation: render::effect::LogicOperation) {
+        self.effect.color_operation = render::effect::ColorOperation::Logic(operation);
+        self.effect.color_operation.apply();
+    }
+
+    /// Set the constant color used by the `Constant*` [blend factors](render::effect::BlendFactor),
+    /// as a 32-bit RGBA value.
+    #[doc(alias = "C3D_BlendingColor")]
+    pub fn set_blend_color(&mut self, rgba_color: u32) {
+        // The GPU stores the red component in the lowest byte.
+        self.effect.blend_color = rgba_color.swap_bytes();
+        unsafe { citro3d_sys::C3D_BlendingColor(self.effect.blend_color) };
+    }
+
+    /// Configure the stencil test, which requires a render target with a
+    /// [`Depth24Stencil8`](render::DepthFormat::Depth24Stencil8) buffer. If
+    /// `enabled`, fragments are only drawn if `reference & input_mask` passes
+    /// the comparison `function` with `stencil & input_mask`, where `stencil`
+    /// is the value in the stencil buffer. `write_mask` selects which bits of
+    /// the stencil buffer can be changed by the [stencil operations](Self::set_stencil_op).
+    ///
+    /// The stencil test is disabled by default.
+    #[doc(alias = "C3D_StencilTest")]
+    pub fn set_stencil_test(
+        &mut self,
+        enabled: bool,
+        function: render::effect::TestFunction,
+        reference: u8,
+        input_mask: u8,
+        write_mask: u8,
+    ) {
+        self.effect.stencil_test = (enabled, function, reference, input_mask, write_mask);
+        unsafe {
+            citro3d_sys::C3D_StencilTest(
+                enabled,
+                function as ctru_sys::GPU_TESTFUNC,
+                reference.into(),
+                input_mask.into(),
+                write_mask.into(),
+            );
+        }
+    }
+
+    /// Set how the stencil buffer is updated when a fragment fails the
+    /// [stencil test](Self::set_stencil_test), passes it but fails the
+    /// [depth test](Self::set_depth_test), or passes both, e.g. to
+    /// [`Replace`](render::effect::StencilOperation::Replace) the stencil
+    /// value with the reference value wherever a mask shape is drawn.
+    ///
+    /// By default, all three [`Keep`](render::effect::StencilOperation::Keep)
+    /// the stencil value.
+    #[doc(alias = "C3D_StencilOp")]
+    pub fn set_stencil_op(
+        &mut self,
+        stencil_fail: render::effect::StencilOperation,
+        depth_fail: render::effect::StencilOperation,
+        pass: render::effect::StencilOperation,
+    ) {
+        self.effect.stencil_op = [stencil_fail, depth_fail, pass];
+        unsafe {
+            citro3d_sys::C3D_StencilOp(
+                stencil_fail as ctru_sys::GPU_STENCILOP,
+                depth_fail as ctru_sys::GPU_STENCILOP,
+                pass as ctru_sys::GPU_STENCILOP,
+            );
+        }
+    }
+
+    /// Configure the alpha test. If `enabled`, fragments are only drawn if
+    /// their alpha value passes the comparison `function` with `reference`,
+    /// e.g. `set_alpha_test(true, TestFunction::Greater, 127)` to draw cutout
+    /// sprites or foliage without sorting them for blending.
+    ///
+    /// The alpha test is disabled by default.
+    #[doc(alias = "C3D_AlphaTest")]
+    pub fn set_alpha_test(
+        &mut self,
+        enabled: bool,
+        function: render::effect::TestFunction,
+        reference: u8,
+    ) {
+        self.effect.alpha_test = (enabled, function, reference);
+        unsafe {
+            citro3d_sys::C3D_AlphaTest(
+                enabled,
+                function as ctru_sys::GPU_TESTFUNC,
+                reference.into(),
+            );
+        }
+    }
 
+    /// Configure the early depth test, which discards fragments before they
+    /// are shaded, using a separate coarse depth buffer. This can reduce the
+    /// fragment load of heavy scenes, e.g. after filling the depth buffer in a
+    /// pre-pass (see [`render::DepthTarget`]).
+    ///
+    /// If `enabled`, fragments pass the test if their depth passes the
+    /// comparison `function` with the value in the early depth buffer.
+    /// `reference` is the depth in [0, 1] the early depth buffer is cleared to.
+    ///
+    /// The early depth test is disabled by default.
+    #[doc(alias = "C3D_EarlyDepthTest")]
+    pub fn set_early_depth_test(
+        &mut self,
+        enabled: bool,
+        function: render::effect::EarlyDepthFunction,
+        reference: f32,
+    ) {
+        let reference = render::DepthFormat::Depth24.clear_value(reference, 0);
+        self.effect.early_depth_test = (enabled, function, reference);
         unsafe {
-            citro3d_sys::C3D_FrameEnd(0);
+            citro3d_sys::C3D_EarlyDepthTest(
+                enabled,
+                function as ctru_sys::GPU_EARLYDEPTHFUNC,
+                reference,
+            );
         }
     }
 
+    /// Submit all drawing done so far in the current frame to the GPU. This
+    /// must be called within [`render_frame_with`](Self::render_frame_with).
+    ///
+    /// This flushes the command list and queues it for the GPU without waiting
+    /// for it to run. Draws issued after the split are queued behind it, so they
+    /// see the completed render target. This is needed to sample a
+    /// [texture target](render::TextureTarget) later in the same frame it was
+    /// drawn to, since the GPU would otherwise read the texture before drawing
+    /// into it has finished. The CPU must still not access the texture until
+    /// the GPU is done with the frame, e.g. once the next frame begins.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::render::ColorFormat;
+    /// # use citro3d::texture::Unit;
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let target = instance
+    ///     .texture_target(64, 64, ColorFormat::RGBA8, None)
+    ///     .unwrap();
+    ///
+    /// instance.render_frame_with(|frame| {
+    ///     frame.select_render_target(&target).unwrap();
+    ///     // Draw into the texture...
+    ///
+    ///     frame.split_frame();
+    ///     target.bind(frame, Unit::Unit0);
+    ///     // Draw to the screen, sampling the texture...
+    /// });
+    /// ```
+    #[doc(alias = "C3D_FrameSplit")]
+    pub fn split_frame(&mut self) {
+        unsafe { citro3d_sys::C3D_FrameSplit(0) };
+    }
+
+    /// Set a callback to run at the end of every frame, once the GPU has
+    /// finished rendering it, e.g. to synchronize audio or streaming with
+    /// rendering. This replaces any previously set callback.
+    ///
+    /// The callback runs on the thread handling GPU events rather than the
+    /// thread rendering the frame, so it must be [`Send`]. It must not set or
+    /// clear the callback itself.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::sync::Arc;
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let frames = Arc::new(AtomicU32::new(0));
+    /// let counter = Arc::clone(&frames);
+    /// instance.set_frame_end_callback(move || {
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// ```
+    #[doc(alias = "C3D_FrameEndHook")]
+    pub fn set_frame_end_callback(&mut self, callback: impl FnMut() + Send + 'static) {
+        unsafe extern "C" fn trampoline(_: *mut libc::c_void) {
+            if let Some(callback) = FRAME_END_CALLBACK.lock().unwrap().as_mut() {
+                callback();
+            }
+        }
+
+        *FRAME_END_CALLBACK.lock().unwrap() = Some(Box::new(callback));
+        unsafe { citro3d_sys::C3D_FrameEndHook(Some(trampoline), std::ptr::null_mut()) };
+    }
+
+    /// Remove the callback set with
+    /// [`set_frame_end_callback`](Self::set_frame_end_callback), if any.
+    #[doc(alias = "C3D_FrameEndHook")]
+    pub fn clear_frame_end_callback(&mut self) {
+        unsafe { citro3d_sys::C3D_FrameEndHook(None, std::ptr::null_mut()) };
+        *FRAME_END_CALLBACK.lock().unwrap() = None;
+    }
+
+    /// Limit how often frames are rendered, e.g. to 30 FPS for a game which
+    /// can't keep up with the display's 60 FPS. Returns the previous limit.
+    ///
+    /// [`render_frame_with`](Self::render_frame_with) waits before starting a
+    /// new frame to keep within the limit. The default is 60 FPS, the refresh
+    /// rate of the screens. Only values above `0.0` and up to `60.0` change the
+    /// limit, so e.g. `set_frame_rate(0.0)` just returns the current limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let previous = instance.set_frame_rate(30.0);
+    /// // ...
+    /// instance.set_frame_rate(previous);
+    /// ```
+    #[doc(alias = "C3D_FrameRate")]
+    pub fn set_frame_rate(&mut self, fps: f32) -> f32 {
+        unsafe { citro3d_sys::C3D_FrameRate(fps) }
+    }
+
+    /// Get timing and resource usage statistics for the last rendered frame.
+    #[doc(alias = "C3D_GetDrawingTime")]
+    #[doc(alias = "C3D_GetProcessingTime")]
+    #[doc(alias = "C3D_GetCmdBufUsage")]
+    pub fn frame_stats(&self) -> render::FrameStats {
+        // `citro3d` measures times in milliseconds.
+        let millis = |ms: f32| Duration::from_secs_f32(ms.max(0.0) / 1000.0);
+
+        unsafe {
+            render::FrameStats {
+                drawing_time: millis(citro3d_sys::C3D_GetDrawingTime()),
+                processing_time: millis(citro3d_sys::C3D_GetProcessingTime()),
+                cmd_buf_usage: citro3d_sys::C3D_GetCmdBufUsage(),
+            }
+        }
+    }
+
+    /// Get the number of frames which have been displayed on the given screen.
+    /// If this advances by more than one between two rendered frames, frames
+    /// were dropped in between.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::render::ScreenKind;
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let before = instance.frame_counter(ScreenKind::Top);
+    /// instance.render_frame_with(|_| {});
+    /// let dropped = instance
+    ///     .frame_counter(ScreenKind::Top)
+    ///     .wrapping_sub(before)
+    ///     .saturating_sub(1);
+    /// ```
+    #[doc(alias = "C3D_FrameCounter")]
+    pub fn frame_counter(&self, screen: render::ScreenKind) -> u32 {
+        unsafe { citro3d_sys::C3D_FrameCounter(screen as _) }
+    }
+
+    /// Block until the next vertical blank of the given screen, e.g. to
+    /// synchronize game logic with the display's refresh.
+    #[doc(alias = "gspWaitForVBlank")]
+    #[doc(alias = "gspWaitForEvent")]
+    pub fn wait_for_vblank(&self, screen: render::ScreenKind) {
+        let event = match screen {
+            render::ScreenKind::Top => ctru_sys::GSPGPU_EVENT_VBlank0,
+            render::ScreenKind::Bottom => ctru_sys::GSPGPU_EVENT_VBlank1,
+        };
+        unsafe { ctru_sys::gspWaitForEvent(event, true) };
+    }
+
     /// Get the buffer info being used, if it exists. Note that the resulting
     /// [`buffer::Info`] is copied from the one currently in use.
     #[doc(alias = "C3D_GetBufInfo")]
@@ -178,7 +977,7 @@ impl Instance {
     /// Set the buffer info to use for any following draw calls.
     #[doc(alias = "C3D_SetBufInfo")]
     pub fn set_buffer_info(&mut self, buffer_info: &buffer::Info) {
-        let raw: *const _ = &buffer_info.0;
+        let raw: *const _ = &buffer_info.raw;
         // SAFETY: C3D_SetBufInfo actually copies the pointee instead of mutating it.
         unsafe { citro3d_sys::C3D_SetBufInfo(raw.cast_mut()) };
     }
@@ -199,62 +998,73 @@ impl Instance {
         unsafe { citro3d_sys::C3D_SetAttrInfo(raw.cast_mut()) };
     }
 
-    /// Render primitives from the current vertex array buffer.
-    #[doc(alias = "C3D_DrawArrays")]
-    pub fn draw_arrays(&mut self, primitive: buffer::Primitive, vbo_data: buffer::Slice) {
-        self.set_buffer_info(vbo_data.info());
-
-        // TODO: should we also require the attrib info directly here?
+    /// Use the given [`shader::Program`] for subsequent draw calls. The
+    /// instance keeps the program alive while it is bound, since `citro3d`
+    /// only stores a pointer to it.
+    #[doc(alias = "C3D_BindProgram")]
+    pub fn bind_program(&mut self, program: &Rc<shader::Program>) {
+        // SAFETY: AFAICT C3D_BindProgram just copies pointers from the given program,
+        // instead of mutating the pointee in any way that would cause UB
         unsafe {
-            citro3d_sys::C3D_DrawArrays(
-                primitive as ctru_sys::GPU_Primitive_t,
-                vbo_data.index(),
-                vbo_data.len(),
-            );
+            citro3d_sys::C3D_BindProgram(program.as_raw().cast_mut());
         }
+        self.program = Some(Rc::clone(program));
     }
-    /// Indexed drawing
-    ///
-    /// Draws the vertices in `buf` indexed by `indices`. `indices` must be linearly allocated
-    ///
-    /// # Safety
-    // TODO: #41 might be able to solve this:
-    /// If `indices` goes out of scope before the current frame ends it will cause a
-    /// use-after-free (possibly by the GPU).
-    ///
-    /// # Panics
-    ///
-    /// If the given index buffer is too long to have its length converted to `i32`.
-    #[doc(alias = "C3D_DrawElements")]
-    pub unsafe fn draw_elements<I: Index>(
-        &mut self,
-        primitive: buffer::Primitive,
-        vbo_data: buffer::Slice,
-        indices: &Indices<'_, I>,
-    ) {
-        self.set_buffer_info(vbo_data.info());
 
-        let indices = &indices.buffer;
-        let elements = indices.as_ptr().cast();
+    /// Get the currently bound [`shader::Program`], if any.
+    pub fn program(&self) -> Option<&Rc<shader::Program>> {
+        self.program.as_ref()
+    }
 
-        unsafe {
-            citro3d_sys::C3D_DrawElements(
-                primitive as ctru_sys::GPU_Primitive_t,
-                indices.len().try_into().unwrap(),
-                // flag bit for short or byte
-                I::TYPE,
-                elements,
-            );
+    /// Take a snapshot of the state used for drawing: the attribute and buffer
+    /// info, the bound shader program, the texture combiners and the effect
+    /// settings made through the instance (e.g. with
+    /// [`set_depth_map`](Self::set_depth_map)).
+    ///
+    /// This lets middleware, e.g. a debug overlay, draw with its own state and
+    /// then [restore](Self::restore_state) the application's state afterwards.
+    pub fn save_state(&self) -> render::State {
+        render::State {
+            attr_info: self.attr_info(),
+            buffer_info: self.buffer_info(),
+            program: self.program.clone(),
+            texenvs: std::array::from_fn(|i| unsafe { *citro3d_sys::C3D_GetTexEnv(i as _) }),
+            effect: self.effect,
         }
     }
 
-    /// Use the given [`shader::Program`] for subsequent draw calls.
-    pub fn bind_program(&mut self, program: &shader::Program) {
-        // SAFETY: AFAICT C3D_BindProgram just copies pointers from the given program,
-        // instead of mutating the pointee in any way that would cause UB
-        unsafe {
-            citro3d_sys::C3D_BindProgram(program.as_raw().cast_mut());
+    /// Send the effect settings made through the instance (e.g. with
+    /// [`set_depth_test`](Self::set_depth_test)) to `citro3d` again. This
+    /// undoes any changes made to them directly through `citro3d-sys`, e.g. by
+    /// a library which draws with its own state, like `citro2d`.
+    pub fn reapply_effects(&self) {
+        self.effect.apply();
+    }
+
+    /// Restore a snapshot of the state taken with [`save_state`](Self::save_state).
+    /// The snapshot keeps its shader program alive, so it can always be bound
+    /// again.
+    #[doc(alias = "C3D_SetTexEnv")]
+    pub fn restore_state(&mut self, state: &render::State) {
+        if let Some(attr_info) = &state.attr_info {
+            self.set_attr_info(attr_info);
+        }
+        if let Some(buffer_info) = &state.buffer_info {
+            self.set_buffer_info(buffer_info);
         }
+
+        if let Some(program) = &state.program {
+            self.bind_program(program);
+        }
+
+        for (i, texenv) in state.texenvs.iter().enumerate() {
+            let raw: *const _ = texenv;
+            // SAFETY: C3D_SetTexEnv copies the pointee instead of mutating it.
+            unsafe { citro3d_sys::C3D_SetTexEnv(i as _, raw.cast_mut()) };
+        }
+
+        self.effect = state.effect;
+        self.effect.apply();
     }
 
     /// Binds a new [`LightEnv`], returning the previous one (if present).
@@ -278,77 +1088,119 @@ impl Instance {
         old_env
     }
 
-    pub fn light_env(&self) -> Option<Pin<&LightEnv>> {
-        self.light_env.as_ref().map(|env| env.as_ref())
-    }
-
-    pub fn light_env_mut(&mut self) -> Option<Pin<&mut LightEnv>> {
-        self.light_env.as_mut().map(|env| env.as_mut())
-    }
-
-    /// Bind a uniform to the given `index` in the vertex shader for the next draw call.
+    /// Bind a texture to the given texture unit, to be sampled by subsequent
+    /// draw calls. Returns the texture previously bound to the unit (if any).
+    ///
+    /// The instance keeps a reference to the texture until another texture is
+    /// bound to the same unit, and then until the GPU is done with the frame
+    /// being rendered, so it can't be freed while the GPU may still be reading
+    /// from it.
     ///
     /// # Example
     ///
     /// ```
     /// # let _runner = test_runner::GdbRunner::default();
-    /// # use citro3d::uniform;
-    /// # use citro3d::math::Matrix4;
-    /// #
+    /// # use std::rc::Rc;
+    /// # use citro3d::texture::{ColorFormat, Texture, Unit};
     /// # let mut instance = citro3d::Instance::new().unwrap();
-    /// let idx = uniform::Index::from(0);
-    /// let mtx = Matrix4::identity();
-    /// instance.bind_vertex_uniform(idx, &mtx);
+    /// let texture = Rc::new(Texture::new(64, 64, ColorFormat::Rgba8).unwrap());
+    /// instance.bind_texture(Unit::Unit0, &texture);
     /// ```
-    pub fn bind_vertex_uniform(&mut self, index: uniform::Index, uniform: impl Into<Uniform>) {
-        uniform.into().bind(self, shader::Type::Vertex, index);
+    #[doc(alias = "C3D_TexBind")]
+    pub fn bind_texture(
+        &mut self,
+        unit: texture::Unit,
+        texture: &Rc<texture::Texture>,
+    ) -> Option<Rc<texture::Texture>> {
+        unsafe { citro3d_sys::C3D_TexBind(unit.index() as libc::c_int, texture.as_raw()) };
+        let previous = self.textures[unit.index()].replace(Rc::clone(texture));
+        self.keep_alive_for_frame(previous.as_ref());
+        previous
     }
 
-    /// Bind a uniform to the given `index` in the geometry shader for the next draw call.
-    ///
-    /// # Example
+    /// Unbind the texture from the given texture unit, returning it (if any).
     ///
-    /// ```
-    /// # let _runner = test_runner::GdbRunner::default();
-    /// # use citro3d::uniform;
-    /// # use citro3d::math::Matrix4;
-    /// #
-    /// # let mut instance = citro3d::Instance::new().unwrap();
-    /// let idx = uniform::Index::from(0);
-    /// let mtx = Matrix4::identity();
-    /// instance.bind_geometry_uniform(idx, &mtx);
-    /// ```
-    pub fn bind_geometry_uniform(&mut self, index: uniform::Index, uniform: impl Into<Uniform>) {
-        uniform.into().bind(self, shader::Type::Geometry, index);
+    /// The instance keeps a reference to the texture until the GPU is done with
+    /// the frame being rendered, so the returned texture can be dropped right
+    /// away.
+    #[doc(alias = "C3D_TexBind")]
+    pub fn unbind_texture(&mut self, unit: texture::Unit) -> Option<Rc<texture::Texture>> {
+        unsafe { citro3d_sys::C3D_TexBind(unit.index() as libc::c_int, std::ptr::null_mut()) };
+        let previous = self.textures[unit.index()].take();
+        self.keep_alive_for_frame(previous.as_ref());
+        previous
     }
 
-    /// Retrieve the [`TexEnv`] for the given stage, initializing it first if necessary.
+    /// Keep a texture which is no longer bound alive until the next frame
+    /// begins, since draw calls already submitted may still sample it.
+    fn keep_alive_for_frame(&mut self, texture: Option<&Rc<texture::Texture>>) {
+        if let Some(texture) = texture {
+            self.frame_resources.push(Rc::clone(texture) as Rc<dyn Any>);
+        }
+    }
+
+    /// The texture bound to the given texture unit with
+    /// [`bind_texture`](Self::bind_texture), if any.
+    pub fn bound_texture(&self, unit: texture::Unit) -> Option<&Rc<texture::Texture>> {
+        self.textures[unit.index()].as_ref()
+    }
+
+    pub fn light_env(&self) -> Option<Pin<&LightEnv>> {
+        self.light_env.as_ref().map(|env| env.as_ref())
+    }
+
+    pub fn light_env_mut(&mut self) -> Option<Pin<&mut LightEnv>> {
+        self.light_env.as_mut().map(|env| env.as_mut())
+    }
+
+    /// Write raw GPU register commands into the current command buffer, for
+    /// hardware features which don't have a safe API yet. This should be
+    /// called while rendering a frame, e.g. within [`Instance::render_frame_with`].
+    ///
+    /// `citro3d` only sends the state it tracks to the GPU right before each
+    /// draw call, so raw writes to registers it manages may be overwritten by
+    /// the next draw. Any tracked state selected by `invalidate` is marked as
+    /// dirty afterwards, so `citro3d` sends it again.
     ///
     /// # Example
     ///
     /// ```
-    /// # use citro3d::texenv;
     /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::command::Invalidate;
     /// # let mut instance = citro3d::Instance::new().unwrap();
-    /// let stage0 = texenv::Stage::new(0).unwrap();
-    /// let texenv0 = instance.texenv(stage0);
+    /// instance.render_frame_with(|instance| unsafe {
+    ///     instance.raw_commands(Invalidate::empty(), |cmd| {
+    ///         // GPUREG_FRAGOP_ALPHA_TEST: disable the alpha test
+    ///         cmd.write(0x104, 0);
+    ///     });
+    /// });
     /// ```
-    #[doc(alias = "C3D_GetTexEnv")]
-    #[doc(alias = "C3D_TexEnvInit")]
-    pub fn texenv(&mut self, stage: texenv::Stage) -> &mut texenv::TexEnv {
-        let texenv = &mut self.texenvs[stage.0];
-        texenv.get_or_init(|| TexEnv::new(stage));
-        // We have to do this weird unwrap to get a mutable reference,
-        // since there is no `get_mut_or_init` or equivalent
-        texenv.get_mut().unwrap()
+    ///
+    /// # Safety
+    ///
+    /// The written commands must be valid for the GPU, and must not leave it
+    /// in a state which conflicts with what `citro3d` (or this crate) expects,
+    /// other than the state selected by `invalidate`. Invalid commands can hang
+    /// the GPU or make it access arbitrary memory.
+    #[doc(alias = "GPUCMD_Add")]
+    pub unsafe fn raw_commands(
+        &mut self,
+        invalidate: command::Invalidate,
+        f: impl FnOnce(&mut command::RawCommands),
+    ) {
+        f(&mut command::RawCommands::new());
+        invalidate.apply();
     }
 }
 
-// This only exists to be an alias, which admittedly is kinda silly. The default
-// impl should be equivalent though, since RenderQueue has a drop impl too.
+// `C3D_Fini` is called once the `RenderQueue` is dropped, which may be after the
+// instance itself (e.g. if render targets are still alive). The frame end
+// callback belongs to the instance though, so it is removed here.
 impl Drop for Instance {
     #[doc(alias = "C3D_Fini")]
-    fn drop(&mut self) {}
+    fn drop(&mut self) {
+        self.clear_frame_end_callback();
+    }
 }
 
 impl Drop for RenderQueue {
@@ -382,4 +1234,72 @@ mod tests {
         drop(instance);
         drop(target);
     }
+
+    #[test]
+    fn begin_frame() {
+        let gfx = Gfx::new().unwrap();
+        let screen = gfx.top_screen.borrow_mut();
+
+        let mut instance = Instance::new().unwrap();
+        let target = instance.render_target(10, 10, screen, None).unwrap();
+
+        let mut frame = instance.begin_frame(render::FrameFlags::SYNC_DRAW).unwrap();
+        frame.select_render_target(&target).unwrap();
+        frame.end();
+    }
+
+    #[test]
+    fn render_to_targets() {
+        let gfx = Gfx::new().unwrap();
+
+        let mut instance = Instance::new().unwrap();
+        let top = instance
+            .render_target(10, 10, gfx.top_screen.borrow_mut(), None)
+            .unwrap();
+        let bottom = instance
+            .render_target(10, 10, gfx.bottom_screen.borrow_mut(), None)
+            .unwrap();
+
+        let mut drawn = Vec::new();
+        instance
+            .render_to_targets(&[&top, &bottom], |_, index| drawn.push(index))
+            .unwrap();
+
+        assert_eq!(drawn, [0, 1]);
+    }
+
+    #[test]
+    fn render_target_info() {
+        use render::RenderTarget;
+
+        let gfx = Gfx::new().unwrap();
+        let screen = gfx.top_screen.borrow_mut();
+
+        let instance = Instance::new().unwrap();
+        let target = instance
+            .render_target(16, 32, screen, Some(render::DepthFormat::Depth16))
+            .unwrap();
+
+        assert_eq!((target.width(), target.height()), (16, 32));
+        assert!(matches!(
+            target.depth_format(),
+            Some(render::DepthFormat::Depth16)
+        ));
+    }
+
+    #[test]
+    fn save_and_restore_state() {
+        let mut instance = Instance::new().unwrap();
+        instance.set_depth_map(true, -1.0, 0.0001);
+        instance.set_shadow_sampling(true, 0.5);
+        let state = instance.save_state();
+
+        // e.g. middleware drawing with its own settings
+        instance.set_depth_map(false, 1.0, 0.0);
+        instance.set_shadow_sampling(false, 0.0);
+
+        instance.restore_state(&state);
+        assert_eq!(instance.effect.depth_map, (true, -1.0, 0.0001));
+        assert_eq!(instance.effect.shadow_sampling, (true, 0.5));
+    }
 }
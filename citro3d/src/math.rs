@@ -1,19 +1,35 @@
 //! Safe wrappers for working with matrix and vector types provided by `citro3d`.
 
-// TODO: bench FFI calls into `inline statics` generated by bindgen, vs
-// reimplementing some of those calls. Many of them are pretty trivial impls
+// The hottest operations (matrix multiplication and translation, and most FVec
+// math) are reimplemented in Rust rather than calling the `static inline`
+// wrappers generated by bindgen, which can't be inlined across the FFI boundary.
+// See `examples/math-bench.rs` for a comparison.
 
+mod bounds;
+mod curve;
+mod euler;
+mod frustum;
 mod fvec;
 mod matrix;
 mod ops;
 mod projection;
+mod quat;
+mod ray;
+mod stack;
 
+pub use bounds::{Aabb, Sphere};
+pub use curve::{ArcLength, CatmullRom, CubicBezier, Curve, QuadraticBezier};
+pub use euler::RotationOrder;
+pub use frustum::Frustum;
 pub use fvec::{FVec, FVec3, FVec4};
 pub use matrix::Matrix4;
 pub use projection::{
     AspectRatio, ClipPlanes, CoordinateOrientation, Orthographic, Perspective, Projection,
-    ScreenOrientation, StereoDisplacement,
+    ScreenOrientation, StereoDisplacement, Viewport,
 };
+pub use quat::FQuat;
+pub use ray::Ray;
+pub use stack::MatrixStack;
 
 /// A 4-vector of `u8`s.
 ///
@@ -43,16 +59,50 @@ impl IVec {
     pub fn w(self) -> u8 {
         (self.0 >> 24) as u8
     }
+
+    /// Pack a vector of normalized values (`0.0..=1.0`) into bytes, e.g. an
+    /// RGBA color for a vertex attribute with
+    /// [`attrib::Format::UnsignedByte`](crate::attrib::Format::UnsignedByte).
+    /// Values outside the range are clamped.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{FVec4, IVec};
+    /// let color = IVec::from_normalized(FVec4::new(1.0, 0.5, 0.0, 2.0));
+    /// assert_eq!(color, IVec::new(255, 128, 0, 255));
+    /// ```
+    pub fn from_normalized(v: FVec4) -> Self {
+        let [x, y, z, w] =
+            [v.x(), v.y(), v.z(), v.w()].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        Self::new(x, y, z, w)
+    }
+
+    /// Unpack the bytes into a vector of normalized values (`0.0..=1.0`).
+    /// This is the inverse of [`from_normalized`](Self::from_normalized).
+    pub fn to_normalized(self) -> FVec4 {
+        let [x, y, z, w] = [self.x(), self.y(), self.z(), self.w()].map(|c| f32::from(c) / 255.0);
+        FVec4::new(x, y, z, w)
+    }
 }
 
-/// A quaternion, internally represented the same way as [`FVec`].
-#[allow(dead_code)]
-#[doc(alias = "C3D_FQuat")]
-pub struct FQuat(citro3d_sys::C3D_FQuat);
+#[cfg(feature = "mint")]
+impl From<mint::Vector4<u8>> for IVec {
+    fn from(value: mint::Vector4<u8>) -> Self {
+        Self::new(value.x, value.y, value.z, value.w)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<IVec> for mint::Vector4<u8> {
+    fn from(value: IVec) -> Self {
+        [value.x(), value.y(), value.z(), value.w()].into()
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use super::IVec;
+    use super::*;
 
     #[test]
     fn ivec_getters_work() {
@@ -62,4 +112,44 @@ mod tests {
         assert_eq!(iv.z(), 3);
         assert_eq!(iv.w(), 4);
     }
+
+    #[test]
+    fn ivec_normalized() {
+        let iv = IVec::new(0, 64, 128, 255);
+        assert_eq!(IVec::from_normalized(iv.to_normalized()), iv);
+        assert_eq!(
+            IVec::from_normalized(FVec4::new(-1.0, 0.0, 1.0, 2.0)),
+            IVec::new(0, 0, 255, 255)
+        );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let matrix = Matrix4::identity().translated(1.0, 2.0, 3.0);
+        let json = serde_json::to_string(&matrix).unwrap();
+        assert!(json.starts_with("[[1.0,0.0,0.0,1.0],"));
+        assert_eq!(serde_json::from_str::<Matrix4>(&json).unwrap(), matrix);
+
+        let vec = FVec3::new(1.0, 2.0, 3.0);
+        let json = serde_json::to_string(&vec).unwrap();
+        assert_eq!(json, "[1.0,2.0,3.0]");
+        assert_eq!(serde_json::from_str::<FVec3>(&json).unwrap(), vec);
+
+        let quat = FQuat::from_axis_angle(FVec3::new(0.0, 1.0, 0.0), 1.0);
+        let json = serde_json::to_string(&quat).unwrap();
+        assert_eq!(serde_json::from_str::<FQuat>(&json).unwrap(), quat);
+
+        let projection = Projection::perspective(
+            1.0,
+            AspectRatio::TopScreen,
+            ClipPlanes {
+                near: 0.1,
+                far: 100.0,
+            },
+        )
+        .coordinates(CoordinateOrientation::LeftHanded);
+        let json = serde_json::to_string(&projection).unwrap();
+        let deserialized: Projection<Perspective> = serde_json::from_str(&json).unwrap();
+        assert_eq!(Matrix4::from(deserialized), Matrix4::from(projection));
+    }
 }
@@ -0,0 +1,224 @@
+//! Bounding volumes, for culling and simple collision tests.
+
+use super::{FVec3, Matrix4};
+
+/// An axis-aligned bounding box.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::{Aabb, FVec3};
+/// let a = Aabb::new(FVec3::splat(0.0), FVec3::splat(1.0));
+/// let b = Aabb::new(FVec3::splat(2.0), FVec3::splat(3.0));
+///
+/// assert!(!a.intersects(&b));
+/// assert!(a.merge(&b).contains(&b));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    /// The corner with the smallest coordinates.
+    pub min: FVec3,
+    /// The corner with the largest coordinates.
+    pub max: FVec3,
+}
+
+impl Aabb {
+    /// Create a box from its corners. `min` should be less than or equal to
+    /// `max` in every component.
+    pub fn new(min: FVec3, max: FVec3) -> Self {
+        Self { min, max }
+    }
+
+    /// The smallest box containing all of `points`, or [`None`] if there are
+    /// no points.
+    pub fn from_points(points: impl IntoIterator<Item = FVec3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |aabb, point| Self {
+            min: aabb.min.min(point),
+            max: aabb.max.max(point),
+        }))
+    }
+
+    /// The center of the box.
+    pub fn center(&self) -> FVec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Half the size of the box along each axis.
+    pub fn half_extents(&self) -> FVec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// The eight corners of the box.
+    pub fn corners(&self) -> [FVec3; 8] {
+        let (min, max) = (self.min, self.max);
+        std::array::from_fn(|i| {
+            FVec3::new(
+                if i & 1 == 0 { min.x() } else { max.x() },
+                if i & 2 == 0 { min.y() } else { max.y() },
+                if i & 4 == 0 { min.z() } else { max.z() },
+            )
+        })
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Whether `point` is inside the box.
+    pub fn contains_point(&self, point: FVec3) -> bool {
+        self.min.max(point) == point && self.max.min(point) == point
+    }
+
+    /// Whether `other` is entirely inside the box.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Whether the boxes overlap.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x() <= other.max.x()
+            && self.max.x() >= other.min.x()
+            && self.min.y() <= other.max.y()
+            && self.max.y() >= other.min.y()
+            && self.min.z() <= other.max.z()
+            && self.max.z() >= other.min.z()
+    }
+
+    /// The smallest axis-aligned box containing this box after it is
+    /// transformed by `matrix`, which should be an affine transformation.
+    pub fn transform(&self, matrix: &Matrix4) -> Self {
+        let corners = self.corners().map(|corner| transform_point(matrix, corner));
+        // There are always 8 corners.
+        Self::from_points(corners).unwrap()
+    }
+}
+
+/// A bounding sphere.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::{FVec3, Matrix4, Sphere};
+/// let sphere = Sphere::new(FVec3::splat(0.0), 1.0);
+/// let moved = sphere.transform(&Matrix4::identity().translated(3.0, 0.0, 0.0));
+///
+/// assert!(!sphere.intersects(&moved));
+/// assert!(moved.contains_point(FVec3::new(3.5, 0.0, 0.0)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sphere {
+    /// The center of the sphere.
+    pub center: FVec3,
+    /// The radius of the sphere.
+    pub radius: f32,
+}
+
+impl Sphere {
+    /// Create a sphere from its center and radius.
+    pub fn new(center: FVec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// The smallest sphere containing `aabb`.
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        Self::new(aabb.center(), aabb.half_extents().magnitude())
+    }
+
+    /// A sphere containing both spheres.
+    pub fn merge(&self, other: &Self) -> Self {
+        let distance = self.center.distance(other.center);
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+
+        let radius = (distance + self.radius + other.radius) / 2.0;
+        // Move from `self` towards `other` so both edges are covered.
+        let direction = (other.center - self.center) / distance;
+        Self::new(self.center + direction * (radius - self.radius), radius)
+    }
+
+    /// Whether `point` is inside the sphere.
+    pub fn contains_point(&self, point: FVec3) -> bool {
+        self.center.distance(point) <= self.radius
+    }
+
+    /// Whether `other` is entirely inside the sphere.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.center.distance(other.center) + other.radius <= self.radius
+    }
+
+    /// Whether the spheres overlap.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.center.distance(other.center) <= self.radius + other.radius
+    }
+
+    /// A sphere containing this sphere after it is transformed by `matrix`,
+    /// which should be an affine transformation. With non-uniform scaling,
+    /// the radius is scaled by the largest factor.
+    pub fn transform(&self, matrix: &Matrix4) -> Self {
+        let rows = matrix.rows_xyzw();
+        let scale = (0..3)
+            .map(|column| (0..3).map(|row| rows[row][column].powi(2)).sum::<f32>())
+            .fold(0.0, f32::max)
+            .sqrt();
+
+        Self::new(transform_point(matrix, self.center), self.radius * scale)
+    }
+}
+
+/// Transform `point` by an affine `matrix`.
+fn transform_point(matrix: &Matrix4, point: FVec3) -> FVec3 {
+    let out = matrix * point;
+    FVec3::new(out.x(), out.y(), out.z())
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn aabb() {
+        let aabb = Aabb::from_points([
+            FVec3::new(1.0, -1.0, 0.0),
+            FVec3::new(-1.0, 2.0, 0.5),
+            FVec3::new(0.0, 0.0, -3.0),
+        ])
+        .unwrap();
+        assert_eq!(aabb.min, FVec3::new(-1.0, -1.0, -3.0));
+        assert_eq!(aabb.max, FVec3::new(1.0, 2.0, 0.5));
+        assert!(aabb.contains_point(FVec3::splat(0.0)));
+        assert!(!aabb.contains_point(FVec3::splat(1.5)));
+        assert!(Aabb::from_points([]).is_none());
+
+        let rotated = aabb.transform(&Matrix4::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        assert_abs_diff_eq!(rotated.min, FVec3::new(-2.0, -1.0, -3.0));
+        assert_abs_diff_eq!(rotated.max, FVec3::new(1.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn sphere() {
+        let a = Sphere::new(FVec3::splat(0.0), 1.0);
+        let b = Sphere::new(FVec3::new(4.0, 0.0, 0.0), 1.0);
+        let merged = a.merge(&b);
+        assert_abs_diff_eq!(merged.center, FVec3::new(2.0, 0.0, 0.0));
+        assert_abs_diff_eq!(merged.radius, 3.0);
+        assert!(merged.contains(&a) && merged.contains(&b));
+        assert_eq!(merged.merge(&a), merged);
+
+        let scaled = a.transform(&Matrix4::diagonal(1.0, 3.0, 2.0, 1.0));
+        assert_abs_diff_eq!(scaled.radius, 3.0);
+    }
+}
@@ -0,0 +1,249 @@
+//! Evaluation of Bézier and Catmull-Rom curves, e.g. for camera paths,
+//! projectile arcs and 2D path drawing.
+
+use super::FVec3;
+
+/// A parametric curve through 3D space, defined for `t` in `0.0..=1.0`.
+pub trait Curve {
+    /// The position on the curve at `t`.
+    fn position(&self, t: f32) -> FVec3;
+
+    /// The derivative of the curve at `t`. This is not normalized, so its
+    /// magnitude is the speed at which [`Curve::position`] moves with `t`.
+    fn tangent(&self, t: f32) -> FVec3;
+}
+
+/// A quadratic Bézier curve, with a single control point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuadraticBezier {
+    pub start: FVec3,
+    pub control: FVec3,
+    pub end: FVec3,
+}
+
+impl Curve for QuadraticBezier {
+    fn position(&self, t: f32) -> FVec3 {
+        let u = 1.0 - t;
+        self.start * (u * u) + self.control * (2.0 * u * t) + self.end * (t * t)
+    }
+
+    fn tangent(&self, t: f32) -> FVec3 {
+        let u = 1.0 - t;
+        (self.control - self.start) * (2.0 * u) + (self.end - self.control) * (2.0 * t)
+    }
+}
+
+/// A cubic Bézier curve, with two control points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    pub start: FVec3,
+    pub control1: FVec3,
+    pub control2: FVec3,
+    pub end: FVec3,
+}
+
+impl Curve for CubicBezier {
+    fn position(&self, t: f32) -> FVec3 {
+        let u = 1.0 - t;
+        self.start * (u * u * u)
+            + self.control1 * (3.0 * u * u * t)
+            + self.control2 * (3.0 * u * t * t)
+            + self.end * (t * t * t)
+    }
+
+    fn tangent(&self, t: f32) -> FVec3 {
+        let u = 1.0 - t;
+        (self.control1 - self.start) * (3.0 * u * u)
+            + (self.control2 - self.control1) * (6.0 * u * t)
+            + (self.end - self.control2) * (3.0 * t * t)
+    }
+}
+
+/// A uniform Catmull-Rom spline, which passes through all of its points.
+///
+/// `t` is spread evenly over the segments between points, so `t = 0.0` is the
+/// first point and `t = 1.0` is the last. Use [`ArcLength`] for constant-speed
+/// motion along the spline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatmullRom {
+    points: Vec<FVec3>,
+}
+
+impl CatmullRom {
+    /// Create a spline through the given points. At least two points are needed
+    /// for the spline to have any length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` is empty.
+    pub fn new(points: Vec<FVec3>) -> Self {
+        assert!(!points.is_empty(), "a spline needs at least one point");
+        Self { points }
+    }
+
+    /// The points the spline passes through.
+    pub fn points(&self) -> &[FVec3] {
+        &self.points
+    }
+
+    fn segments(&self) -> usize {
+        self.points.len().saturating_sub(1).max(1)
+    }
+
+    /// Find the segment `t` lies in, along with the control points of that
+    /// segment and the parameter within it. The end points are repeated so the
+    /// spline reaches them.
+    fn segment(&self, t: f32) -> ([FVec3; 4], f32) {
+        let last = self.points.len() - 1;
+        let scaled = t.clamp(0.0, 1.0) * self.segments() as f32;
+        let i = (scaled as usize).min(self.segments() - 1);
+
+        let point = |i: usize| self.points[i.min(last)];
+        let p0 = point(i.saturating_sub(1));
+        let p1 = point(i);
+        let p2 = point(i + 1);
+        let p3 = point(i + 2);
+
+        ([p0, p1, p2, p3], scaled - i as f32)
+    }
+}
+
+impl Curve for CatmullRom {
+    fn position(&self, t: f32) -> FVec3 {
+        let ([p0, p1, p2, p3], t) = self.segment(t);
+        let (t2, t3) = (t * t, t * t * t);
+
+        (p1 * 2.0
+            + (p2 - p0) * t
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+            * 0.5
+    }
+
+    fn tangent(&self, t: f32) -> FVec3 {
+        let ([p0, p1, p2, p3], t) = self.segment(t);
+        let t2 = t * t;
+
+        // Scaled by the number of segments, since the local parameter moves
+        // that many times faster than the global one.
+        ((p2 - p0)
+            + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * (2.0 * t)
+            + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * (3.0 * t2))
+            * (0.5 * self.segments() as f32)
+    }
+}
+
+/// A lookup table for the arc length of a [`Curve`], used to move along it at
+/// a constant speed regardless of how the curve is parameterized.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArcLength {
+    // The distance along the curve at evenly spaced values of `t`.
+    distances: Vec<f32>,
+}
+
+impl ArcLength {
+    /// Approximate the arc length of `curve` by sampling it at `samples + 1`
+    /// evenly spaced points. More samples give a more accurate result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is zero.
+    pub fn new(curve: &impl Curve, samples: usize) -> Self {
+        assert!(samples > 0, "at least one sample is needed");
+
+        let mut distances = Vec::with_capacity(samples + 1);
+        let mut total = 0.0;
+        let mut previous = curve.position(0.0);
+        distances.push(total);
+
+        for i in 1..=samples {
+            let position = curve.position(i as f32 / samples as f32);
+            total += position.distance(previous);
+            distances.push(total);
+            previous = position;
+        }
+
+        Self { distances }
+    }
+
+    /// The total length of the curve.
+    pub fn length(&self) -> f32 {
+        *self.distances.last().unwrap()
+    }
+
+    /// The parameter `t` at which the curve is `distance` units from its start.
+    /// `distance` is clamped to the length of the curve.
+    pub fn t_at_distance(&self, distance: f32) -> f32 {
+        let samples = self.distances.len() - 1;
+        let distance = distance.clamp(0.0, self.length());
+
+        let i = self
+            .distances
+            .partition_point(|&d| d < distance)
+            .clamp(1, samples);
+        let (start, end) = (self.distances[i - 1], self.distances[i]);
+        let fraction = if end > start {
+            (distance - start) / (end - start)
+        } else {
+            0.0
+        };
+
+        (i as f32 - 1.0 + fraction) / samples as f32
+    }
+
+    /// The parameter `t` at which the given fraction of the curve's length
+    /// has been travelled, where `0.0` is the start and `1.0` is the end.
+    pub fn t_at_fraction(&self, fraction: f32) -> f32 {
+        self.t_at_distance(fraction * self.length())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn bezier_endpoints() {
+        let curve = CubicBezier {
+            start: FVec3::new(0.0, 0.0, 0.0),
+            control1: FVec3::new(1.0, 2.0, 0.0),
+            control2: FVec3::new(2.0, 2.0, 0.0),
+            end: FVec3::new(3.0, 0.0, 0.0),
+        };
+
+        assert_abs_diff_eq!(curve.position(0.0), curve.start);
+        assert_abs_diff_eq!(curve.position(1.0), curve.end);
+        assert_abs_diff_eq!(curve.tangent(0.0), FVec3::new(3.0, 6.0, 0.0));
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_points() {
+        let points = vec![
+            FVec3::new(0.0, 0.0, 0.0),
+            FVec3::new(1.0, 1.0, 0.0),
+            FVec3::new(2.0, 0.0, 0.0),
+        ];
+        let spline = CatmullRom::new(points.clone());
+
+        assert_abs_diff_eq!(spline.position(0.0), points[0]);
+        assert_abs_diff_eq!(spline.position(0.5), points[1]);
+        assert_abs_diff_eq!(spline.position(1.0), points[2]);
+    }
+
+    #[test]
+    fn arc_length_of_line() {
+        let line = QuadraticBezier {
+            start: FVec3::new(0.0, 0.0, 0.0),
+            control: FVec3::new(0.5, 0.0, 0.0),
+            end: FVec3::new(4.0, 0.0, 0.0),
+        };
+        let arc = ArcLength::new(&line, 64);
+
+        assert_abs_diff_eq!(arc.length(), 4.0, epsilon = 1e-4);
+
+        let t = arc.t_at_distance(2.0);
+        assert_abs_diff_eq!(line.position(t).x(), 2.0, epsilon = 1e-2);
+    }
+}
@@ -0,0 +1,194 @@
+//! Conversions between rotations and Euler angles.
+
+use super::{FQuat, FVec3, Matrix4};
+
+/// The order in which the rotations described by Euler angles are applied.
+///
+/// The name lists the axes in the order they are applied to a vector, around
+/// the fixed (world) axes. For example, [`XYZ`](Self::XYZ) rotates around X
+/// first, then Y, then Z, i.e. the rotation matrix is `Rz * Ry * Rx`. This is
+/// the same as rotating around the body's own axes in the opposite order
+/// (Z, then the rotated Y, then the rotated X).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RotationOrder {
+    /// Around X, then Y, then Z.
+    #[default]
+    XYZ,
+    /// Around X, then Z, then Y.
+    XZY,
+    /// Around Y, then X, then Z.
+    YXZ,
+    /// Around Y, then Z, then X.
+    YZX,
+    /// Around Z, then X, then Y.
+    ZXY,
+    /// Around Z, then Y, then X.
+    ZYX,
+}
+
+impl RotationOrder {
+    /// The axes (0 for X, 1 for Y, 2 for Z) in the order they are applied.
+    fn axes(self) -> [usize; 3] {
+        match self {
+            Self::XYZ => [0, 1, 2],
+            Self::XZY => [0, 2, 1],
+            Self::YXZ => [1, 0, 2],
+            Self::YZX => [1, 2, 0],
+            Self::ZXY => [2, 0, 1],
+            Self::ZYX => [2, 1, 0],
+        }
+    }
+
+    /// Whether the axes are a cyclic permutation of XYZ.
+    fn is_cyclic(self) -> bool {
+        matches!(self, Self::XYZ | Self::YZX | Self::ZXY)
+    }
+}
+
+/// The component of `v` for an axis (0 for X, 1 for Y, 2 for Z).
+fn component(v: FVec3, axis: usize) -> f32 {
+    [v.x(), v.y(), v.z()][axis]
+}
+
+/// The unit vector along an axis (0 for X, 1 for Y, 2 for Z).
+fn unit(axis: usize) -> FVec3 {
+    let mut v = [0.0; 3];
+    v[axis] = 1.0;
+    FVec3::new(v[0], v[1], v[2])
+}
+
+impl FQuat {
+    /// A rotation from Euler angles in radians. Each component of `angles` is
+    /// the angle around that axis, and `order` is the order the rotations are
+    /// applied in.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{FQuat, FVec3, RotationOrder};
+    /// # use approx::assert_abs_diff_eq;
+    /// use std::f32::consts::FRAC_PI_2;
+    ///
+    /// let angles = FVec3::new(FRAC_PI_2, FRAC_PI_2, 0.0);
+    /// let x_first = FQuat::from_euler_angles(angles, RotationOrder::XYZ);
+    /// let y_first = FQuat::from_euler_angles(angles, RotationOrder::YXZ);
+    ///
+    /// let v = FVec3::new(0.0, 0.0, 1.0);
+    /// assert_abs_diff_eq!(x_first * v, FVec3::new(0.0, -1.0, 0.0));
+    /// assert_abs_diff_eq!(y_first * v, FVec3::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn from_euler_angles(angles: FVec3, order: RotationOrder) -> Self {
+        order
+            .axes()
+            .into_iter()
+            .fold(Self::identity(), |rotation, axis| {
+                Self::from_axis_angle(unit(axis), component(angles, axis)) * rotation
+            })
+    }
+
+    /// Decompose the rotation into Euler angles in radians, such that
+    /// [`from_euler_angles`](Self::from_euler_angles) with the same `order`
+    /// gives back the same rotation.
+    ///
+    /// The angle around the second axis is in `-π/2..=π/2`, and the others are
+    /// in `-π..=π`. At gimbal lock (when the second angle is `±π/2`), the
+    /// angle around the last axis is `0.0`.
+    pub fn to_euler_angles(self, order: RotationOrder) -> FVec3 {
+        self.to_matrix().to_euler_angles(order)
+    }
+}
+
+impl Matrix4 {
+    /// A rotation matrix from Euler angles in radians. See
+    /// [`FQuat::from_euler_angles`].
+    pub fn from_euler_angles(angles: FVec3, order: RotationOrder) -> Self {
+        FQuat::from_euler_angles(angles, order).to_matrix()
+    }
+
+    /// Decompose a rotation matrix into Euler angles in radians. The matrix
+    /// should only contain a rotation (no scaling or translation). See
+    /// [`FQuat::to_euler_angles`].
+    pub fn to_euler_angles(&self, order: RotationOrder) -> FVec3 {
+        let m = self.rows_xyzw();
+        let [i, j, k] = order.axes();
+        let sign = if order.is_cyclic() { 1.0 } else { -1.0 };
+
+        let sin_j = (-sign * m[k][i]).clamp(-1.0, 1.0);
+        let angle_j = sin_j.asin();
+
+        let (angle_i, angle_k) = if sin_j.abs() < 0.9999 {
+            (
+                (sign * m[k][j]).atan2(m[k][k]),
+                (sign * m[j][i]).atan2(m[i][i]),
+            )
+        } else {
+            // Gimbal lock: the first and last axes line up, so only their
+            // combined angle is known. Put all of it in the first one.
+            ((m[i][j] * sin_j).atan2(m[j][j]), 0.0)
+        };
+
+        let mut angles = [0.0; 3];
+        angles[i] = angle_i;
+        angles[j] = angle_j;
+        angles[k] = angle_k;
+        FVec3::new(angles[0], angles[1], angles[2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    const ORDERS: [RotationOrder; 6] = [
+        RotationOrder::XYZ,
+        RotationOrder::XZY,
+        RotationOrder::YXZ,
+        RotationOrder::YZX,
+        RotationOrder::ZXY,
+        RotationOrder::ZYX,
+    ];
+
+    #[test]
+    fn round_trip() {
+        let angles = FVec3::new(0.3, -1.1, 0.8);
+        for order in ORDERS {
+            let q = FQuat::from_euler_angles(angles, order);
+            assert_abs_diff_eq!(q.to_euler_angles(order), angles, epsilon = 1e-4);
+
+            let m = Matrix4::from_euler_angles(angles, order);
+            let expected = order
+                .axes()
+                .into_iter()
+                .fold(Matrix4::identity(), |m, axis| {
+                    let rotation = Matrix4::identity().rotated(unit(axis), component(angles, axis));
+                    rotation * m
+                });
+            assert_abs_diff_eq!(m, expected, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn gimbal_lock() {
+        for order in ORDERS {
+            let mut angles = [0.4, 0.4, 0.4];
+            angles[order.axes()[1]] = FRAC_PI_2;
+            let angles = FVec3::new(angles[0], angles[1], angles[2]);
+
+            let q = FQuat::from_euler_angles(angles, order);
+            let decomposed = q.to_euler_angles(order);
+            assert_abs_diff_eq!(component(decomposed, order.axes()[2]), 0.0);
+
+            let v = FVec3::new(1.0, 2.0, 3.0);
+            assert_abs_diff_eq!(
+                FQuat::from_euler_angles(decomposed, order) * v,
+                q * v,
+                epsilon = 1e-3
+            );
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! View frustums, for culling geometry on the CPU before drawing it.
+
+use super::{Aabb, FVec3, FVec4, Matrix4, Sphere};
+
+/// The [view frustum](https://en.wikipedia.org/wiki/Viewing_frustum) of a
+/// projection, as six planes facing inwards. This can be used to skip drawing
+/// objects which are entirely off-screen.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::*;
+/// # use std::f32::consts::PI;
+/// let projection: Matrix4 = Projection::perspective(
+///     PI / 4.0,
+///     AspectRatio::TopScreen,
+///     ClipPlanes {
+///         near: 0.1,
+///         far: 100.0,
+///     },
+/// )
+/// .into();
+/// let view = Matrix4::identity();
+///
+/// let frustum = Frustum::from_matrix(&(projection * view));
+/// assert!(frustum.intersects_sphere(&Sphere::new(FVec3::new(0.0, 0.0, -10.0), 1.0)));
+/// // Behind the camera.
+/// assert!(!frustum.intersects_sphere(&Sphere::new(FVec3::new(0.0, 0.0, 10.0), 1.0)));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [FVec4; 6],
+}
+
+impl Frustum {
+    /// Extract the frustum planes from a projection matrix. If the matrix is
+    /// `projection * view`, the planes are in world space; if it also includes
+    /// a model matrix, they are in that model's space.
+    pub fn from_matrix(matrix: &Matrix4) -> Self {
+        let [x, y, z, w] = matrix.rows_wzyx();
+
+        // The PICA clips to `-w <= x, y <= w` and `-w <= z <= 0`.
+        let planes = [w + x, w - x, w + y, w - y, w + z, -z].map(|plane| {
+            let normal = FVec3::new(plane.x(), plane.y(), plane.z());
+            plane / normal.magnitude()
+        });
+
+        Self { planes }
+    }
+
+    /// The frustum's planes, as `(a, b, c, d)` for the plane
+    /// `ax + by + cz + d = 0`. Each plane's normal `(a, b, c)` is normalized
+    /// and points into the frustum.
+    ///
+    /// The planes are in the order left, right, bottom, top, near, far, before
+    /// any screen rotation in the matrix.
+    pub fn planes(&self) -> &[FVec4; 6] {
+        &self.planes
+    }
+
+    /// Whether `point` is inside the frustum.
+    pub fn contains_point(&self, point: FVec3) -> bool {
+        self.planes
+            .iter()
+            .all(|&plane| signed_distance(plane, point) >= 0.0)
+    }
+
+    /// Whether a sphere intersects the frustum, i.e. is at least partly
+    /// inside it.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes
+            .iter()
+            .all(|&plane| signed_distance(plane, sphere.center) >= -sphere.radius)
+    }
+
+    /// Whether an axis-aligned bounding box intersects the frustum.
+    ///
+    /// This is conservative: a box near a corner of the frustum may be
+    /// reported as intersecting even if it is just outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let Aabb { min, max } = *aabb;
+        self.planes.iter().all(|&plane| {
+            // The corner furthest along the plane's normal.
+            let corner = FVec3::new(
+                if plane.x() >= 0.0 { max.x() } else { min.x() },
+                if plane.y() >= 0.0 { max.y() } else { min.y() },
+                if plane.z() >= 0.0 { max.z() } else { min.z() },
+            );
+            signed_distance(plane, corner) >= 0.0
+        })
+    }
+}
+
+/// The signed distance from a normalized `plane` to `point`.
+fn signed_distance(plane: FVec4, point: FVec3) -> f32 {
+    FVec3::new(plane.x(), plane.y(), plane.z()).dot(point) + plane.w()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{ClipPlanes, Projection};
+
+    #[test]
+    fn culling() {
+        let projection: Matrix4 = Projection::orthographic(
+            -1.0..1.0,
+            -1.0..1.0,
+            ClipPlanes {
+                near: 0.0,
+                far: 10.0,
+            },
+        )
+        .into();
+        let frustum = Frustum::from_matrix(&projection);
+
+        assert!(frustum.contains_point(FVec3::new(0.0, 0.0, -5.0)));
+        assert!(!frustum.contains_point(FVec3::new(0.0, 0.0, -11.0)));
+        assert!(!frustum.contains_point(FVec3::new(2.0, 0.0, -5.0)));
+
+        assert!(frustum.intersects_sphere(&Sphere::new(FVec3::new(1.5, 0.0, -5.0), 1.0)));
+        assert!(!frustum.intersects_sphere(&Sphere::new(FVec3::new(2.5, 0.0, -5.0), 1.0)));
+
+        let inside = Aabb::new(FVec3::new(0.5, 0.5, -1.0), FVec3::new(3.0, 3.0, 1.0));
+        let outside = Aabb::new(FVec3::new(1.5, -1.0, -5.0), FVec3::new(3.0, 1.0, -4.0));
+        assert!(frustum.intersects_aabb(&inside));
+        assert!(!frustum.intersects_aabb(&outside));
+    }
+}
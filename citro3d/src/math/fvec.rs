@@ -46,6 +46,85 @@ impl<const N: usize> FVec<N> {
     pub fn z(self) -> f32 {
         unsafe { self.0.__bindgen_anon_1.z }
     }
+
+    /// Get the underlying [`citro3d_sys::C3D_FVec`].
+    pub fn into_raw(self) -> citro3d_sys::C3D_FVec {
+        self.0
+    }
+
+    /// The component-wise minimum of two vectors.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::FVec3;
+    /// # use approx::assert_abs_diff_eq;
+    /// let l = FVec3::new(1.0, 5.0, 3.0);
+    /// let r = FVec3::new(4.0, 2.0, 3.0);
+    /// assert_abs_diff_eq!(l.min(r), FVec3::new(1.0, 2.0, 3.0));
+    /// ```
+    pub fn min(self, rhs: Self) -> Self {
+        self.zip_map(rhs, f32::min)
+    }
+
+    /// The component-wise maximum of two vectors.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::FVec3;
+    /// # use approx::assert_abs_diff_eq;
+    /// let l = FVec3::new(1.0, 5.0, 3.0);
+    /// let r = FVec3::new(4.0, 2.0, 3.0);
+    /// assert_abs_diff_eq!(l.max(r), FVec3::new(4.0, 5.0, 3.0));
+    /// ```
+    pub fn max(self, rhs: Self) -> Self {
+        self.zip_map(rhs, f32::max)
+    }
+
+    /// Linearly interpolate between `self` (at `t = 0.0`) and `rhs` (at
+    /// `t = 1.0`).
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::FVec4;
+    /// # use approx::assert_abs_diff_eq;
+    /// let l = FVec4::splat(0.0);
+    /// let r = FVec4::new(2.0, 4.0, 6.0, 8.0);
+    /// assert_abs_diff_eq!(l.lerp(r, 0.5), FVec4::new(1.0, 2.0, 3.0, 4.0));
+    /// ```
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        self.zip_map(rhs, |a, b| a + (b - a) * t)
+    }
+
+    /// Apply `f` to each of the vector's components.
+    pub(super) fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        self.zip_map(self, |a, _| f(a))
+    }
+
+    /// The sum of the products of corresponding components, i.e. the dot
+    /// product.
+    fn sum_of_products(self, rhs: Self) -> f32 {
+        let (lhs, rhs) = unsafe { (self.0.c, rhs.0.c) };
+        (4 - N..4).map(|i| lhs[i] * rhs[i]).sum()
+    }
+
+    /// Apply `f` to each pair of corresponding components of the vectors.
+    /// Components beyond the vector's size are left as in `self`.
+    pub(super) fn zip_map(self, rhs: Self, f: impl Fn(f32, f32) -> f32) -> Self {
+        let (lhs, rhs) = unsafe { (self.0.c, rhs.0.c) };
+        let mut out = self;
+        // Components are stored in WZYX order, so the used ones are last.
+        out.0.c = std::array::from_fn(|i| {
+            if i >= 4 - N {
+                f(lhs[i], rhs[i])
+            } else {
+                lhs[i]
+            }
+        });
+        out
+    }
 }
 
 impl FVec4 {
@@ -70,7 +149,7 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_New")]
     pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
-        Self(unsafe { citro3d_sys::FVec4_New(x, y, z, w) })
+        Self(citro3d_sys::C3D_FVec { c: [w, z, y, x] })
     }
 
     /// Create a new [`FVec4`], setting each component to `v`.
@@ -99,7 +178,8 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_PerspDivide")]
     pub fn perspective_divide(self) -> Self {
-        Self(unsafe { citro3d_sys::FVec4_PerspDivide(self.0) })
+        let w = self.w();
+        Self::new(self.x() / w, self.y() / w, self.z() / w, 1.0)
     }
 
     /// The dot product of two vectors.
@@ -115,7 +195,7 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_Dot")]
     pub fn dot(self, rhs: Self) -> f32 {
-        unsafe { citro3d_sys::FVec4_Dot(self.0, rhs.0) }
+        self.sum_of_products(rhs)
     }
 
     /// The magnitude of the vector.
@@ -129,8 +209,9 @@ impl FVec4 {
     /// assert_abs_diff_eq!(v.magnitude(), 2.0);
     /// ```
     #[doc(alias = "FVec4_Magnitude")]
+    #[doc(alias = "length")]
     pub fn magnitude(self) -> f32 {
-        unsafe { citro3d_sys::FVec4_Magnitude(self.0) }
+        self.dot(self).sqrt()
     }
 
     /// Normalize the vector to a magnitude of `1.0`.
@@ -145,7 +226,22 @@ impl FVec4 {
     /// ```
     #[doc(alias = "FVec4_Normalize")]
     pub fn normalize(self) -> Self {
-        Self(unsafe { citro3d_sys::FVec4_Normalize(self.0) })
+        self * self.magnitude().recip()
+    }
+
+    /// The distance between two points in 4D space.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::FVec4;
+    /// # use approx::assert_abs_diff_eq;
+    /// let l = FVec4::new(1.0, 1.0, 1.0, 1.0);
+    /// let r = FVec4::new(2.0, 2.0, 2.0, 2.0);
+    /// assert_abs_diff_eq!(l.distance(r), 2.0);
+    /// ```
+    pub fn distance(self, rhs: Self) -> f32 {
+        (self - rhs).magnitude()
     }
 }
 
@@ -160,7 +256,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_New")]
     pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self(unsafe { citro3d_sys::FVec3_New(x, y, z) })
+        Self(citro3d_sys::C3D_FVec { c: [0.0, z, y, x] })
     }
 
     /// Create a new [`FVec3`], setting each component to the given `v`.
@@ -189,7 +285,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Distance")]
     pub fn distance(self, rhs: Self) -> f32 {
-        unsafe { citro3d_sys::FVec3_Distance(self.0, rhs.0) }
+        (self - rhs).magnitude()
     }
 
     /// The cross product of two 3D vectors.
@@ -205,7 +301,11 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Cross")]
     pub fn cross(self, rhs: Self) -> Self {
-        Self(unsafe { citro3d_sys::FVec3_Cross(self.0, rhs.0) })
+        Self::new(
+            self.y() * rhs.z() - self.z() * rhs.y(),
+            self.z() * rhs.x() - self.x() * rhs.z(),
+            self.x() * rhs.y() - self.y() * rhs.x(),
+        )
     }
 
     /// The dot product of two vectors.
@@ -221,7 +321,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Dot")]
     pub fn dot(self, rhs: Self) -> f32 {
-        unsafe { citro3d_sys::FVec3_Dot(self.0, rhs.0) }
+        self.sum_of_products(rhs)
     }
 
     /// The magnitude of the vector.
@@ -235,8 +335,9 @@ impl FVec3 {
     /// assert_abs_diff_eq!(v.magnitude(), 3.0);
     /// ```
     #[doc(alias = "FVec3_Magnitude")]
+    #[doc(alias = "length")]
     pub fn magnitude(self) -> f32 {
-        unsafe { citro3d_sys::FVec3_Magnitude(self.0) }
+        self.dot(self).sqrt()
     }
 
     /// Normalize the vector to a magnitude of `1.0`.
@@ -251,7 +352,7 @@ impl FVec3 {
     /// ```
     #[doc(alias = "FVec3_Normalize")]
     pub fn normalize(self) -> Self {
-        Self(unsafe { citro3d_sys::FVec3_Normalize(self.0) })
+        self * self.magnitude().recip()
     }
 }
 
@@ -281,6 +382,80 @@ impl From<FVec3> for glam::Vec3 {
     }
 }
 
+#[cfg(feature = "mint")]
+impl From<mint::Vector4<f32>> for FVec4 {
+    fn from(value: mint::Vector4<f32>) -> Self {
+        Self::new(value.x, value.y, value.z, value.w)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<mint::Vector3<f32>> for FVec3 {
+    fn from(value: mint::Vector3<f32>) -> Self {
+        Self::new(value.x, value.y, value.z)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<mint::Point3<f32>> for FVec3 {
+    fn from(value: mint::Point3<f32>) -> Self {
+        Self::new(value.x, value.y, value.z)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<FVec4> for mint::Vector4<f32> {
+    fn from(value: FVec4) -> Self {
+        [value.x(), value.y(), value.z(), value.w()].into()
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<FVec3> for mint::Vector3<f32> {
+    fn from(value: FVec3) -> Self {
+        [value.x(), value.y(), value.z()].into()
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<FVec3> for mint::Point3<f32> {
+    fn from(value: FVec3) -> Self {
+        [value.x(), value.y(), value.z()].into()
+    }
+}
+
+/// Serialized as `[x, y, z, w]`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FVec4 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.x(), self.y(), self.z(), self.w()].serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FVec4 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [x, y, z, w] = <[f32; 4]>::deserialize(deserializer)?;
+        Ok(Self::new(x, y, z, w))
+    }
+}
+
+/// Serialized as `[x, y, z]`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FVec3 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.x(), self.y(), self.z()].serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FVec3 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [x, y, z] = <[f32; 3]>::deserialize(deserializer)?;
+        Ok(Self::new(x, y, z))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use approx::assert_abs_diff_eq;
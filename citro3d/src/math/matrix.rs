@@ -1,6 +1,6 @@
 use std::mem::MaybeUninit;
 
-use super::{CoordinateOrientation, FVec3, FVec4};
+use super::{CoordinateOrientation, FQuat, FVec3, FVec4, Viewport};
 
 /// A 4x4 row-major matrix of `f32`s.
 ///
@@ -67,6 +67,15 @@ impl Matrix4 {
     }
 
     /// Transpose the matrix, swapping rows and columns.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::Matrix4;
+    /// let mut m = Matrix4::identity();
+    /// m.translate(1.0, 2.0, 3.0);
+    /// assert_eq!(m.transpose().rows_xyzw()[3], [1.0, 2.0, 3.0, 1.0]);
+    /// ```
     #[doc(alias = "Mtx_Transpose")]
     pub fn transpose(mut self) -> Matrix4 {
         unsafe {
@@ -87,7 +96,15 @@ impl Matrix4 {
     /// directions.
     #[doc(alias = "Mtx_Translate")]
     pub fn translate(&mut self, x: f32, y: f32, z: f32) {
-        unsafe { citro3d_sys::Mtx_Translate(self.as_raw_mut(), x, y, z, false) }
+        // Equivalent to `Mtx_Translate(self, x, y, z, false)`, i.e. `T * self`.
+        let rows = unsafe { &mut self.as_raw_mut().r };
+        let last = unsafe { rows[3].c };
+        for (row, amount) in rows.iter_mut().zip([x, y, z]) {
+            let row = unsafe { &mut row.c };
+            for (cell, last) in row.iter_mut().zip(last) {
+                *cell += last * amount;
+            }
+        }
     }
 
     /// Scale a transformation matrix by the given amounts in the X, Y, and Z directions.
@@ -120,8 +137,124 @@ impl Matrix4 {
         unsafe { citro3d_sys::Mtx_RotateZ(self.as_raw_mut(), angle, false) }
     }
 
+    /// [`translate`](Self::translate) the matrix, returning it for chaining.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::Matrix4;
+    /// let model = Matrix4::identity()
+    ///     .rotated_y(1.0)
+    ///     .scaled(2.0, 2.0, 2.0)
+    ///     .translated(0.0, 0.0, -3.0);
+    /// ```
+    #[doc(alias = "Mtx_Translate")]
+    pub fn translated(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.translate(x, y, z);
+        self
+    }
+
+    /// [`scale`](Self::scale) the matrix, returning it for chaining.
+    #[doc(alias = "Mtx_Scale")]
+    pub fn scaled(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.scale(x, y, z);
+        self
+    }
+
+    /// [`rotate`](Self::rotate) the matrix, returning it for chaining.
+    #[doc(alias = "Mtx_Rotate")]
+    pub fn rotated(mut self, axis: FVec3, angle: f32) -> Self {
+        self.rotate(axis, angle);
+        self
+    }
+
+    /// [`rotate_x`](Self::rotate_x) the matrix, returning it for chaining.
+    #[doc(alias = "Mtx_RotateX")]
+    pub fn rotated_x(mut self, angle: f32) -> Self {
+        self.rotate_x(angle);
+        self
+    }
+
+    /// [`rotate_y`](Self::rotate_y) the matrix, returning it for chaining.
+    #[doc(alias = "Mtx_RotateY")]
+    pub fn rotated_y(mut self, angle: f32) -> Self {
+        self.rotate_y(angle);
+        self
+    }
+
+    /// [`rotate_z`](Self::rotate_z) the matrix, returning it for chaining.
+    #[doc(alias = "Mtx_RotateZ")]
+    pub fn rotated_z(mut self, angle: f32) -> Self {
+        self.rotate_z(angle);
+        self
+    }
+
+    /// Construct a translation matrix.
+    pub fn from_translation(translation: FVec3) -> Self {
+        Self::identity().translated(translation.x(), translation.y(), translation.z())
+    }
+
+    /// Construct a scaling matrix.
+    pub fn from_scale(scale: FVec3) -> Self {
+        Self::identity().scaled(scale.x(), scale.y(), scale.z())
+    }
+
+    /// Construct a matrix rotating by the given angle around the X axis.
+    pub fn from_rotation_x(angle: f32) -> Self {
+        Self::identity().rotated_x(angle)
+    }
+
+    /// Construct a matrix rotating by the given angle around the Y axis.
+    pub fn from_rotation_y(angle: f32) -> Self {
+        Self::identity().rotated_y(angle)
+    }
+
+    /// Construct a matrix rotating by the given angle around the Z axis.
+    pub fn from_rotation_z(angle: f32) -> Self {
+        Self::identity().rotated_z(angle)
+    }
+
+    /// Construct a rotation matrix from a quaternion.
+    #[doc(alias = "Mtx_FromQuat")]
+    pub fn from_quat(rotation: FQuat) -> Self {
+        rotation.to_matrix()
+    }
+
+    /// Construct a transformation matrix which scales, then rotates, then
+    /// translates (i.e. `T * R * S`). This is the usual model matrix for an
+    /// object in a scene.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{FQuat, FVec3, FVec4, Matrix4};
+    /// # use approx::assert_abs_diff_eq;
+    /// let model = Matrix4::compose(
+    ///     FVec3::new(1.0, 0.0, 0.0),
+    ///     FQuat::identity(),
+    ///     FVec3::splat(2.0),
+    /// );
+    /// assert_abs_diff_eq!(
+    ///     &model * FVec3::new(1.0, 1.0, 1.0),
+    ///     FVec4::new(3.0, 2.0, 2.0, 1.0)
+    /// );
+    /// ```
+    pub fn compose(translation: FVec3, rotation: FQuat, scale: FVec3) -> Self {
+        Self::from_translation(translation) * Self::from_quat(rotation) * Self::from_scale(scale)
+    }
+
     /// Find the inverse of the matrix.
     ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::Matrix4;
+    /// # use approx::assert_abs_diff_eq;
+    /// let m = Matrix4::diagonal(1.0, 2.0, 4.0, 1.0);
+    /// assert_abs_diff_eq!(m.inverse().unwrap(), Matrix4::diagonal(1.0, 0.5, 0.25, 1.0));
+    /// assert!(Matrix4::zero().inverse().is_err());
+    /// ```
+    ///
     /// # Errors
     ///
     /// If the matrix has no inverse, it will be returned unchanged as an [`Err`].
@@ -135,6 +268,81 @@ impl Matrix4 {
         }
     }
 
+    /// Project a point to screen coordinates, using this matrix as the
+    /// combined projection and view (and model) matrix. The result's Z
+    /// component is the depth, from -1 at the near plane to 0 at the far
+    /// plane.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::*;
+    /// # use approx::assert_abs_diff_eq;
+    /// let projection: Matrix4 = Projection::perspective(
+    ///     1.0,
+    ///     AspectRatio::BottomScreen,
+    ///     ClipPlanes {
+    ///         near: 0.1,
+    ///         far: 100.0,
+    ///     },
+    /// )
+    /// .into();
+    ///
+    /// // Points straight ahead of the camera are in the center of the screen.
+    /// let screen = projection.project(FVec3::new(0.0, 0.0, -5.0), &Viewport::bottom_screen());
+    /// assert_abs_diff_eq!(screen.x(), 160.0);
+    /// assert_abs_diff_eq!(screen.y(), 120.0);
+    /// ```
+    pub fn project(&self, point: FVec3, viewport: &Viewport) -> FVec3 {
+        let ndc = (self * point).perspective_divide();
+        let (x, y) = viewport.to_screen(ndc.x(), ndc.y());
+        FVec3::new(x, y, ndc.z())
+    }
+
+    /// Convert screen coordinates and a depth (from -1 at the near plane to 0
+    /// at the far plane) back to a point, using this matrix as the combined
+    /// projection and view (and model) matrix. This is the inverse of
+    /// [`project`](Self::project).
+    ///
+    /// For example, a touch on the bottom screen can be converted into a
+    /// world-space ray from the point at depth -1 to the point at depth 0.
+    ///
+    /// # Errors
+    ///
+    /// If the matrix has no inverse, it is returned as an [`Err`].
+    pub fn unproject(&self, screen: FVec3, viewport: &Viewport) -> Result<FVec3, Self> {
+        let inverse = self.inverse()?;
+        let (x, y) = viewport.to_ndc(screen.x(), screen.y());
+        let point = (&inverse * FVec4::new(x, y, screen.z(), 1.0)).perspective_divide();
+        Ok(FVec3::new(point.x(), point.y(), point.z()))
+    }
+
+    /// Find the determinant of the matrix.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::Matrix4;
+    /// # use approx::assert_abs_diff_eq;
+    /// assert_abs_diff_eq!(Matrix4::diagonal(1.0, 2.0, 3.0, 4.0).determinant(), 24.0);
+    /// ```
+    pub fn determinant(self) -> f32 {
+        // `Mtx_Inverse` returns the determinant it computes along the way.
+        let mut copy = self;
+        unsafe { citro3d_sys::Mtx_Inverse(copy.as_raw_mut()) }
+    }
+
+    /// Find the normal matrix for this model-view matrix, i.e. its inverse
+    /// transpose. This transforms normals correctly for lighting even when the
+    /// matrix includes non-uniform scaling.
+    ///
+    /// # Errors
+    ///
+    /// If the matrix has no inverse, it will be returned unchanged as an [`Err`].
+    pub fn normal_matrix(self) -> Result<Self, Self> {
+        self.inverse().map(Self::transpose)
+    }
+
     /// Construct the identity matrix.
     #[doc(alias = "Mtx_Identity")]
     pub fn identity() -> Self {
@@ -157,7 +365,26 @@ impl Matrix4 {
 
     /// Construct a 3D transformation matrix for a camera, given its position,
     /// target, and upward direction.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{CoordinateOrientation, FVec3, Matrix4};
+    /// # use approx::assert_abs_diff_eq;
+    /// let eye = FVec3::new(0.0, 0.0, 5.0);
+    /// let view = Matrix4::looking_at(
+    ///     eye,
+    ///     FVec3::splat(0.0),
+    ///     FVec3::new(0.0, 1.0, 0.0),
+    ///     CoordinateOrientation::RightHanded,
+    /// );
+    ///
+    /// // The camera ends up at the origin of view space.
+    /// let origin = &view * eye;
+    /// assert_abs_diff_eq!(origin, citro3d::math::FVec4::new(0.0, 0.0, 0.0, 1.0));
+    /// ```
     #[doc(alias = "Mtx_LookAt")]
+    #[doc(alias = "look_at")]
     pub fn looking_at(
         camera_position: FVec3,
         camera_target: FVec3,
@@ -197,3 +424,126 @@ impl From<Matrix4> for glam::Mat4 {
         glam::Mat4::from_cols_array_2d(&mat.rows_xyzw()).transpose()
     }
 }
+
+#[cfg(feature = "mint")]
+impl From<mint::RowMatrix4<f32>> for Matrix4 {
+    fn from(mat: mint::RowMatrix4<f32>) -> Self {
+        Matrix4::from_rows([mat.x, mat.y, mat.z, mat.w].map(FVec4::from))
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<mint::ColumnMatrix4<f32>> for Matrix4 {
+    fn from(mat: mint::ColumnMatrix4<f32>) -> Self {
+        mint::RowMatrix4::from(mat).into()
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<Matrix4> for mint::RowMatrix4<f32> {
+    fn from(mat: Matrix4) -> Self {
+        mat.rows_xyzw().into()
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<Matrix4> for mint::ColumnMatrix4<f32> {
+    fn from(mat: Matrix4) -> Self {
+        mint::RowMatrix4::from(mat).into()
+    }
+}
+
+/// Serialized as an array of rows, each in XYZW order.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Matrix4 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.rows_xyzw().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Matrix4 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = <[[f32; 4]; 4]>::deserialize(deserializer)?;
+        Ok(Self::from_rows(
+            rows.map(|[x, y, z, w]| FVec4::new(x, y, z, w)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn inverse_transpose_determinant() {
+        let mut m = Matrix4::diagonal(2.0, 4.0, 8.0, 1.0);
+        m.translate(1.0, 2.0, 3.0);
+
+        assert_abs_diff_eq!(m.determinant(), 64.0);
+        assert_abs_diff_eq!(Matrix4::zero().determinant(), 0.0);
+        assert_abs_diff_eq!(m * m.inverse().unwrap(), Matrix4::identity());
+        assert_abs_diff_eq!(m.transpose().transpose(), m);
+        assert_abs_diff_eq!(m.normal_matrix().unwrap(), m.inverse().unwrap().transpose());
+    }
+
+    #[test]
+    fn project_unproject() {
+        use crate::math::{AspectRatio, ClipPlanes, Projection, ScreenOrientation};
+
+        let view = Matrix4::identity()
+            .rotated_y(0.5)
+            .translated(1.0, 2.0, -3.0);
+        let point = FVec3::new(0.5, -0.25, -8.0);
+        let clip_planes = ClipPlanes {
+            near: 0.1,
+            far: 100.0,
+        };
+
+        for orientation in [
+            ScreenOrientation::Rotated,
+            ScreenOrientation::None,
+            ScreenOrientation::PortraitFlipped,
+        ] {
+            let projection: Matrix4 =
+                Projection::perspective(1.0, AspectRatio::TopScreen, clip_planes)
+                    .screen(orientation)
+                    .into();
+            let viewport = Viewport {
+                orientation,
+                ..Viewport::top_screen()
+            };
+
+            let matrix = projection * view;
+            let screen = matrix.project(point, &viewport);
+            assert_abs_diff_eq!(
+                matrix.unproject(screen, &viewport).unwrap(),
+                point,
+                epsilon = 1.0e-3
+            );
+        }
+
+        // A point up and to the right of the camera is up and to the right on
+        // screen, regardless of the screen rotation.
+        let projection: Matrix4 =
+            Projection::perspective(1.0, AspectRatio::TopScreen, clip_planes).into();
+        let screen = projection.project(FVec3::new(1.0, 1.0, -5.0), &Viewport::top_screen());
+        assert!(screen.x() > 200.0 && screen.y() < 120.0);
+    }
+
+    #[test]
+    fn composition() {
+        let translation = FVec3::new(1.0, 2.0, 3.0);
+        let rotation = FQuat::from_axis_angle(FVec3::new(0.0, 0.0, 1.0), 1.0);
+        let scale = FVec3::new(2.0, 3.0, 4.0);
+
+        let chained = Matrix4::identity()
+            .scaled(2.0, 3.0, 4.0)
+            .rotated_z(1.0)
+            .translated(1.0, 2.0, 3.0);
+        assert_abs_diff_eq!(Matrix4::compose(translation, rotation, scale), chained);
+        assert_abs_diff_eq!(Matrix4::from_rotation_z(1.0), Matrix4::from_quat(rotation));
+    }
+}
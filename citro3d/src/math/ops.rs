@@ -1,10 +1,10 @@
 use std::mem::MaybeUninit;
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 #[cfg(feature = "approx")]
 use approx::AbsDiffEq;
 
-use super::{FVec, FVec3, FVec4, Matrix4};
+use super::{FQuat, FVec, FVec3, FVec4, Matrix4};
 
 // region: FVec4 math operators
 
@@ -13,7 +13,7 @@ impl Add for FVec4 {
 
     #[doc(alias = "FVec4_Add")]
     fn add(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Add(self.0, rhs.0) })
+        self.zip_map(rhs, |a, b| a + b)
     }
 }
 
@@ -22,7 +22,7 @@ impl Sub for FVec4 {
 
     #[doc(alias = "FVec4_Subtract")]
     fn sub(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Subtract(self.0, rhs.0) })
+        self.zip_map(rhs, |a, b| a - b)
     }
 }
 
@@ -31,7 +31,7 @@ impl Neg for FVec4 {
 
     #[doc(alias = "FVec4_Negate")]
     fn neg(self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Negate(self.0) })
+        self.map(|a| -a)
     }
 }
 
@@ -40,7 +40,7 @@ impl Mul<f32> for FVec4 {
 
     #[doc(alias = "FVec4_Scale")]
     fn mul(self, rhs: f32) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec4_Scale(self.0, rhs) })
+        self.map(|a| a * rhs)
     }
 }
 
@@ -53,7 +53,7 @@ impl Add for FVec3 {
 
     #[doc(alias = "FVec3_Add")]
     fn add(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Add(self.0, rhs.0) })
+        self.zip_map(rhs, |a, b| a + b)
     }
 }
 
@@ -62,7 +62,7 @@ impl Sub for FVec3 {
 
     #[doc(alias = "FVec3_Subtract")]
     fn sub(self, rhs: Self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Subtract(self.0, rhs.0) })
+        self.zip_map(rhs, |a, b| a - b)
     }
 }
 
@@ -71,7 +71,7 @@ impl Neg for FVec3 {
 
     #[doc(alias = "FVec3_Negate")]
     fn neg(self) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Negate(self.0) })
+        self.map(|a| -a)
     }
 }
 
@@ -80,7 +80,7 @@ impl Mul<f32> for FVec3 {
 
     #[doc(alias = "FVec3_Scale")]
     fn mul(self, rhs: f32) -> Self::Output {
-        Self(unsafe { citro3d_sys::FVec3_Scale(self.0, rhs) })
+        self.map(|a| a * rhs)
     }
 }
 
@@ -97,6 +97,71 @@ where
     }
 }
 
+impl<const N: usize> Mul for FVec<N> {
+    type Output = Self;
+
+    /// Multiply two vectors component-wise.
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.zip_map(rhs, |a, b| a * b)
+    }
+}
+
+impl<const N: usize> Div for FVec<N> {
+    type Output = Self;
+
+    /// Divide two vectors component-wise.
+    fn div(self, rhs: Self) -> Self::Output {
+        self.zip_map(rhs, |a, b| a / b)
+    }
+}
+
+impl<const N: usize> Mul<FVec<N>> for f32
+where
+    FVec<N>: Mul<f32>,
+{
+    type Output = <FVec<N> as Mul<f32>>::Output;
+
+    fn mul(self, rhs: FVec<N>) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl<const N: usize> AddAssign for FVec<N>
+where
+    FVec<N>: Add<Output = Self>,
+{
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const N: usize> SubAssign for FVec<N>
+where
+    FVec<N>: Sub<Output = Self>,
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const N: usize> MulAssign<f32> for FVec<N>
+where
+    FVec<N>: Mul<f32, Output = Self>,
+{
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const N: usize> DivAssign<f32> for FVec<N>
+where
+    FVec<N>: Div<f32, Output = Self>,
+{
+    fn div_assign(&mut self, rhs: f32) {
+        *self = *self / rhs;
+    }
+}
+
 impl<const N: usize> PartialEq for FVec<N> {
     fn eq(&self, other: &Self) -> bool {
         let range = (4 - N)..;
@@ -123,6 +188,48 @@ impl<const N: usize> AbsDiffEq for FVec<N> {
     }
 }
 
+// region: Quaternion math operators
+
+impl Mul for FQuat {
+    type Output = Self;
+
+    #[doc(alias = "Quat_Multiply")]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(unsafe { citro3d_sys::Quat_Multiply(self.0, rhs.0) })
+    }
+}
+
+impl Mul<FVec3> for FQuat {
+    type Output = FVec3;
+
+    #[doc(alias = "Quat_CrossFVec3")]
+    fn mul(self, rhs: FVec3) -> Self::Output {
+        self.rotate_vec(rhs)
+    }
+}
+
+impl PartialEq for FQuat {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { self.0.c == other.0.c }
+    }
+}
+
+#[cfg(feature = "approx")]
+impl AbsDiffEq for FQuat {
+    type Epsilon = f32;
+
+    fn default_epsilon() -> Self::Epsilon {
+        f32::EPSILON.sqrt()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        let (lhs, rhs) = unsafe { (&self.0.c, &other.0.c) };
+        lhs.abs_diff_eq(rhs, epsilon)
+    }
+}
+
+// endregion
+
 // region: Matrix math operators
 
 impl Add<Matrix4> for Matrix4 {
@@ -156,11 +263,17 @@ impl Mul<Matrix4> for Matrix4 {
 
     #[doc(alias = "Mtx_Multiply")]
     fn mul(self, rhs: Matrix4) -> Self::Output {
-        let mut out = MaybeUninit::uninit();
-        unsafe {
-            citro3d_sys::Mtx_Multiply(out.as_mut_ptr(), self.as_raw(), rhs.as_raw());
-            Matrix4::from_raw(out.assume_init())
-        }
+        // Rows are stored as WZYX, so column `k` of a row is at `c[3 - k]`.
+        let (lhs, rhs) = unsafe { (self.as_raw().r, rhs.as_raw().r) };
+        let rows = lhs.map(|row| {
+            let row = unsafe { row.c };
+            citro3d_sys::C3D_FVec {
+                c: std::array::from_fn(|i| {
+                    (0..4).map(|k| row[3 - k] * unsafe { rhs[k].c[i] }).sum()
+                }),
+            }
+        });
+        Matrix4::from_raw(citro3d_sys::C3D_Mtx { r: rows })
     }
 }
 
@@ -233,6 +346,16 @@ mod tests {
         assert_abs_diff_eq!(-l, FVec3::splat(-1.0));
         assert_abs_diff_eq!(l * 1.5, FVec3::splat(1.5));
         assert_abs_diff_eq!(l / 2.0, FVec3::splat(0.5));
+        assert_abs_diff_eq!(2.0 * l, r);
+        assert_abs_diff_eq!(r * FVec3::new(1.0, 2.0, 3.0), FVec3::new(2.0, 4.0, 6.0));
+        assert_abs_diff_eq!(r / FVec3::new(1.0, 2.0, 4.0), FVec3::new(2.0, 1.0, 0.5));
+
+        let mut v = l;
+        v += r;
+        v *= 2.0;
+        v -= l;
+        v /= 5.0;
+        assert_abs_diff_eq!(v, l);
     }
 
     #[test]
@@ -245,6 +368,13 @@ mod tests {
         assert_abs_diff_eq!(-l, FVec4::splat(-1.0));
         assert_abs_diff_eq!(l * 1.5, FVec4::splat(1.5));
         assert_abs_diff_eq!(l / 2.0, FVec4::splat(0.5));
+        assert_abs_diff_eq!(2.0 * l, r);
+        assert_abs_diff_eq!(
+            r * FVec4::new(1.0, 2.0, 3.0, 4.0),
+            FVec4::new(2.0, 4.0, 6.0, 8.0)
+        );
+        assert_abs_diff_eq!(l.min(r), l);
+        assert_abs_diff_eq!(l.max(r), r);
     }
 
     #[test]
@@ -256,4 +386,49 @@ mod tests {
         assert_abs_diff_eq!(l + r, Matrix4::diagonal(2.0, 3.0, 4.0, 5.0));
         assert_abs_diff_eq!(l - r, Matrix4::diagonal(0.0, 1.0, 2.0, 3.0));
     }
+
+    #[test]
+    fn matches_ffi() {
+        let l = FVec4::new(1.0, -2.0, 3.0, 0.5);
+        let r = FVec4::new(-4.0, 5.0, 0.25, 2.0);
+        let ffi = FVec4::from_raw;
+        unsafe {
+            assert_abs_diff_eq!(l + r, ffi(citro3d_sys::FVec4_Add(l.0, r.0)));
+            assert_abs_diff_eq!(l - r, ffi(citro3d_sys::FVec4_Subtract(l.0, r.0)));
+            assert_abs_diff_eq!(l * 3.0, ffi(citro3d_sys::FVec4_Scale(l.0, 3.0)));
+            assert_abs_diff_eq!(l.dot(r), citro3d_sys::FVec4_Dot(l.0, r.0));
+            assert_abs_diff_eq!(l.normalize(), ffi(citro3d_sys::FVec4_Normalize(l.0)));
+            assert_abs_diff_eq!(
+                l.perspective_divide(),
+                ffi(citro3d_sys::FVec4_PerspDivide(l.0))
+            );
+        }
+
+        let l = FVec3::new(1.0, -2.0, 3.0);
+        let r = FVec3::new(-4.0, 5.0, 0.25);
+        let ffi = FVec::<3>;
+        unsafe {
+            assert_abs_diff_eq!(l.cross(r), ffi(citro3d_sys::FVec3_Cross(l.0, r.0)));
+            assert_abs_diff_eq!(l.distance(r), citro3d_sys::FVec3_Distance(l.0, r.0));
+            assert_abs_diff_eq!(l.normalize(), ffi(citro3d_sys::FVec3_Normalize(l.0)));
+        }
+
+        let mut l = Matrix4::identity();
+        l.rotate_y(1.0);
+        l.scale(1.0, 2.0, 3.0);
+        let r = Matrix4::identity()
+            .rotated_x(-0.5)
+            .translated(4.0, 5.0, 6.0);
+
+        let mut expected = MaybeUninit::uninit();
+        let expected = unsafe {
+            citro3d_sys::Mtx_Multiply(expected.as_mut_ptr(), l.as_raw(), r.as_raw());
+            Matrix4::from_raw(expected.assume_init())
+        };
+        assert_abs_diff_eq!(l * r, expected);
+
+        let mut expected = l;
+        unsafe { citro3d_sys::Mtx_Translate(expected.as_raw_mut(), 1.0, 2.0, 3.0, false) };
+        assert_abs_diff_eq!(l.translated(1.0, 2.0, 3.0), expected);
+    }
 }
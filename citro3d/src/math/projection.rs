@@ -1,7 +1,7 @@
 use std::mem::MaybeUninit;
 use std::ops::Range;
 
-use super::Matrix4;
+use super::{FVec4, Matrix4};
 
 /// Configuration for a 3D [projection](https://en.wikipedia.org/wiki/3D_projection).
 /// See specific `Kind` implementations for constructors, e.g.
@@ -9,6 +9,7 @@ use super::Matrix4;
 ///
 /// To use the resulting projection, convert it to a [`Matrix4`] with [`From`]/[`Into`].
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Projection<Kind> {
     coordinates: CoordinateOrientation,
     rotation: ScreenOrientation,
@@ -69,11 +70,13 @@ impl<Kind> Projection<Kind> {
 
 /// See [`Projection::perspective`].
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Perspective {
     vertical_fov_radians: f32,
     aspect_ratio: AspectRatio,
     clip_planes: ClipPlanes,
     stereo: Option<StereoDisplacement>,
+    near_plane: Option<FVec4>,
 }
 
 impl Projection<Perspective> {
@@ -86,7 +89,8 @@ impl Projection<Perspective> {
     /// * `aspect_ratio`: the aspect ratio of the projection
     /// * `clip_planes`: the near and far clip planes of the view frustum.
     ///   [`ClipPlanes`] are always defined by near and far values, regardless
-    ///   of the projection's [`CoordinateOrientation`].
+    ///   of the projection's [`CoordinateOrientation`]. The far plane may be
+    ///   infinitely far away, see [`ClipPlanes::infinite`].
     ///
     /// # Examples
     ///
@@ -119,9 +123,46 @@ impl Projection<Perspective> {
             aspect_ratio,
             clip_planes,
             stereo: None,
+            near_plane: None,
         })
     }
 
+    /// Replace the near clip plane with an arbitrary `plane` in view space,
+    /// given as `(a, b, c, d)` for the plane `ax + by + cz + d = 0`. Points on
+    /// the positive side of the plane are kept. The far plane is adjusted to
+    /// keep the frustum as tight as possible.
+    ///
+    /// This is useful for rendering reflections (e.g. water or mirrors) into a
+    /// texture, where geometry behind the reflecting surface must be clipped.
+    /// It uses Eric Lengyel's
+    /// [oblique near-plane clipping](https://terathon.com/lengyel/Lengyel-Oblique.pdf)
+    /// technique. The plane should face away from the camera and not pass
+    /// through it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{AspectRatio, ClipPlanes, FVec4, Matrix4, Projection};
+    /// # use std::f32::consts::PI;
+    /// // Clip everything below the water surface at y = -1 (in view space).
+    /// let water = FVec4::new(0.0, 1.0, 0.0, 1.0);
+    /// let mtx: Matrix4 = Projection::perspective(
+    ///     PI / 4.0,
+    ///     AspectRatio::TopScreen,
+    ///     ClipPlanes {
+    ///         near: 0.1,
+    ///         far: 100.0,
+    ///     },
+    /// )
+    /// .oblique_near_plane(water)
+    /// .into();
+    /// ```
+    pub fn oblique_near_plane(mut self, plane: FVec4) -> Self {
+        self.inner.near_plane = Some(plane);
+        self
+    }
+
     /// Helper function to build both eyes' perspective projection matrices
     /// at once. See [`StereoDisplacement`] for details on how to configure
     /// stereoscopy.
@@ -171,14 +212,26 @@ impl From<Projection<Perspective>> for Matrix4 {
             aspect_ratio,
             clip_planes,
             stereo,
+            near_plane,
         } = projection.inner;
 
+        // citro3d can't build an infinite projection directly, so build a
+        // finite one and replace its depth terms afterwards.
+        let infinite = clip_planes.is_infinite();
+        let far = if infinite {
+            clip_planes.near * 2.0
+        } else {
+            clip_planes.far
+        };
+
         let mut result = MaybeUninit::uninit();
 
         if let Some(stereo) = stereo {
             let make_mtx = match projection.rotation {
                 ScreenOrientation::Rotated => citro3d_sys::Mtx_PerspStereoTilt,
-                ScreenOrientation::None => citro3d_sys::Mtx_PerspStereo,
+                ScreenOrientation::None
+                | ScreenOrientation::Portrait
+                | ScreenOrientation::PortraitFlipped => citro3d_sys::Mtx_PerspStereo,
             };
             unsafe {
                 make_mtx(
@@ -186,7 +239,7 @@ impl From<Projection<Perspective>> for Matrix4 {
                     vertical_fov_radians,
                     aspect_ratio.into(),
                     clip_planes.near,
-                    clip_planes.far,
+                    far,
                     stereo.displacement,
                     stereo.screen_depth,
                     projection.coordinates.is_left_handed(),
@@ -195,7 +248,9 @@ impl From<Projection<Perspective>> for Matrix4 {
         } else {
             let make_mtx = match projection.rotation {
                 ScreenOrientation::Rotated => citro3d_sys::Mtx_PerspTilt,
-                ScreenOrientation::None => citro3d_sys::Mtx_Persp,
+                ScreenOrientation::None
+                | ScreenOrientation::Portrait
+                | ScreenOrientation::PortraitFlipped => citro3d_sys::Mtx_Persp,
             };
             unsafe {
                 make_mtx(
@@ -203,18 +258,51 @@ impl From<Projection<Perspective>> for Matrix4 {
                     vertical_fov_radians,
                     aspect_ratio.into(),
                     clip_planes.near,
-                    clip_planes.far,
+                    far,
                     projection.coordinates.is_left_handed(),
                 );
             }
         }
 
-        unsafe { Self::from_raw(result.assume_init()) }
+        let mut result = unsafe { result.assume_init() };
+        if infinite {
+            // The limit of the depth terms as `far` goes to infinity, which
+            // maps the near plane to -1 and infinity to 0.
+            let depth = unsafe { &mut result.r[2].__bindgen_anon_1 };
+            depth.z = 0.0;
+            depth.w = -clip_planes.near;
+        }
+        projection.rotation.apply_flip(&mut result);
+
+        let result = Self::from_raw(result);
+        match near_plane {
+            Some(plane) => oblique_near_plane(result, plane),
+            None => result,
+        }
     }
 }
 
+/// Replace the near plane of a perspective `projection` with `plane`, adapting
+/// Lengyel's technique to the PICA's clip space depth range of `-w..=0`.
+fn oblique_near_plane(projection: Matrix4, plane: FVec4) -> Matrix4 {
+    let Ok(inverse) = projection.inverse() else {
+        return projection;
+    };
+
+    // The corner of the clip space far plane furthest behind `plane`, which
+    // the new far plane passes through.
+    let clip_plane = &inverse.transpose() * plane;
+    let corner = &inverse * FVec4::new(clip_plane.x().signum(), clip_plane.y().signum(), 0.0, 1.0);
+
+    // The near plane is where `z + w = 0`, so the Z row becomes `scale * plane - w`.
+    let [x, y, _, w] = projection.rows_wzyx();
+    let z = plane * (w.dot(corner) / plane.dot(corner)) - w;
+    Matrix4::from_rows([x, y, z, w])
+}
+
 /// See [`Projection::orthographic`].
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Orthographic {
     clip_planes_x: Range<f32>,
     clip_planes_y: Range<f32>,
@@ -258,13 +346,41 @@ impl Projection<Orthographic> {
             clip_planes_z,
         })
     }
+
+    /// Construct an orthographic projection for a screen held in portrait
+    /// orientation, covering `width` by `height` units with the origin at the
+    /// bottom-left (e.g. 240x400 for the top screen, or 240x320 for the bottom
+    /// screen). See [`ScreenOrientation::Portrait`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{Projection, ClipPlanes, Matrix4};
+    /// #
+    /// let mtx: Matrix4 = Projection::portrait(
+    ///     240.0,
+    ///     400.0,
+    ///     ClipPlanes {
+    ///         near: 0.0,
+    ///         far: 100.0,
+    ///     },
+    /// )
+    /// .into();
+    /// ```
+    pub fn portrait(width: f32, height: f32, clip_planes_z: ClipPlanes) -> Self {
+        Self::orthographic(0.0..width, 0.0..height, clip_planes_z)
+            .screen(ScreenOrientation::Portrait)
+    }
 }
 
 impl From<Projection<Orthographic>> for Matrix4 {
     fn from(projection: Projection<Orthographic>) -> Self {
         let make_mtx = match projection.rotation {
             ScreenOrientation::Rotated => citro3d_sys::Mtx_OrthoTilt,
-            ScreenOrientation::None => citro3d_sys::Mtx_Ortho,
+            ScreenOrientation::None
+            | ScreenOrientation::Portrait
+            | ScreenOrientation::PortraitFlipped => citro3d_sys::Mtx_Ortho,
         };
 
         let Orthographic {
@@ -285,8 +401,11 @@ impl From<Projection<Orthographic>> for Matrix4 {
                 clip_planes_z.far,
                 projection.coordinates.is_left_handed(),
             );
-            Self::from_raw(out.assume_init())
         }
+
+        let mut out = unsafe { out.assume_init() };
+        projection.rotation.apply_flip(&mut out);
+        Self::from_raw(out)
     }
 }
 
@@ -296,6 +415,7 @@ impl From<Projection<Orthographic>> for Matrix4 {
 /// (or "handedness") of the coordinate system. Coordinates are always +Y-up,
 /// +X-right.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CoordinateOrientation {
     /// A left-handed coordinate system. +Z points into the screen.
     LeftHanded,
@@ -324,12 +444,41 @@ impl Default for CoordinateOrientation {
 /// in framebuffer coordinates is the physical bottom-left of the screen
 /// (i.e. the "width" is smaller than the "height").
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum ScreenOrientation {
     /// Rotate 90° clockwise to account for the 3DS screen rotation. Most
     /// applications will use this variant.
     Rotated,
     /// Do not apply any extra rotation to the projection.
     None,
+    /// Portrait orientation, for a console rotated 90° counterclockwise (the
+    /// right edge of the screen is at the top). Since render targets already use
+    /// the native portrait layout of the framebuffers, this is the same as
+    /// [`None`](Self::None). Set the same orientation on the target with
+    /// [`Target::set_orientation`](crate::render::Target::set_orientation) to
+    /// get a matching viewport and projection.
+    Portrait,
+    /// Portrait orientation, for a console rotated 90° clockwise (the left
+    /// edge of the screen is at the top).
+    PortraitFlipped,
+}
+
+impl ScreenOrientation {
+    /// Rotate a projection matrix by 180°, if needed for this orientation.
+    fn apply_flip(self, mtx: &mut citro3d_sys::C3D_Mtx) {
+        match self {
+            Self::PortraitFlipped => {
+                // Negating the X and Y rows flips the output in clip space.
+                for row in unsafe { &mut mtx.r[..2] } {
+                    for cell in unsafe { &mut row.c } {
+                        *cell = -*cell;
+                    }
+                }
+            }
+            Self::Rotated | Self::None | Self::Portrait => {}
+        }
+    }
 }
 
 impl Default for ScreenOrientation {
@@ -341,6 +490,7 @@ impl Default for ScreenOrientation {
 /// Configuration for calculating stereoscopic projections.
 // TODO: not totally happy with this name + API yet, but it works for now.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StereoDisplacement {
     /// The horizontal offset of the eye from center. Negative values
     /// correspond to the left eye, and positive values to the right eye.
@@ -373,6 +523,30 @@ impl StereoDisplacement {
 
         (left_eye, right_eye)
     }
+
+    /// Construct displacement for the left and right eyes from the position of
+    /// the 3D slider, as returned by [`ctru::os::current_3d_slider_state`].
+    /// The interocular distance grows linearly with the slider, from `0.0`
+    /// (no 3D effect) at the bottom to `max_interocular_distance` at the top.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::StereoDisplacement;
+    /// let slider = ctru::os::current_3d_slider_state();
+    /// let (left, right) = StereoDisplacement::from_slider(slider, 0.5, 2.0);
+    /// ```
+    pub fn from_slider(
+        slider: f32,
+        max_interocular_distance: f32,
+        screen_depth: f32,
+    ) -> (Self, Self) {
+        Self::new(
+            slider.clamp(0.0, 1.0) * max_interocular_distance,
+            screen_depth,
+        )
+    }
 }
 
 /// Configuration for the clipping planes of a projection.
@@ -387,7 +561,12 @@ impl StereoDisplacement {
 /// [`CoordinateOrientation`]. In other words, these values will be negated
 /// when used with a [`RightHanded`](CoordinateOrientation::RightHanded)
 /// orientation.
+///
+/// [`Perspective`] projections also support an infinitely distant far plane
+/// (see [`ClipPlanes::infinite`]), which spends less depth precision on
+/// distant geometry in large outdoor scenes.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClipPlanes {
     /// The Z-depth of the near clip plane, usually close or equal to zero.
     pub near: f32,
@@ -395,8 +574,35 @@ pub struct ClipPlanes {
     pub far: f32,
 }
 
+impl ClipPlanes {
+    /// Clip planes with the far plane infinitely far away. This is only
+    /// supported by [`Perspective`] projections.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{AspectRatio, ClipPlanes, Matrix4, Projection};
+    /// # use std::f32::consts::PI;
+    /// let mtx: Matrix4 =
+    ///     Projection::perspective(PI / 4.0, AspectRatio::TopScreen, ClipPlanes::infinite(0.1)).into();
+    /// ```
+    pub fn infinite(near: f32) -> Self {
+        Self {
+            near,
+            far: f32::INFINITY,
+        }
+    }
+
+    /// Whether the far plane is infinitely far away.
+    pub fn is_infinite(&self) -> bool {
+        self.far == f32::INFINITY
+    }
+}
+
 /// The aspect ratio of a projection plane.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 #[doc(alias = "C3D_AspectRatioTop")]
 #[doc(alias = "C3D_AspectRatioBot")]
@@ -407,6 +613,10 @@ pub enum AspectRatio {
     /// The aspect ratio of the 3DS' bottom screen.
     #[doc(alias = "C3D_AspectRatioBot")]
     BottomScreen,
+    /// The aspect ratio of the 3DS' top screen held in portrait orientation.
+    TopScreenPortrait,
+    /// The aspect ratio of the 3DS' bottom screen held in portrait orientation.
+    BottomScreenPortrait,
     /// A custom aspect ratio (should be calcualted as `width / height`).
     Other(f32),
 }
@@ -416,9 +626,140 @@ impl From<AspectRatio> for f32 {
         match ratio {
             AspectRatio::TopScreen => citro3d_sys::C3D_AspectRatioTop as f32,
             AspectRatio::BottomScreen => citro3d_sys::C3D_AspectRatioBot as f32,
+            AspectRatio::TopScreenPortrait => 1.0 / citro3d_sys::C3D_AspectRatioTop as f32,
+            AspectRatio::BottomScreenPortrait => 1.0 / citro3d_sys::C3D_AspectRatioBot as f32,
             AspectRatio::Other(ratio) => ratio,
         }
     }
 }
 
+/// A rectangle of the screen which a projection is drawn to, used to convert
+/// between clip space and screen pixels with [`Matrix4::project`] and
+/// [`Matrix4::unproject`].
+///
+/// Screen coordinates are in pixels as the screen is held, with the origin at
+/// the top-left and +Y down (like touch input). The `orientation` should match
+/// the [`ScreenOrientation`] of the projection.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Viewport {
+    /// The left edge of the viewport.
+    pub x: f32,
+    /// The top edge of the viewport.
+    pub y: f32,
+    /// The width of the viewport.
+    pub width: f32,
+    /// The height of the viewport.
+    pub height: f32,
+    /// The screen orientation of the projection.
+    pub orientation: ScreenOrientation,
+}
+
+impl Viewport {
+    /// The whole top screen (400x240), for a [`ScreenOrientation::Rotated`]
+    /// projection.
+    pub fn top_screen() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 400.0,
+            height: 240.0,
+            orientation: ScreenOrientation::Rotated,
+        }
+    }
+
+    /// The whole bottom screen (320x240), for a [`ScreenOrientation::Rotated`]
+    /// projection. This matches the coordinates of touch input.
+    pub fn bottom_screen() -> Self {
+        Self {
+            width: 320.0,
+            ..Self::top_screen()
+        }
+    }
+
+    /// Convert normalized device coordinates to screen coordinates.
+    pub(super) fn to_screen(&self, ndc_x: f32, ndc_y: f32) -> (f32, f32) {
+        let (right, up) = match self.orientation {
+            // See `Mtx_PerspTilt`: clip X points up and clip Y points left.
+            ScreenOrientation::Rotated => (-ndc_y, ndc_x),
+            ScreenOrientation::None | ScreenOrientation::Portrait => (ndc_x, ndc_y),
+            ScreenOrientation::PortraitFlipped => (-ndc_x, -ndc_y),
+        };
+
+        (
+            self.x + (right + 1.0) / 2.0 * self.width,
+            self.y + (1.0 - up) / 2.0 * self.height,
+        )
+    }
+
+    /// Convert screen coordinates to normalized device coordinates.
+    pub(super) fn to_ndc(&self, x: f32, y: f32) -> (f32, f32) {
+        let right = (x - self.x) / self.width * 2.0 - 1.0;
+        let up = 1.0 - (y - self.y) / self.height * 2.0;
+
+        match self.orientation {
+            ScreenOrientation::Rotated => (up, -right),
+            ScreenOrientation::None | ScreenOrientation::Portrait => (right, up),
+            ScreenOrientation::PortraitFlipped => (-right, -up),
+        }
+    }
+}
+
 // endregion
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::math::FVec3;
+
+    #[test]
+    fn stereo_from_slider() {
+        let (left, right) = StereoDisplacement::from_slider(0.5, 0.4, 2.0);
+        assert_abs_diff_eq!(left.displacement, -0.1);
+        assert_abs_diff_eq!(right.displacement, 0.1);
+        assert_abs_diff_eq!(right.screen_depth, 2.0);
+
+        let (left, _) = StereoDisplacement::from_slider(2.0, 0.4, 2.0);
+        assert_abs_diff_eq!(left.displacement, -0.2);
+    }
+
+    #[test]
+    fn infinite_perspective() {
+        let projection = |clip_planes| -> Matrix4 {
+            Projection::perspective(1.0, AspectRatio::TopScreen, clip_planes)
+                .screen(ScreenOrientation::None)
+                .into()
+        };
+        let near = 0.5;
+        let infinite = projection(ClipPlanes::infinite(near));
+        let very_far = projection(ClipPlanes { near, far: 1.0e7 });
+        assert_abs_diff_eq!(infinite, very_far);
+
+        // The near plane maps to a depth of -1.
+        let clip = &infinite * FVec3::new(0.0, 0.0, -near);
+        assert_abs_diff_eq!(clip.perspective_divide(), FVec4::new(0.0, 0.0, -1.0, 1.0));
+    }
+
+    #[test]
+    fn oblique_near_plane() {
+        let plane = FVec4::new(0.0, 0.5, -1.0, -2.0);
+        let mtx: Matrix4 = Projection::perspective(
+            1.0,
+            AspectRatio::TopScreen,
+            ClipPlanes {
+                near: 0.1,
+                far: 100.0,
+            },
+        )
+        .oblique_near_plane(plane)
+        .into();
+
+        // Points on the plane map to the near plane, at a depth of -1.
+        for point in [FVec3::new(0.0, 0.0, -2.0), FVec3::new(0.0, 2.0, -1.0)] {
+            let clip = (&mtx * point).perspective_divide();
+            assert_abs_diff_eq!(clip.z(), -1.0);
+        }
+    }
+}
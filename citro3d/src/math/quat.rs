@@ -0,0 +1,278 @@
+//! Quaternions, for representing rotations.
+
+use std::fmt;
+use std::mem::MaybeUninit;
+
+use super::{FVec, FVec3, FVec4, Matrix4};
+
+/// A quaternion, internally represented the same way as [`FVec`](super::FVec).
+/// The `i`, `j` and `k` components are stored as `x`, `y` and `z`, and the
+/// real component `r` as `w`.
+///
+/// Quaternions representing rotations should have a magnitude of `1.0`; see
+/// [`normalize`](Self::normalize).
+#[doc(alias = "C3D_FQuat")]
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct FQuat(pub(crate) citro3d_sys::C3D_FQuat);
+
+impl fmt::Debug for FQuat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FQuat")
+            .field("i", &self.i())
+            .field("j", &self.j())
+            .field("k", &self.k())
+            .field("r", &self.r())
+            .finish()
+    }
+}
+
+impl FQuat {
+    /// Create a new quaternion from its components.
+    #[doc(alias = "Quat_New")]
+    pub fn new(i: f32, j: f32, k: f32, r: f32) -> Self {
+        Self(unsafe { citro3d_sys::Quat_New(i, j, k, r) })
+    }
+
+    /// The identity quaternion, i.e. no rotation.
+    #[doc(alias = "Quat_Identity")]
+    pub fn identity() -> Self {
+        Self(unsafe { citro3d_sys::Quat_Identity() })
+    }
+
+    /// A rotation by `angle` radians around `axis`.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{FQuat, FVec3};
+    /// # use approx::assert_abs_diff_eq;
+    /// use std::f32::consts::FRAC_PI_2;
+    ///
+    /// let q = FQuat::from_axis_angle(FVec3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+    /// assert_abs_diff_eq!(q * FVec3::new(1.0, 0.0, 0.0), FVec3::new(0.0, 1.0, 0.0));
+    /// ```
+    #[doc(alias = "Quat_FromAxisAngle")]
+    pub fn from_axis_angle(axis: FVec3, angle: f32) -> Self {
+        Self(unsafe { citro3d_sys::Quat_FromAxisAngle(axis.0, angle) })
+    }
+
+    /// A rotation from Euler angles in radians, i.e. `pitch` around the X
+    /// axis, `yaw` around the Y axis and `roll` around the Z axis. To choose
+    /// the order the rotations are applied in, use
+    /// [`from_euler_angles`](Self::from_euler_angles).
+    #[doc(alias = "Quat_FromPitchYawRoll")]
+    pub fn from_euler(pitch: f32, yaw: f32, roll: f32) -> Self {
+        Self(unsafe { citro3d_sys::Quat_FromPitchYawRoll(pitch, yaw, roll, false) })
+    }
+
+    /// The rotation described by a transformation matrix. The matrix should
+    /// only contain a rotation (no scaling or translation).
+    #[doc(alias = "Quat_FromMtx")]
+    pub fn from_matrix(matrix: &Matrix4) -> Self {
+        Self(unsafe { citro3d_sys::Quat_FromMtx(matrix.as_raw()) })
+    }
+
+    /// Convert the rotation to a transformation matrix.
+    #[doc(alias = "Mtx_FromQuat")]
+    pub fn to_matrix(self) -> Matrix4 {
+        let mut out = MaybeUninit::uninit();
+        unsafe {
+            citro3d_sys::Mtx_FromQuat(out.as_mut_ptr(), self.0);
+            Matrix4::from_raw(out.assume_init())
+        }
+    }
+
+    /// The quaternion's `i` component.
+    pub fn i(self) -> f32 {
+        unsafe { self.0.__bindgen_anon_1.x }
+    }
+
+    /// The quaternion's `j` component.
+    pub fn j(self) -> f32 {
+        unsafe { self.0.__bindgen_anon_1.y }
+    }
+
+    /// The quaternion's `k` component.
+    pub fn k(self) -> f32 {
+        unsafe { self.0.__bindgen_anon_1.z }
+    }
+
+    /// The quaternion's real component.
+    pub fn r(self) -> f32 {
+        unsafe { self.0.__bindgen_anon_1.w }
+    }
+
+    /// The dot product of two quaternions.
+    #[doc(alias = "Quat_Dot")]
+    pub fn dot(self, rhs: Self) -> f32 {
+        unsafe { citro3d_sys::Quat_Dot(self.0, rhs.0) }
+    }
+
+    /// The magnitude of the quaternion.
+    pub fn magnitude(self) -> f32 {
+        FVec4::from_raw(self.0).magnitude()
+    }
+
+    /// Normalize the quaternion to a magnitude of `1.0`.
+    #[doc(alias = "Quat_Normalize")]
+    pub fn normalize(self) -> Self {
+        Self(unsafe { citro3d_sys::Quat_Normalize(self.0) })
+    }
+
+    /// The conjugate of the quaternion, i.e. with `i`, `j` and `k` negated.
+    /// For a normalized quaternion, this is the opposite rotation.
+    #[doc(alias = "Quat_Conjugate")]
+    pub fn conjugate(self) -> Self {
+        Self(unsafe { citro3d_sys::Quat_Conjugate(self.0) })
+    }
+
+    /// The inverse of the quaternion, such that `q * q.inverse()` is the
+    /// identity.
+    #[doc(alias = "Quat_Inverse")]
+    pub fn inverse(self) -> Self {
+        Self(unsafe { citro3d_sys::Quat_Inverse(self.0) })
+    }
+
+    /// Rotate a vector by this quaternion. This is the same as `self * v`.
+    #[doc(alias = "Quat_CrossFVec3")]
+    pub fn rotate_vec(self, v: FVec3) -> FVec3 {
+        FVec(unsafe { citro3d_sys::Quat_CrossFVec3(self.0, v.0) })
+    }
+
+    /// Spherically interpolate between two rotations (at `t = 0.0` and
+    /// `t = 1.0`), taking the shortest path.
+    ///
+    /// # Example
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::math::{FQuat, FVec3};
+    /// # use approx::assert_abs_diff_eq;
+    /// use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+    ///
+    /// let z = FVec3::new(0.0, 0.0, 1.0);
+    /// let a = FQuat::identity();
+    /// let b = FQuat::from_axis_angle(z, FRAC_PI_2);
+    /// assert_abs_diff_eq!(a.slerp(b, 0.5), FQuat::from_axis_angle(z, FRAC_PI_4));
+    /// ```
+    pub fn slerp(self, other: Self, t: f32) -> Self {
+        let (a, mut b) = unsafe { (self.0.c, other.0.c) };
+
+        let mut cos = a.iter().zip(&b).map(|(a, b)| a * b).sum::<f32>();
+        if cos < 0.0 {
+            // `q` and `-q` are the same rotation, pick the closer one.
+            b = b.map(|c| -c);
+            cos = -cos;
+        }
+
+        let (wa, wb) = if cos > 0.9995 {
+            // The rotations are nearly identical, so avoid dividing by ~zero.
+            (1.0 - t, t)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+
+        let mut out = self;
+        out.0.c = std::array::from_fn(|i| a[i] * wa + b[i] * wb);
+        out
+    }
+}
+
+impl From<FQuat> for Matrix4 {
+    fn from(q: FQuat) -> Self {
+        q.to_matrix()
+    }
+}
+
+impl From<Matrix4> for FQuat {
+    fn from(matrix: Matrix4) -> Self {
+        Self::from_matrix(&matrix)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::Quat> for FQuat {
+    fn from(q: glam::Quat) -> Self {
+        Self::new(q.x, q.y, q.z, q.w)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<FQuat> for glam::Quat {
+    fn from(q: FQuat) -> Self {
+        glam::Quat::from_xyzw(q.i(), q.j(), q.k(), q.r())
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<mint::Quaternion<f32>> for FQuat {
+    fn from(q: mint::Quaternion<f32>) -> Self {
+        Self::new(q.v.x, q.v.y, q.v.z, q.s)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<FQuat> for mint::Quaternion<f32> {
+    fn from(q: FQuat) -> Self {
+        mint::Quaternion {
+            v: [q.i(), q.j(), q.k()].into(),
+            s: q.r(),
+        }
+    }
+}
+
+/// Serialized as `[i, j, k, r]`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FQuat {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.i(), self.j(), self.k(), self.r()].serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FQuat {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [i, j, k, r] = <[f32; 4]>::deserialize(deserializer)?;
+        Ok(Self::new(i, j, k, r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn rotation() {
+        let q = FQuat::from_axis_angle(FVec3::new(0.0, 1.0, 0.0), FRAC_PI_2);
+        let v = FVec3::new(1.0, 2.0, 3.0);
+
+        assert_abs_diff_eq!(q * FQuat::identity(), q);
+        assert_abs_diff_eq!(q * q.conjugate(), FQuat::identity());
+        assert_abs_diff_eq!(q * q.inverse(), FQuat::identity());
+        assert_abs_diff_eq!(q.magnitude(), 1.0);
+        assert_abs_diff_eq!(q.conjugate() * (q * v), v);
+
+        let m = q.to_matrix();
+        let rotated = &m * v;
+        assert_abs_diff_eq!(
+            FVec3::new(rotated.x(), rotated.y(), rotated.z()),
+            q.rotate_vec(v)
+        );
+        assert_abs_diff_eq!(FQuat::from_matrix(&m), q);
+    }
+
+    #[test]
+    fn euler() {
+        let pitch = FQuat::from_euler(FRAC_PI_2, 0.0, 0.0);
+        assert_abs_diff_eq!(
+            pitch,
+            FQuat::from_axis_angle(FVec3::new(1.0, 0.0, 0.0), FRAC_PI_2)
+        );
+    }
+}
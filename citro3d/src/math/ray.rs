@@ -0,0 +1,197 @@
+//! Rays, for picking and simple collision tests.
+
+use super::{Aabb, FVec3, FVec4, Matrix4, Sphere, Viewport};
+
+/// A half-line starting at `origin` and going in `direction`.
+///
+/// The intersection tests return the distance along the ray to the first
+/// intersection in front of its origin, in units of `direction`'s length
+/// (i.e. the intersection point is [`at(t)`](Self::at)).
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::{FVec3, Ray, Sphere};
+/// let ray = Ray::new(FVec3::splat(0.0), FVec3::new(0.0, 0.0, -1.0));
+/// let target = Sphere::new(FVec3::new(0.0, 0.0, -5.0), 1.0);
+///
+/// assert_eq!(ray.intersect_sphere(&target), Some(4.0));
+/// assert_eq!(ray.at(4.0), FVec3::new(0.0, 0.0, -4.0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    /// The start of the ray.
+    pub origin: FVec3,
+    /// The direction of the ray. This doesn't need to be normalized.
+    pub direction: FVec3,
+}
+
+impl Ray {
+    /// Create a ray from its origin and direction.
+    pub fn new(origin: FVec3, direction: FVec3) -> Self {
+        Self { origin, direction }
+    }
+
+    /// The ray through a point on the screen, e.g. a touch on the bottom
+    /// screen, from the near plane towards the far plane. `matrix` is the
+    /// combined projection and view matrix, so the ray is in world space.
+    ///
+    /// # Errors
+    ///
+    /// If the matrix has no inverse, it is returned as an [`Err`].
+    pub fn from_screen(
+        matrix: &Matrix4,
+        x: f32,
+        y: f32,
+        viewport: &Viewport,
+    ) -> Result<Self, Matrix4> {
+        let near = matrix.unproject(FVec3::new(x, y, -1.0), viewport)?;
+        let far = matrix.unproject(FVec3::new(x, y, 0.0), viewport)?;
+        Ok(Self::new(near, (far - near).normalize()))
+    }
+
+    /// The point at distance `t` along the ray.
+    pub fn at(&self, t: f32) -> FVec3 {
+        self.origin + self.direction * t
+    }
+
+    /// Intersect the ray with a plane, given as `(a, b, c, d)` for the plane
+    /// `ax + by + cz + d = 0`. Returns [`None`] if the ray is parallel to the
+    /// plane or points away from it.
+    pub fn intersect_plane(&self, plane: FVec4) -> Option<f32> {
+        let normal = FVec3::new(plane.x(), plane.y(), plane.z());
+        let denominator = normal.dot(self.direction);
+        if denominator == 0.0 {
+            return None;
+        }
+
+        let t = -(normal.dot(self.origin) + plane.w()) / denominator;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Intersect the ray with an axis-aligned bounding box. If the origin is
+    /// inside the box, this returns `0.0`.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let origin = [self.origin.x(), self.origin.y(), self.origin.z()];
+        let direction = [self.direction.x(), self.direction.y(), self.direction.z()];
+        let min = [aabb.min.x(), aabb.min.y(), aabb.min.z()];
+        let max = [aabb.max.x(), aabb.max.y(), aabb.max.z()];
+
+        // Intersect the ray with the "slab" between each pair of faces.
+        let mut near = 0.0_f32;
+        let mut far = f32::INFINITY;
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let t0 = (min[axis] - origin[axis]) / direction[axis];
+            let t1 = (max[axis] - origin[axis]) / direction[axis];
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+
+        (near <= far).then_some(near)
+    }
+
+    /// Intersect the ray with a sphere. If the origin is inside the sphere,
+    /// this returns `0.0`.
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let offset = self.origin - sphere.center;
+        let a = self.direction.dot(self.direction);
+        let b = offset.dot(self.direction);
+        let c = offset.dot(offset) - sphere.radius * sphere.radius;
+
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+
+        let discriminant = b * b - a * c;
+        if discriminant < 0.0 || a == 0.0 {
+            return None;
+        }
+
+        let t = (-b - discriminant.sqrt()) / a;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Intersect the ray with a triangle, from either side. This uses the
+    /// [Möller–Trumbore](https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm)
+    /// algorithm.
+    pub fn intersect_triangle(&self, [a, b, c]: [FVec3; 3]) -> Option<f32> {
+        let edge1 = b - a;
+        let edge2 = c - a;
+
+        let p = self.direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        if determinant.abs() < f32::EPSILON {
+            // The ray is parallel to the triangle.
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+
+        let offset = self.origin - a;
+        let u = offset.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = offset.cross(edge1);
+        let v = self.direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(q) * inverse;
+        (t >= 0.0).then_some(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn intersections() {
+        let ray = Ray::new(FVec3::new(0.0, 1.0, 0.0), FVec3::new(1.0, 0.0, 0.0));
+
+        assert_abs_diff_eq!(
+            ray.intersect_plane(FVec4::new(-1.0, 0.0, 0.0, 2.0))
+                .unwrap(),
+            2.0
+        );
+        assert!(
+            ray.intersect_plane(FVec4::new(1.0, 0.0, 0.0, 2.0))
+                .is_none()
+        );
+        assert!(
+            ray.intersect_plane(FVec4::new(0.0, 1.0, 0.0, 0.0))
+                .is_none()
+        );
+
+        let aabb = Aabb::new(FVec3::new(3.0, 0.0, -1.0), FVec3::new(4.0, 2.0, 1.0));
+        assert_abs_diff_eq!(ray.intersect_aabb(&aabb).unwrap(), 3.0);
+        let behind = Aabb::new(FVec3::new(-4.0, 0.0, -1.0), FVec3::new(-3.0, 2.0, 1.0));
+        assert!(ray.intersect_aabb(&behind).is_none());
+
+        let sphere = Sphere::new(FVec3::new(5.0, 1.0, 0.0), 1.0);
+        assert_abs_diff_eq!(ray.intersect_sphere(&sphere).unwrap(), 4.0);
+        let inside = Sphere::new(FVec3::new(0.0, 1.0, 0.0), 1.0);
+        assert_eq!(ray.intersect_sphere(&inside), Some(0.0));
+
+        let triangle = [
+            FVec3::new(2.0, 0.0, -1.0),
+            FVec3::new(2.0, 3.0, -1.0),
+            FVec3::new(2.0, 0.0, 2.0),
+        ];
+        assert_abs_diff_eq!(ray.intersect_triangle(triangle).unwrap(), 2.0);
+        let missed = triangle.map(|vertex| vertex + FVec3::new(0.0, 5.0, 0.0));
+        assert!(ray.intersect_triangle(missed).is_none());
+    }
+}
@@ -0,0 +1,181 @@
+//! A stack of transformation matrices, for hierarchical transforms.
+
+use super::Matrix4;
+use crate::render::Frame;
+use crate::{shader, uniform};
+
+/// A stack of transformation matrices, like the fixed-function matrix stacks
+/// of older graphics APIs. This is useful for hierarchical transforms while
+/// traversing a scene: [`push`](Self::push) before transforming a child node
+/// and [`pop`](Self::pop) afterwards to get the parent's transform back.
+///
+/// The stack can be bound to a shader uniform, in which case
+/// [`update`](Self::update) uploads the current matrix whenever it has changed.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::math::{Matrix4, MatrixStack};
+/// # use citro3d::{render, shader, uniform};
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// let mut stack = MatrixStack::new();
+/// stack.bind(shader::Type::Vertex, uniform::Index::from(0));
+///
+/// let mut frame = instance.begin_frame(render::FrameFlags::SYNC_DRAW).unwrap();
+/// stack.current_mut().translate(0.0, 0.0, -5.0);
+/// stack.scoped(|stack| {
+///     stack.current_mut().rotate_y(1.0);
+///     stack.update(&mut frame);
+///     // Draw the child node...
+/// });
+/// stack.update(&mut frame);
+/// // Draw the parent node...
+/// ```
+#[doc(alias = "C3D_MtxStack")]
+#[derive(Debug, Clone)]
+pub struct MatrixStack {
+    /// The matrices on the stack. This is never empty, and the last matrix is
+    /// the current one.
+    matrices: Vec<Matrix4>,
+    uniform: Option<(shader::Type, uniform::Index)>,
+    dirty: bool,
+}
+
+impl MatrixStack {
+    /// Create a stack with only the identity matrix on it.
+    #[doc(alias = "MtxStack_Init")]
+    pub fn new() -> Self {
+        Self {
+            matrices: vec![Matrix4::identity()],
+            uniform: None,
+            dirty: true,
+        }
+    }
+
+    /// Bind the stack to a uniform, so [`update`](Self::update) uploads the
+    /// current matrix to it.
+    #[doc(alias = "MtxStack_Bind")]
+    pub fn bind(&mut self, shader: shader::Type, index: uniform::Index) {
+        self.uniform = Some((shader, index));
+        self.dirty = true;
+    }
+
+    /// Unbind the stack from its uniform, if any.
+    pub fn unbind(&mut self) {
+        self.uniform = None;
+    }
+
+    /// Get the current matrix, i.e. the top of the stack.
+    pub fn current(&self) -> &Matrix4 {
+        self.matrices.last().unwrap()
+    }
+
+    /// Get a mutable reference to the current matrix, e.g. to transform it.
+    pub fn current_mut(&mut self) -> &mut Matrix4 {
+        self.dirty = true;
+        self.matrices.last_mut().unwrap()
+    }
+
+    /// Replace the current matrix.
+    pub fn set(&mut self, matrix: Matrix4) {
+        *self.current_mut() = matrix;
+    }
+
+    /// Multiply the current matrix by `matrix`, i.e. apply `matrix` before the
+    /// current transform.
+    pub fn multiply(&mut self, matrix: &Matrix4) {
+        let current = self.current_mut();
+        *current = *current * *matrix;
+    }
+
+    /// Push a copy of the current matrix onto the stack.
+    #[doc(alias = "MtxStack_Push")]
+    pub fn push(&mut self) {
+        self.matrices.push(*self.current());
+    }
+
+    /// Pop the current matrix off the stack, restoring the previous one. The
+    /// bottom of the stack can't be popped, in which case this returns [`None`].
+    #[doc(alias = "MtxStack_Pop")]
+    pub fn pop(&mut self) -> Option<Matrix4> {
+        if self.matrices.len() == 1 {
+            return None;
+        }
+
+        self.dirty = true;
+        self.matrices.pop()
+    }
+
+    /// Run `f` with a copy of the current matrix pushed onto the stack, then
+    /// pop it again.
+    pub fn scoped<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let depth = self.depth();
+        self.push();
+        let result = f(self);
+        self.matrices.truncate(depth);
+        self.dirty = true;
+        result
+    }
+
+    /// The number of matrices on the stack, which is always at least 1.
+    pub fn depth(&self) -> usize {
+        self.matrices.len()
+    }
+
+    /// Upload the current matrix to the bound uniform if it has changed since
+    /// the last update. This does nothing if the stack isn't bound.
+    #[doc(alias = "MtxStack_Update")]
+    pub fn update(&mut self, frame: &mut Frame<'_>) {
+        let Some((shader, index)) = self.uniform else {
+            return;
+        };
+        if !self.dirty {
+            return;
+        }
+
+        let current = *self.current();
+        match shader {
+            shader::Type::Vertex => frame.bind_vertex_uniform(index, current),
+            shader::Type::Geometry => frame.bind_geometry_uniform(index, current),
+        }
+        self.dirty = false;
+    }
+}
+
+impl Default for MatrixStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn push_pop() {
+        let mut stack = MatrixStack::new();
+        assert!(stack.pop().is_none());
+
+        let parent = Matrix4::identity().translated(1.0, 2.0, 3.0);
+        stack.set(parent);
+        stack.push();
+        stack.current_mut().rotate_x(1.0);
+        assert_eq!(stack.depth(), 2);
+
+        stack.pop().unwrap();
+        assert_abs_diff_eq!(*stack.current(), parent);
+
+        let scale = Matrix4::diagonal(2.0, 2.0, 2.0, 1.0);
+        stack.scoped(|stack| {
+            stack.push();
+            stack.multiply(&scale);
+            assert_eq!(stack.depth(), 3);
+        });
+        assert_eq!(stack.depth(), 1);
+        assert_abs_diff_eq!(*stack.current(), parent);
+    }
+}
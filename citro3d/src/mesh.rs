@@ -0,0 +1,205 @@
+//! CPU-side processing of indexed triangle meshes, e.g. to generate the extra
+//! vertex attributes needed for lighting of runtime-generated geometry.
+//!
+//! These functions return one value per vertex, which can be interleaved with
+//! the rest of the vertex data and described with
+//! [`attrib::Info::add_loader`](crate::attrib::Info::add_loader).
+
+use crate::buffer::Index;
+use crate::math::{FVec3, FVec4};
+use crate::{Error, Result};
+
+/// Compute smooth per-vertex normals for an indexed triangle list, by averaging
+/// the normals of the triangles sharing each vertex, weighted by their area.
+///
+/// Triangles are expected to be wound counterclockwise when seen from the
+/// front. Vertices which aren't part of any triangle get a zero normal.
+///
+/// # Errors
+///
+/// * [`Error::InvalidSize`] if the length of `indices` is not a multiple of 3.
+/// * [`Error::IndexOutOfBounds`] if any index is out of bounds for `positions`.
+pub fn smooth_normals<I>(positions: &[FVec3], indices: &[I]) -> Result<Vec<FVec3>>
+where
+    I: Index + Copy + Into<usize>,
+{
+    validate(positions.len(), indices)?;
+
+    let mut normals = vec![FVec3::splat(0.0); positions.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[i].into());
+        // The magnitude of the cross product is twice the triangle's area.
+        let face = (positions[b] - positions[a]).cross(positions[c] - positions[a]);
+
+        for v in [a, b, c] {
+            normals[v] = normals[v] + face;
+        }
+    }
+
+    Ok(normals.into_iter().map(normalize_or_zero).collect())
+}
+
+/// Compute per-vertex tangents for an indexed triangle list, for use with
+/// normal (bump) mapping.
+///
+/// The tangent points in the direction of increasing U texture coordinate, and
+/// is made perpendicular to the vertex's normal. Its `w` component is `1.0` or
+/// `-1.0`, giving the handedness of the tangent space: use [`bitangent`] to
+/// compute the bitangent (the direction of increasing V) from it.
+///
+/// # Errors
+///
+/// * [`Error::InvalidSize`] if the length of `indices` is not a multiple of 3,
+///   or if `normals` or `uvs` don't have the same length as `positions`.
+/// * [`Error::IndexOutOfBounds`] if any index is out of bounds for `positions`.
+pub fn tangents<I>(
+    positions: &[FVec3],
+    normals: &[FVec3],
+    uvs: &[[f32; 2]],
+    indices: &[I],
+) -> Result<Vec<FVec4>>
+where
+    I: Index + Copy + Into<usize>,
+{
+    if normals.len() != positions.len() || uvs.len() != positions.len() {
+        return Err(Error::InvalidSize);
+    }
+    validate(positions.len(), indices)?;
+
+    let mut u_dirs = vec![FVec3::splat(0.0); positions.len()];
+    let mut v_dirs = vec![FVec3::splat(0.0); positions.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[i].into());
+
+        let edge1 = positions[b] - positions[a];
+        let edge2 = positions[c] - positions[a];
+        let [du1, dv1] = [uvs[b][0] - uvs[a][0], uvs[b][1] - uvs[a][1]];
+        let [du2, dv2] = [uvs[c][0] - uvs[a][0], uvs[c][1] - uvs[a][1]];
+
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() <= f32::EPSILON {
+            // Degenerate texture mapping, which doesn't define a direction.
+            continue;
+        }
+
+        let u_dir = (edge1 * dv2 - edge2 * dv1) / det;
+        let v_dir = (edge2 * du1 - edge1 * du2) / det;
+
+        for v in [a, b, c] {
+            u_dirs[v] = u_dirs[v] + u_dir;
+            v_dirs[v] = v_dirs[v] + v_dir;
+        }
+    }
+
+    let tangents = normals
+        .iter()
+        .zip(u_dirs.into_iter().zip(v_dirs))
+        .map(|(&normal, (u_dir, v_dir))| {
+            // Gram-Schmidt orthogonalize against the normal.
+            let mut tangent = normalize_or_zero(u_dir - normal * normal.dot(u_dir));
+            if tangent == FVec3::splat(0.0) {
+                tangent = any_perpendicular(normal);
+            }
+
+            let handedness = if normal.cross(tangent).dot(v_dir) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+
+            FVec4::new(tangent.x(), tangent.y(), tangent.z(), handedness)
+        })
+        .collect();
+
+    Ok(tangents)
+}
+
+/// Compute the bitangent of a vertex from its normal and a tangent returned
+/// by [`tangents`].
+pub fn bitangent(normal: FVec3, tangent: FVec4) -> FVec3 {
+    normal.cross(FVec3::new(tangent.x(), tangent.y(), tangent.z())) * tangent.w()
+}
+
+fn validate<I>(vertex_count: usize, indices: &[I]) -> Result<()>
+where
+    I: Index + Copy + Into<usize>,
+{
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::InvalidSize);
+    }
+
+    for &idx in indices {
+        let idx: usize = idx.into();
+        if idx >= vertex_count {
+            return Err(Error::IndexOutOfBounds {
+                idx: idx.try_into()?,
+                len: vertex_count.try_into()?,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn normalize_or_zero(v: FVec3) -> FVec3 {
+    if v.magnitude() > 0.0 {
+        v.normalize()
+    } else {
+        v
+    }
+}
+
+fn any_perpendicular(normal: FVec3) -> FVec3 {
+    let axis = if normal.x().abs() < 0.9 {
+        FVec3::new(1.0, 0.0, 0.0)
+    } else {
+        FVec3::new(0.0, 1.0, 0.0)
+    };
+    normalize_or_zero(axis.cross(normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    /// A unit quad in the XY plane, facing +Z, with UVs matching X and Y.
+    fn quad() -> (Vec<FVec3>, Vec<[f32; 2]>, Vec<u16>) {
+        let positions = vec![
+            FVec3::new(0.0, 0.0, 0.0),
+            FVec3::new(1.0, 0.0, 0.0),
+            FVec3::new(1.0, 1.0, 0.0),
+            FVec3::new(0.0, 1.0, 0.0),
+        ];
+        let uvs = vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        (positions, uvs, vec![0, 1, 2, 0, 2, 3])
+    }
+
+    #[test]
+    fn quad_normals_and_tangents() {
+        let (positions, uvs, indices) = quad();
+
+        let normals = smooth_normals(&positions, &indices).unwrap();
+        for &normal in &normals {
+            assert_abs_diff_eq!(normal, FVec3::new(0.0, 0.0, 1.0));
+        }
+
+        let tangents = tangents(&positions, &normals, &uvs, &indices).unwrap();
+        for (&normal, &tangent) in normals.iter().zip(&tangents) {
+            assert_abs_diff_eq!(tangent, FVec4::new(1.0, 0.0, 0.0, 1.0));
+            assert_abs_diff_eq!(bitangent(normal, tangent), FVec3::new(0.0, 1.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn out_of_bounds_index() {
+        let (positions, _, _) = quad();
+        assert!(matches!(
+            smooth_normals(&positions, &[0u8, 1, 4]),
+            Err(Error::IndexOutOfBounds { idx: 4, len: 4 })
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+//! Prepare per-frame data on another CPU core while the main thread renders.
+//!
+//! A [`Pipeline`] owns two buffers of frame data and a worker thread (usually on
+//! the system core). While the main thread renders with one buffer, the worker
+//! fills the other, and they are swapped every frame. Only data preparation
+//! happens on the worker: GPU commands must still be submitted from the thread
+//! which owns the [`Instance`](crate::Instance).
+//!
+//! Vertex data can be prepared directly into linear memory (e.g. a
+//! [`LinearBuffer`](crate::buffer::LinearBuffer)), so no copy is needed before drawing. The
+//! types used to describe buffers and uniforms ([`buffer::Info`](crate::buffer::Info),
+//! [`attrib::Info`](crate::attrib::Info), [`Matrix4`](crate::math::Matrix4),
+//! [`Uniform`](crate::uniform::Uniform)) are all [`Send`], so they can be
+//! built on the worker too.
+//!
+//! # Enabling the system core
+//!
+//! Applications can only create threads on the system core (core 1) after
+//! allowing themselves some of its time, e.g. with
+//! [`Apt::set_app_cpu_time_limit`](ctru::services::apt::Apt::set_app_cpu_time_limit).
+//! Otherwise, [`Pipeline::spawn`] fails with [`Error::FailedToInitialize`].
+
+use std::ffi::c_void;
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::{Error, Result};
+
+/// The processor ID of the system core, which applications can use for
+/// background work.
+pub const SYSTEM_CORE: libc::c_int = 1;
+
+/// The stack size of pipeline worker threads.
+const STACK_SIZE: usize = 64 * 1024;
+
+/// Runs a preparation function on another core, double-buffering its output.
+/// See the [module documentation](self) for details.
+pub struct Pipeline<T: Send + 'static> {
+    to_worker: Option<Sender<T>>,
+    from_worker: Receiver<T>,
+    thread: ctru_sys::Thread,
+}
+
+impl<T: Send + 'static> Pipeline<T> {
+    /// Spawn a worker on the [system core](SYSTEM_CORE) which calls `prepare` to
+    /// fill each buffer before it is handed out by [`Pipeline::next`].
+    ///
+    /// Both buffers are prepared once immediately, so the first two frames
+    /// don't need to wait for the worker to catch up.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the worker thread could not be created.
+    pub fn spawn(buffers: [T; 2], prepare: impl FnMut(&mut T) + Send + 'static) -> Result<Self> {
+        Self::spawn_on(SYSTEM_CORE, buffers, prepare)
+    }
+
+    /// Like [`Pipeline::spawn`], but on the given processor. `-2` uses the
+    /// application's default core, and New 3DS systems have extra cores `2`
+    /// and `3` available.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the worker thread could not be created.
+    #[doc(alias = "threadCreate")]
+    pub fn spawn_on(
+        processor_id: libc::c_int,
+        buffers: [T; 2],
+        mut prepare: impl FnMut(&mut T) + Send + 'static,
+    ) -> Result<Self> {
+        let (to_worker, worker_rx) = mpsc::channel::<T>();
+        let (worker_tx, from_worker) = mpsc::channel();
+
+        for buffer in buffers {
+            // The receiver is alive until the closure below is dropped.
+            let _ = to_worker.send(buffer);
+        }
+
+        let work: Box<dyn FnOnce() + Send> = Box::new(move || {
+            // Dropping either end of the channels (including by panicking
+            // here) tells the other thread that the pipeline is shutting down.
+            while let Ok(mut buffer) = worker_rx.recv() {
+                prepare(&mut buffer);
+                if worker_tx.send(buffer).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let thread = unsafe { spawn_thread(processor_id, work)? };
+
+        Ok(Self {
+            to_worker: Some(to_worker),
+            from_worker,
+            thread,
+        })
+    }
+
+    /// Wait for the next prepared buffer. The buffer is given back to the
+    /// worker to be prepared again when the returned [`Prepared`] is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the preparation function panicked.
+    pub fn next(&mut self) -> Prepared<'_, T> {
+        let value = self
+            .from_worker
+            .recv()
+            .expect("pipeline worker panicked while preparing data");
+
+        Prepared {
+            value: Some(value),
+            pipeline: self,
+        }
+    }
+}
+
+impl<T: Send + 'static> Drop for Pipeline<T> {
+    #[doc(alias = "threadJoin")]
+    #[doc(alias = "threadFree")]
+    fn drop(&mut self) {
+        // Closing the channel stops the worker once it finishes its current buffer.
+        drop(self.to_worker.take());
+
+        unsafe {
+            let _ = ctru_sys::threadJoin(self.thread, u64::MAX);
+            ctru_sys::threadFree(self.thread);
+        }
+    }
+}
+
+/// A prepared buffer handed out by [`Pipeline::next`].
+pub struct Prepared<'pipeline, T: Send + 'static> {
+    value: Option<T>,
+    pipeline: &'pipeline Pipeline<T>,
+}
+
+impl<T: Send + 'static> Deref for Prepared<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().unwrap()
+    }
+}
+
+impl<T: Send + 'static> DerefMut for Prepared<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().unwrap()
+    }
+}
+
+impl<T: Send + 'static> Drop for Prepared<'_, T> {
+    fn drop(&mut self) {
+        if let (Some(value), Some(to_worker)) = (self.value.take(), &self.pipeline.to_worker) {
+            // If the worker is gone, the error surfaces in the next call to `next`.
+            let _ = to_worker.send(value);
+        }
+    }
+}
+
+/// Create a libctru thread on `processor_id` which runs `work`.
+///
+/// # Safety
+///
+/// The returned thread must be joined and freed.
+unsafe fn spawn_thread(
+    processor_id: libc::c_int,
+    work: Box<dyn FnOnce() + Send>,
+) -> Result<ctru_sys::Thread> {
+    unsafe extern "C" fn entry(arg: *mut c_void) {
+        let work = unsafe { Box::from_raw(arg.cast::<Box<dyn FnOnce() + Send>>()) };
+        // Unwinding across the FFI boundary is undefined behavior, and the
+        // pipeline notices the worker is gone through its channels anyway.
+        let _ = panic::catch_unwind(AssertUnwindSafe(work));
+    }
+
+    // Run at the same priority as the spawning thread. If this fails, the
+    // highest priority available to applications is used instead.
+    let mut priority = 0x18;
+    let _ = unsafe { ctru_sys::svcGetThreadPriority(&mut priority, ctru_sys::CUR_THREAD_HANDLE) };
+
+    let arg = Box::into_raw(Box::new(work));
+    let thread = unsafe {
+        ctru_sys::threadCreate(
+            Some(entry),
+            arg.cast(),
+            STACK_SIZE,
+            priority,
+            processor_id,
+            false,
+        )
+    };
+
+    if thread.is_null() {
+        drop(unsafe { Box::from_raw(arg) });
+        Err(Error::FailedToInitialize)
+    } else {
+        Ok(thread)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use crate::math::Matrix4;
+    use crate::uniform::Uniform;
+    use crate::{attrib, buffer};
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn frame_data_is_send() {
+        assert_send::<buffer::Info>();
+        assert_send::<attrib::Info>();
+        assert_send::<Matrix4>();
+        assert_send::<Uniform>();
+    }
+
+    /// Sets a flag when dropped, i.e. when the worker thread has finished.
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn pipeline_prepares_in_order() {
+        let finished = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(Arc::clone(&finished));
+        let mut frame = 0;
+
+        // Use the application core, which doesn't need any time set aside.
+        let mut pipeline = Pipeline::spawn_on(-2, [0, 0], move |data: &mut u32| {
+            let _flag = &flag;
+            frame += 1;
+            *data = frame;
+        })
+        .unwrap();
+
+        assert_eq!(*pipeline.next(), 1);
+        assert_eq!(*pipeline.next(), 2);
+        // The first buffer was handed back, so it is prepared again.
+        assert_eq!(*pipeline.next(), 3);
+
+        drop(pipeline);
+        assert!(finished.load(Ordering::SeqCst));
+    }
+}
@@ -1,35 +1,56 @@
 //! This module provides render target types and options for controlling transfer
 //! of data to the GPU, including the format of color and depth data to be rendered.
 
+use std::any::Any;
 use std::cell::RefMut;
+use std::ffi::c_void;
+use std::pin::Pin;
 use std::rc::Rc;
+use std::time::Duration;
 
 use citro3d_sys::{
-    C3D_DEPTHTYPE, C3D_RenderTarget, C3D_RenderTargetCreate, C3D_RenderTargetDelete,
+    C3D_DEPTHTYPE, C3D_RenderTarget, C3D_RenderTargetCreate, C3D_RenderTargetCreateFromTex,
+    C3D_RenderTargetDelete,
 };
+use ctru::linear::LinearAllocator;
 use ctru::services::gfx::Screen;
 use ctru::services::gspgpu::FramebufferFormat;
 use ctru_sys::{GPU_COLORBUF, GPU_DEPTHBUF};
 
+use crate::buffer::{self, Index, Indices};
+use crate::color::Color;
+use crate::math::{ClipPlanes, Orthographic, Projection, ScreenOrientation, Viewport};
+use crate::uniform::{self, Uniform};
 use crate::{Error, RenderQueue, Result};
+use crate::{attrib, shader, texenv, texture};
 
 pub mod effect;
-mod transfer;
+mod immediate;
+pub mod transfer;
+#[cfg(feature = "validation")]
+mod validation;
+
+pub use immediate::ImmediateDraw;
 
 /// A render target for `citro3d`. Frame data will be written to this target
 /// to be rendered on the GPU and displayed on the screen.
 #[doc(alias = "C3D_RenderTarget")]
 pub struct Target<'screen> {
     raw: *mut citro3d_sys::C3D_RenderTarget,
-    // This is unused after construction, but ensures unique access to the
-    // screen this target writes to during rendering
-    _screen: RefMut<'screen, dyn Screen>,
+    color_format: ColorFormat,
+    transfer_flags: transfer::Flags,
+    orientation: ScreenOrientation,
+    // Ensures unique access to the screen this target writes to during rendering
+    screen: RefMut<'screen, dyn Screen>,
     _queue: Rc<RenderQueue>,
+    // Keeps a shared depth buffer alive for as long as this target uses it.
+    _depth: Option<Rc<DepthBuffer>>,
 }
 
 impl Drop for Target<'_> {
     #[doc(alias = "C3D_RenderTargetDelete")]
     fn drop(&mut self) {
+        self.detach_output();
         unsafe {
             C3D_RenderTargetDelete(self.raw);
         }
@@ -47,6 +68,12 @@ impl<'screen> Target<'screen> {
         depth_format: Option<DepthFormat>,
         queue: Rc<RenderQueue>,
     ) -> Result<Self> {
+        // The GPU can't address render buffers larger than 1024x1024.
+        let valid_dimension = |d: usize| (1..=1024).contains(&d);
+        if !valid_dimension(width) || !valid_dimension(height) {
+            return Err(Error::InvalidSize);
+        }
+
         let color_format: ColorFormat = screen.framebuffer_format().into();
 
         let raw = unsafe {
@@ -58,27 +85,501 @@ impl<'screen> Target<'screen> {
             )
         };
 
+        // With valid dimensions, creation only fails if the color or depth
+        // buffer couldn't be allocated.
         if raw.is_null() {
-            return Err(Error::FailedToInitialize);
+            return Err(Error::OutOfMemory);
         }
 
+        let target = Self {
+            raw,
+            color_format,
+            transfer_flags: transfer::Flags::default(),
+            orientation: ScreenOrientation::default(),
+            screen,
+            _queue: queue,
+            _depth: None,
+        };
         // Set the render target to actually output to the given screen
-        let flags = transfer::Flags::default()
-            .in_format(color_format.into())
-            .out_format(color_format.into());
+        target.link_output();
+
+        Ok(target)
+    }
+
+    /// Create a new render target without a depth buffer of its own, which
+    /// uses the given shared `depth` buffer instead.
+    pub(crate) fn with_shared_depth(
+        width: usize,
+        height: usize,
+        screen: RefMut<'screen, dyn Screen>,
+        depth: &Rc<DepthBuffer>,
+        queue: Rc<RenderQueue>,
+    ) -> Result<Self> {
+        if (width, height) != depth.size() {
+            return Err(Error::InvalidSize);
+        }
+
+        let mut target = Self::new(width, height, screen, None, queue)?;
+
+        // Since the target was created without a depth buffer, `citro3d` won't
+        // try to free this one when the target is deleted.
+        unsafe {
+            citro3d_sys::C3D_FrameBufDepth(
+                &mut (*target.raw).frameBuf,
+                depth.raw,
+                depth.format as GPU_DEPTHBUF,
+            );
+        }
+        target._depth = Some(Rc::clone(depth));
+
+        Ok(target)
+    }
+
+    /// Display this target on a different screen from now on, e.g. to move a
+    /// view from the top screen to the bottom screen without recreating its
+    /// buffers. Returns the screen which was previously used.
+    ///
+    /// The target keeps the color format it was created with, and is converted
+    /// to the format of the new screen's framebuffer when it is displayed.
+    #[doc(alias = "C3D_RenderTargetSetOutput")]
+    pub fn set_output(
+        &mut self,
+        screen: RefMut<'screen, dyn Screen>,
+    ) -> RefMut<'screen, dyn Screen> {
+        let previous = std::mem::replace(&mut self.screen, screen);
+        self.link_output();
+        previous
+    }
+
+    /// Clear the render target with the given 32-bit RGBA color and depth buffer value.
+    /// Use `flags` to specify whether color and/or depth should be overwritten.
+    #[doc(alias = "C3D_RenderTargetClear")]
+    pub fn clear(&mut self, flags: ClearFlags, rgba_color: u32, depth: u32) {
+        unsafe {
+            citro3d_sys::C3D_RenderTargetClear(self.raw, flags.bits(), rgba_color, depth);
+        }
+    }
+
+    /// Clear the render target with the given color (with full alpha), `depth`
+    /// in [0, 1] and `stencil` value, which are converted to the formats of
+    /// the target's buffers. Use `flags` to specify whether color and/or depth
+    /// should be overwritten; the stencil value is only written along with the
+    /// depth of a [`Depth24Stencil8`](DepthFormat::Depth24Stencil8) buffer.
+    #[doc(alias = "C3D_RenderTargetClear")]
+    pub fn clear_with(&mut self, flags: ClearFlags, color: Color, depth: f32, stencil: u8) {
+        clear_target(&*self, flags, color, depth, stencil);
+    }
+
+    /// Set the flags used to transfer the rendered image to the screen, e.g. to
+    /// downscale a target twice the size of the screen for anti-aliasing.
+    ///
+    /// The input and output formats of `flags` are always replaced with the
+    /// formats of the target and the screen.
+    #[doc(alias = "C3D_RenderTargetSetOutput")]
+    pub fn set_transfer_flags(&mut self, flags: transfer::Flags) {
+        self.transfer_flags = flags;
+        self.link_output();
+    }
+
+    /// The flags used to transfer the rendered image to the screen.
+    pub fn transfer_flags(&self) -> transfer::Flags {
+        self.link_flags()
+    }
+
+    /// Set the orientation the screen is held in while this target is
+    /// displayed. This doesn't change how the image is transferred to the
+    /// screen, but the [`viewport`](Self::viewport) and
+    /// [`orthographic`](Self::orthographic) projection of the target follow it,
+    /// so the image is drawn upright. Targets default to
+    /// [`ScreenOrientation::Rotated`].
+    pub fn set_orientation(&mut self, orientation: ScreenOrientation) {
+        self.orientation = orientation;
+    }
+
+    /// The orientation the screen is held in while this target is displayed.
+    /// See [`set_orientation`](Self::set_orientation).
+    pub fn orientation(&self) -> ScreenOrientation {
+        self.orientation
+    }
+
+    /// The whole image displayed on the screen, in pixels as the screen is held
+    /// in the target's [orientation](Self::orientation). This is the size of
+    /// the target after it is downscaled by the transfer flags (e.g. for
+    /// [anti-aliasing](AaMode)), so it is 400x240 for a regular target of the
+    /// top screen held normally, or 240x400 held in portrait.
+    pub fn viewport(&self) -> Viewport {
+        let (width, height) = match self.transfer_flags.scaling_mode() {
+            transfer::Scaling::None => (self.width(), self.height()),
+            transfer::Scaling::X => (self.width() / 2, self.height()),
+            transfer::Scaling::XY => (self.width() / 2, self.height() / 2),
+        };
+        let (width, height) = match self.orientation {
+            ScreenOrientation::Rotated => (height, width),
+            ScreenOrientation::None
+            | ScreenOrientation::Portrait
+            | ScreenOrientation::PortraitFlipped => (width, height),
+        };
+
+        Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: width as f32,
+            height: height as f32,
+            orientation: self.orientation,
+        }
+    }
+
+    /// An orthographic projection which covers the [`viewport`](Self::viewport)
+    /// of the target one unit per pixel, with the origin at the bottom-left
+    /// and rotated to match the target's [orientation](Self::orientation).
+    ///
+    /// For a perspective projection, use the aspect ratio of the viewport and
+    /// the same [`ScreenOrientation`].
+    pub fn orthographic(&self, clip_planes_z: ClipPlanes) -> Projection<Orthographic> {
+        let viewport = self.viewport();
+        Projection::orthographic(0.0..viewport.width, 0.0..viewport.height, clip_planes_z)
+            .screen(self.orientation)
+    }
+
+    #[doc(alias = "C3D_RenderTargetSetOutput")]
+    fn link_output(&self) {
+        let flags = self.link_flags();
+
+        // Unlink the screen the target was previously displayed on, or it
+        // would keep displaying this target after it moves to another screen.
+        self.detach_output();
 
         unsafe {
             citro3d_sys::C3D_RenderTargetSetOutput(
-                raw,
-                screen.as_raw(),
-                screen.side().into(),
+                self.raw,
+                self.screen.as_raw(),
+                self.screen.side().into(),
+                flags.bits(),
+            );
+        }
+    }
+
+    #[doc(alias = "C3D_RenderTargetDetachOutput")]
+    fn detach_output(&self) {
+        // Only detach if the screen is still linked to this target, since
+        // detaching clears whichever target is linked to the screen.
+        unsafe {
+            if (*self.raw).linked {
+                citro3d_sys::C3D_RenderTargetDetachOutput(self.raw);
+            }
+        }
+    }
+
+    fn link_flags(&self) -> transfer::Flags {
+        let out_format: ColorFormat = self.screen.framebuffer_format().into();
+        self.transfer_flags
+            .in_format(self.color_format.into())
+            .out_format(out_format.into())
+    }
+}
+
+impl crate::private::Sealed for Target<'_> {}
+
+impl RenderTarget for Target<'_> {
+    fn as_raw(&self) -> *mut C3D_RenderTarget {
+        self.raw
+    }
+}
+
+/// A type which can be drawn to with
+/// [`Instance::select_render_target`](crate::Instance::select_render_target).
+pub trait RenderTarget: crate::private::Sealed {
+    /// Return the underlying `citro3d` render target.
+    fn as_raw(&self) -> *mut C3D_RenderTarget;
+
+    /// The width of the target's buffers, in pixels. For screen targets this
+    /// is the size the target was created with, in portrait orientation (see
+    /// [`Instance::render_target`](crate::Instance::render_target)), scaled up
+    /// if the target is [anti-aliased](AaMode).
+    fn width(&self) -> usize {
+        usize::from(unsafe { (*self.as_raw()).frameBuf.width })
+    }
+
+    /// The height of the target's buffers, in pixels. See [`width`](Self::width).
+    fn height(&self) -> usize {
+        usize::from(unsafe { (*self.as_raw()).frameBuf.height })
+    }
+
+    /// The format of the target's color buffer. Targets without a color
+    /// buffer (see [`DepthTarget`]) report [`ColorFormat::RGBA8`].
+    fn color_format(&self) -> ColorFormat {
+        let format = unsafe { (*self.as_raw()).frameBuf.colorFmt };
+        ColorFormat::try_from(format as u8).expect("render target has an invalid color format")
+    }
+
+    /// The format of the target's depth buffer, or `None` if it doesn't have one.
+    fn depth_format(&self) -> Option<DepthFormat> {
+        let frame_buf = unsafe { &(*self.as_raw()).frameBuf };
+        if frame_buf.depthBuf.is_null() {
+            return None;
+        }
+        DepthFormat::try_from(frame_buf.depthFmt as u8).ok()
+    }
+
+    /// Clear only the given `rect` of the target to `color` and `depth` (see
+    /// [`Target::clear_with`]), e.g. to redraw only the dirty region of a UI.
+    /// Use `flags` to specify whether color and/or depth should be overwritten.
+    ///
+    /// The rectangle is given in the layout of the target's buffers (like
+    /// [`read_pixels`](Self::read_pixels)), which are made up of 8x8 tiles, so
+    /// it must be aligned to whole tiles. Any drawing submitted before this
+    /// call is finished first, so like other clears it should be done before
+    /// drawing to the target in a frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if `rect` is empty, not aligned to 8
+    /// pixels, or extends outside of the target.
+    #[doc(alias = "C3D_SyncMemoryFill")]
+    fn clear_rect(&self, flags: ClearFlags, rect: Rect, color: Color, depth: f32) -> Result<()> {
+        let (width, height) = (self.width(), self.height());
+        let aligned = [rect.x, rect.y, rect.width, rect.height]
+            .iter()
+            .all(|d| d.is_multiple_of(8));
+        if !aligned
+            || rect.width == 0
+            || rect.height == 0
+            || rect.x + rect.width > width
+            || rect.y + rect.height > height
+        {
+            return Err(Error::InvalidSize);
+        }
+
+        let frame_buf = unsafe { &(*self.as_raw()).frameBuf };
+        let color_fill = (flags.contains(ClearFlags::COLOR) && !frame_buf.colorBuf.is_null())
+            .then(|| (frame_buf.colorBuf, self.color_format().fill_value(color)));
+        let depth_fill = self
+            .depth_format()
+            .filter(|_| flags.contains(ClearFlags::DEPTH))
+            .map(|format| (frame_buf.depthBuf, format.fill_value(depth, 0)));
+
+        // Like `citro3d`, use the first fill unit if only one buffer is cleared.
+        let (first, second) = match (color_fill, depth_fill) {
+            (None, None) => return Ok(()),
+            (None, depth) => (depth, None),
+            fills => fills,
+        };
+
+        for rows in tile_row_ranges(rect, width) {
+            let (start0, value0, end0, control0) = fill_range(first, &rows);
+            let (start1, value1, end1, control1) = fill_range(second, &rows);
+
+            unsafe {
+                citro3d_sys::C3D_SyncMemoryFill(
+                    start0, value0, end0, control0, start1, value1, end1, control1,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read back the color buffer of the target, e.g. to take a screenshot.
+    /// Returns `width * height` pixels as RGBA bytes.
+    ///
+    /// Any drawing submitted before this call is finished first. Pixels are
+    /// returned in the native layout of the target's buffer, so for screen
+    /// targets (which are in portrait orientation, see
+    /// [`Instance::render_target`](crate::Instance::render_target)) each row of
+    /// `width` pixels is a column of the displayed image, starting from the
+    /// bottom left.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidRenderTarget`] if the target has no color buffer (see
+    ///   [`DepthTarget`]) or its color format is unknown.
+    /// * [`Error::Ctru`] if the read back data couldn't be synchronized with
+    ///   the CPU.
+    #[doc(alias = "C3D_SyncDisplayTransfer")]
+    fn read_pixels(&self) -> Result<Vec<u8>> {
+        let frame_buf = unsafe { &(*self.as_raw()).frameBuf };
+        if frame_buf.colorBuf.is_null() {
+            return Err(Error::InvalidRenderTarget);
+        }
+        let (width, height) = (u32::from(frame_buf.width), u32::from(frame_buf.height));
+        let in_format = ColorFormat::try_from(frame_buf.colorFmt as u8)
+            .map_err(|_| Error::InvalidRenderTarget)?;
+
+        let size = (width * height * 4) as usize;
+        let mut pixels = Vec::with_capacity_in(size, LinearAllocator);
+        pixels.resize(size, 0u8);
+
+        let flags = transfer::Flags::default()
+            .in_format(in_format.into())
+            .out_format(transfer::Format::RGBA8);
+
+        unsafe {
+            citro3d_sys::C3D_SyncDisplayTransfer(
+                frame_buf.colorBuf.cast(),
+                citro3d_sys::GX_BUFFER_DIM(width, height),
+                pixels.as_mut_ptr().cast(),
+                citro3d_sys::GX_BUFFER_DIM(width, height),
                 flags.bits(),
             );
         }
 
+        let result =
+            unsafe { ctru_sys::GSPGPU_InvalidateDataCache(pixels.as_ptr().cast(), size as u32) };
+        crate::error::ctru_result(result)?;
+
+        // The GPU stores each pixel as ABGR, like textures.
+        Ok(pixels
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[3], pixel[2], pixel[1], pixel[0]])
+            .collect())
+    }
+}
+
+/// A rectangle of a render target, in pixels. See [`RenderTarget::clear_rect`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    /// The horizontal position of the rectangle.
+    pub x: usize,
+    /// The vertical position of the rectangle.
+    pub y: usize,
+    /// The width of the rectangle.
+    pub width: usize,
+    /// The height of the rectangle.
+    pub height: usize,
+}
+
+impl Rect {
+    /// Create a new rectangle.
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// The ranges of pixels (in buffer order) covered by a tile-aligned `rect` in
+/// a buffer `width` pixels wide, one for each row of tiles. Tiles in a row are
+/// contiguous, and ranges of adjacent rows are merged where possible.
+fn tile_row_ranges(rect: Rect, width: usize) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+
+    for row in rect.y / 8..(rect.y + rect.height) / 8 {
+        let start = (row * width + rect.x) * 8;
+        let end = start + rect.width * 8;
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+
+    ranges
+}
+
+/// The arguments to fill the given range of pixels of a buffer with a value,
+/// or to skip the fill if there is no buffer.
+fn fill_range(
+    buffer: Option<(*mut c_void, transfer::FillValue)>,
+    pixels: &std::ops::Range<usize>,
+) -> (*mut u32, u32, *mut u32, u16) {
+    let Some((buffer, value)) = buffer else {
+        return (std::ptr::null_mut(), 0, std::ptr::null_mut(), 0);
+    };
+
+    let (raw, control) = value.as_raw();
+    let buffer = buffer.cast::<u8>();
+    let size = value.size();
+    unsafe {
+        (
+            buffer.add(pixels.start * size).cast(),
+            raw,
+            buffer.add(pixels.end * size).cast(),
+            control,
+        )
+    }
+}
+
+fn clear_target(
+    target: &(impl RenderTarget + ?Sized),
+    flags: ClearFlags,
+    color: Color,
+    depth: f32,
+    stencil: u8,
+) {
+    let color = target.color_format().clear_value(color);
+    let depth = target
+        .depth_format()
+        .map_or(0, |format| format.clear_value(depth, stencil));
+
+    unsafe {
+        citro3d_sys::C3D_RenderTargetClear(target.as_raw(), flags.bits(), color, depth);
+    }
+}
+
+/// An offscreen render target which draws into a [`Texture`](texture::Texture),
+/// e.g. for mirrors, post-processing or shadow maps. The texture can be sampled
+/// in a later pass with [`TextureTarget::bind`].
+#[doc(alias = "C3D_RenderTargetCreateFromTex")]
+pub struct TextureTarget {
+    raw: *mut C3D_RenderTarget,
+    texture: Rc<texture::Texture>,
+    _queue: Rc<RenderQueue>,
+}
+
+impl TextureTarget {
+    /// Create a new texture in VRAM and a render target which draws into it.
+    pub(crate) fn new(
+        width: usize,
+        height: usize,
+        color_format: ColorFormat,
+        depth_format: Option<DepthFormat>,
+        queue: Rc<RenderQueue>,
+    ) -> Result<Self> {
+        let texture = texture::Texture::with_placement(
+            width,
+            height,
+            color_format.into(),
+            texture::Placement::Vram,
+        )?;
+
+        Self::from_texture(
+            &Rc::new(texture),
+            texture::Face::Bidimensional,
+            depth_format,
+            queue,
+        )
+    }
+
+    /// Create a render target which draws into the given face of an existing
+    /// texture.
+    pub(crate) fn from_texture(
+        texture: &Rc<texture::Texture>,
+        face: texture::Face,
+        depth_format: Option<DepthFormat>,
+        queue: Rc<RenderQueue>,
+    ) -> Result<Self> {
+        if face != texture::Face::Bidimensional && !texture.is_cube() {
+            return Err(Error::InvalidRenderTarget);
+        }
+
+        let raw = unsafe {
+            C3D_RenderTargetCreateFromTex(
+                texture.as_raw(),
+                face as ctru_sys::GPU_TEXFACE,
+                0,
+                depth_format.map_or(C3D_DEPTHTYPE { __i: -1 }, DepthFormat::as_raw),
+            )
+        };
+
+        if raw.is_null() {
+            return Err(Error::OutOfMemory);
+        }
+
         Ok(Self {
             raw,
-            _screen: screen,
+            texture: Rc::clone(texture),
             _queue: queue,
         })
     }
@@ -92,8 +593,140 @@ impl<'screen> Target<'screen> {
         }
     }
 
-    /// Return the underlying `citro3d` render target for this target.
-    pub(crate) fn as_raw(&self) -> *mut C3D_RenderTarget {
+    /// Clear the render target with the given color (with full alpha), `depth`
+    /// in [0, 1] and `stencil` value, which are converted to the formats of
+    /// the target's buffers. Use `flags` to specify whether color and/or depth
+    /// should be overwritten; the stencil value is only written along with the
+    /// depth of a [`Depth24Stencil8`](DepthFormat::Depth24Stencil8) buffer.
+    #[doc(alias = "C3D_RenderTargetClear")]
+    pub fn clear_with(&mut self, flags: ClearFlags, color: Color, depth: f32, stencil: u8) {
+        clear_target(&*self, flags, color, depth, stencil);
+    }
+
+    /// The texture this target draws into, e.g. to bind it with
+    /// [`Instance::bind_texture`](crate::Instance::bind_texture).
+    pub fn texture(&self) -> &Rc<texture::Texture> {
+        &self.texture
+    }
+
+    /// The texture this target draws into, e.g. to change its
+    /// [sampling parameters](texture::Texture::set_filter). Returns `None` if
+    /// the texture is shared, e.g. with other targets drawing to another face
+    /// of a cube map.
+    pub fn texture_mut(&mut self) -> Option<&mut texture::Texture> {
+        Rc::get_mut(&mut self.texture)
+    }
+
+    /// Bind the target's texture to the given texture unit, to sample what
+    /// was rendered to it. Returns the texture previously bound to the unit
+    /// (if any). This is the same as [`Instance::bind_texture`](crate::Instance::bind_texture)
+    /// with the target's [`texture`](Self::texture), which keeps it alive
+    /// while it is bound.
+    ///
+    /// Sampling the texture while drawing to this same target is undefined.
+    /// To sample it later in the same frame, the frame must be
+    /// [split](crate::Instance::split_frame) so the GPU finishes drawing to
+    /// the texture first.
+    #[doc(alias = "C3D_TexBind")]
+    pub fn bind(&self, frame: &mut Frame<'_>, unit: texture::Unit) -> Option<Rc<texture::Texture>> {
+        frame.bind_texture(unit, &self.texture)
+    }
+}
+
+impl Drop for TextureTarget {
+    #[doc(alias = "C3D_RenderTargetDelete")]
+    fn drop(&mut self) {
+        // The texture is deleted afterwards, once no other target uses it.
+        unsafe { C3D_RenderTargetDelete(self.raw) };
+    }
+}
+
+impl crate::private::Sealed for TextureTarget {}
+
+impl RenderTarget for TextureTarget {
+    fn as_raw(&self) -> *mut C3D_RenderTarget {
+        self.raw
+    }
+}
+
+/// An offscreen render target with only a depth buffer, e.g. for a depth
+/// pre-pass which fills a [`DepthBuffer`] shared with a screen target, so that
+/// hidden fragments can be skipped when drawing the color pass.
+///
+/// Color writes are disabled while drawing to the target, so fragment shading
+/// has no effect other than the depth and stencil tests.
+#[doc(alias = "C3D_FrameBufColor")]
+pub struct DepthTarget {
+    raw: *mut C3D_RenderTarget,
+    depth: Rc<DepthBuffer>,
+    _queue: Rc<RenderQueue>,
+}
+
+impl DepthTarget {
+    /// Create a new target which draws into the given shared `depth` buffer.
+    pub(crate) fn new(depth: &Rc<DepthBuffer>, queue: Rc<RenderQueue>) -> Result<Self> {
+        let (width, height) = depth.size();
+
+        // `citro3d` always allocates a color buffer for new targets, so use
+        // the smallest format and free it again right away.
+        let raw = unsafe {
+            C3D_RenderTargetCreate(
+                width.try_into()?,
+                height.try_into()?,
+                ColorFormat::RGBA4 as GPU_COLORBUF,
+                C3D_DEPTHTYPE { __i: -1 },
+            )
+        };
+        if raw.is_null() {
+            return Err(Error::OutOfMemory);
+        }
+
+        unsafe {
+            let frame_buf = &mut (*raw).frameBuf;
+            ctru_sys::vramFree(frame_buf.colorBuf);
+            (*raw).ownsColor = false;
+
+            // A null color buffer disables color writes.
+            citro3d_sys::C3D_FrameBufColor(
+                frame_buf,
+                std::ptr::null_mut(),
+                ColorFormat::RGBA8 as GPU_COLORBUF,
+            );
+            citro3d_sys::C3D_FrameBufDepth(frame_buf, depth.raw, depth.format as GPU_DEPTHBUF);
+        }
+
+        Ok(Self {
+            raw,
+            depth: Rc::clone(depth),
+            _queue: queue,
+        })
+    }
+
+    /// Clear the depth buffer to `depth` in [0, 1] and `stencil` (for a
+    /// [`Depth24Stencil8`](DepthFormat::Depth24Stencil8) buffer).
+    #[doc(alias = "C3D_RenderTargetClear")]
+    pub fn clear(&mut self, depth: f32, stencil: u8) {
+        clear_target(&*self, ClearFlags::DEPTH, Color::default(), depth, stencil);
+    }
+
+    /// The depth buffer this target draws into.
+    pub fn depth_buffer(&self) -> &Rc<DepthBuffer> {
+        &self.depth
+    }
+}
+
+impl Drop for DepthTarget {
+    #[doc(alias = "C3D_RenderTargetDelete")]
+    fn drop(&mut self) {
+        // The depth buffer is freed afterwards, once no other target uses it.
+        unsafe { C3D_RenderTargetDelete(self.raw) };
+    }
+}
+
+impl crate::private::Sealed for DepthTarget {}
+
+impl RenderTarget for DepthTarget {
+    fn as_raw(&self) -> *mut C3D_RenderTarget {
         self.raw
     }
 }
@@ -101,6 +734,7 @@ impl<'screen> Target<'screen> {
 bitflags::bitflags! {
     /// Indicate whether color, depth buffer, or both values should be cleared.
     #[doc(alias = "C3D_ClearBits")]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub struct ClearFlags: u8 {
         /// Clear the color of the render target.
         const COLOR = citro3d_sys::C3D_CLEAR_COLOR;
@@ -111,6 +745,944 @@ bitflags::bitflags! {
     }
 }
 
+/// The target to draw a pass of the frame to, and how it should be cleared
+/// beforehand. Passes are started with
+/// [`Instance::select_render_pass`](crate::Instance::select_render_pass).
+///
+/// By default nothing is cleared. Setting a clear color or depth also enables
+/// clearing the corresponding buffer, unless the flags are overridden with
+/// [`clear_flags`](Self::clear_flags).
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use ctru::services::gfx::Gfx;
+/// # use citro3d::color::Color;
+/// # use citro3d::render::{DepthFormat, RenderPass};
+/// # let gfx = Gfx::new().unwrap();
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// let target = instance
+///     .render_target(
+///         240,
+///         400,
+///         gfx.top_screen.borrow_mut(),
+///         Some(DepthFormat::Depth24),
+///     )
+///     .unwrap();
+///
+/// let pass = RenderPass::new(&target)
+///     .clear_color(Color::new(0.1, 0.1, 0.2))
+///     .clear_depth(0.0);
+///
+/// instance.render_frame_with(|frame| {
+///     frame.select_render_pass(&pass).unwrap();
+///     // Draw calls go here...
+/// });
+/// ```
+#[derive(Clone, Copy)]
+pub struct RenderPass<'target> {
+    target: &'target dyn RenderTarget,
+    flags: ClearFlags,
+    color: Color,
+    depth: f32,
+}
+
+impl<'target> RenderPass<'target> {
+    /// Create a pass drawing to `target`, without clearing it.
+    pub fn new(target: &'target dyn RenderTarget) -> Self {
+        Self {
+            target,
+            flags: ClearFlags::empty(),
+            color: Color::default(),
+            depth: 0.0,
+        }
+    }
+
+    /// Clear the color buffer of the target to `color` (with full alpha).
+    pub fn clear_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self.flags |= ClearFlags::COLOR;
+        self
+    }
+
+    /// Clear the depth buffer of the target to `depth`, in [0, 1]. The value
+    /// is converted to the depth format of the target.
+    pub fn clear_depth(mut self, depth: f32) -> Self {
+        self.depth = depth;
+        self.flags |= ClearFlags::DEPTH;
+        self
+    }
+
+    /// Override which buffers of the target are cleared.
+    pub fn clear_flags(mut self, flags: ClearFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// The target this pass draws to.
+    pub fn target(&self) -> &'target dyn RenderTarget {
+        self.target
+    }
+
+    /// Clear the target as configured, then select it for drawing.
+    pub(crate) fn begin(&self) -> Result<()> {
+        let raw = self.target.as_raw();
+
+        if !self.flags.is_empty() {
+            clear_target(self.target, self.flags, self.color, self.depth, 0);
+        }
+
+        if unsafe { citro3d_sys::C3D_FrameDrawOn(raw) } {
+            Ok(())
+        } else {
+            Err(Error::InvalidRenderTarget)
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Options for beginning a frame with
+    /// [`Instance::begin_frame`](crate::Instance::begin_frame).
+    #[doc(alias = "C3D_FrameBegin")]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct FrameFlags: u8 {
+        /// Wait for the GPU to finish drawing the previous frame before
+        /// beginning the new one.
+        const SYNC_DRAW = citro3d_sys::C3D_FRAME_SYNCDRAW;
+        /// Don't wait if the GPU is still busy with the previous frame, and
+        /// fail with [`Error::WouldBlock`] instead.
+        const NON_BLOCK = citro3d_sys::C3D_FRAME_NONBLOCK;
+    }
+}
+
+/// A frame being rendered, returned by
+/// [`Instance::begin_frame`](crate::Instance::begin_frame) or passed to
+/// [`Instance::render_frame_with`](crate::Instance::render_frame_with).
+///
+/// Draw calls, uniforms and texture combiners are only available through a
+/// frame, so they can't accidentally be used outside of one. The frame also
+/// has the [`Instance`](crate::Instance) methods which can be used while
+/// drawing, e.g. to select render targets or bind shader programs, but not
+/// ones like [`begin_frame`](crate::Instance::begin_frame). The frame is ended
+/// and submitted to the GPU when the guard is dropped.
+#[must_use = "the frame ends as soon as it is dropped"]
+pub struct Frame<'instance> {
+    instance: &'instance mut crate::Instance,
+}
+
+impl<'instance> Frame<'instance> {
+    pub(crate) fn new(instance: &'instance mut crate::Instance) -> Self {
+        // Beginning a frame waits for the GPU to finish the previous one, so
+        // the memory it used can be released now.
+        instance.frame_resources.clear();
+        Self { instance }
+    }
+
+    /// End the frame and submit it to the GPU. This is the same as dropping
+    /// the frame, but makes the end of the frame explicit.
+    #[doc(alias = "C3D_FrameEnd")]
+    pub fn end(self) {
+        drop(self);
+    }
+
+    /// Render primitives from the current vertex array buffer.
+    ///
+    /// # Panics
+    ///
+    /// With the `validation` feature, if the draw call is invalid, e.g. no
+    /// shader program is bound or the vertex buffers don't match the current
+    /// attribute info.
+    #[doc(alias = "C3D_DrawArrays")]
+    #[cfg_attr(feature = "validation", track_caller)]
+    pub fn draw_arrays(&mut self, primitive: buffer::Primitive, vbo_data: buffer::Slice) {
+        #[cfg(feature = "validation")]
+        validation::check_draw(self.instance, primitive, &vbo_data);
+
+        self.set_buffer_info(vbo_data.info());
+
+        // TODO: should we also require the attrib info directly here?
+        unsafe {
+            citro3d_sys::C3D_DrawArrays(
+                primitive as ctru_sys::GPU_Primitive_t,
+                vbo_data.index(),
+                vbo_data.len(),
+            );
+        }
+    }
+
+    /// Render `count` vertices of `vbo_data`, starting from its `first`
+    /// vertex. This is the same as drawing a
+    /// [`subslice`](buffer::Slice::subslice), e.g. to draw each mesh in a large
+    /// shared vertex buffer without registering it again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if the range is not within `vbo_data`,
+    /// including if `first + count` overflows.
+    #[doc(alias = "C3D_DrawArrays")]
+    pub fn draw_arrays_range(
+        &mut self,
+        primitive: buffer::Primitive,
+        vbo_data: buffer::Slice,
+        first: libc::c_int,
+        count: libc::c_int,
+    ) -> Result<()> {
+        let end = first.checked_add(count).ok_or(Error::IndexOutOfBounds {
+            idx: first.saturating_add(count),
+            len: vbo_data.len(),
+        })?;
+        let range = vbo_data.subslice(first..end)?;
+        self.draw_arrays(primitive, range);
+        Ok(())
+    }
+
+    /// Indexed drawing
+    ///
+    /// Draws the vertices in `buf` indexed by `indices`. The frame keeps a
+    /// reference to the index buffer until the GPU is done with it, so
+    /// `indices` may be dropped before the frame ends.
+    ///
+    /// # Panics
+    ///
+    /// If the given index buffer is too long to have its length converted to
+    /// `i32`. With the `validation` feature, also if the draw call is invalid,
+    /// as for [`draw_arrays`](Self::draw_arrays).
+    #[doc(alias = "C3D_DrawElements")]
+    #[cfg_attr(feature = "validation", track_caller)]
+    pub fn draw_elements<I: Index>(
+        &mut self,
+        primitive: buffer::Primitive,
+        vbo_data: buffer::Slice,
+        indices: &Indices<'_, I>,
+    ) {
+        #[cfg(feature = "validation")]
+        validation::check_draw(self.instance, primitive, &vbo_data);
+
+        self.set_buffer_info(vbo_data.info());
+
+        self.keep_alive(Rc::clone(&indices.owner));
+
+        unsafe {
+            citro3d_sys::C3D_DrawElements(
+                primitive as ctru_sys::GPU_Primitive_t,
+                indices.len.try_into().unwrap(),
+                // flag bit for short or byte
+                I::TYPE,
+                indices.ptr.cast(),
+            );
+        }
+    }
+
+    /// Draw `vbo_data` once per transform, binding each transform to the
+    /// vertex shader uniform at `index` first. The PICA200 has no hardware
+    /// instancing, so this issues one draw call per instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{buffer, uniform};
+    /// # use citro3d::math::Matrix4;
+    /// # fn draw(frame: &mut citro3d::render::Frame, slice: buffer::Slice) {
+    /// let model_view = uniform::Index::from(4);
+    /// let transforms: Vec<Matrix4> = (0..10)
+    ///     .map(|i| {
+    ///         let mut transform = Matrix4::identity();
+    ///         transform.translate(i as f32, 0.0, -5.0);
+    ///         transform
+    ///     })
+    ///     .collect();
+    ///
+    /// frame.draw_instances(buffer::Primitive::Triangles, slice, model_view, &transforms);
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// As for [`draw_arrays`](Self::draw_arrays).
+    #[cfg_attr(feature = "validation", track_caller)]
+    pub fn draw_instances(
+        &mut self,
+        primitive: buffer::Primitive,
+        vbo_data: buffer::Slice,
+        index: uniform::Index,
+        transforms: &[crate::math::Matrix4],
+    ) {
+        self.draw_instances_with(primitive, vbo_data, transforms.len(), |frame, i| {
+            frame.bind_vertex_uniform(index, transforms[i]);
+        });
+    }
+
+    /// Draw `vbo_data` `count` times, calling `f` with the index of each
+    /// instance before it is drawn. This allows binding any per-instance data,
+    /// e.g. a transform and a color, or several uniforms starting from some
+    /// register.
+    ///
+    /// # Panics
+    ///
+    /// As for [`draw_arrays`](Self::draw_arrays).
+    #[cfg_attr(feature = "validation", track_caller)]
+    pub fn draw_instances_with(
+        &mut self,
+        primitive: buffer::Primitive,
+        vbo_data: buffer::Slice,
+        count: usize,
+        mut f: impl FnMut(&mut Self, usize),
+    ) {
+        for instance in 0..count {
+            f(self, instance);
+            self.draw_arrays(primitive, vbo_data);
+        }
+    }
+
+    /// Begin drawing `primitive` in immediate mode, sending vertices directly
+    /// to the GPU using the current attribute info. See [`ImmediateDraw`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if the current attribute info has no
+    /// attributes.
+    #[doc(alias = "C3D_ImmDrawBegin")]
+    pub fn draw_immediate(&mut self, primitive: buffer::Primitive) -> Result<ImmediateDraw<'_>> {
+        ImmediateDraw::begin(self.instance, primitive)
+    }
+
+    /// Use `buffer_info` while running `f`, then restore the buffer info which
+    /// was in use before. This keeps independent renderers (e.g. a 2D UI
+    /// layer and a 3D scene) from leaking vertex buffer state into each other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{buffer, render};
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let ui_info = buffer::Info::new();
+    ///
+    /// let mut frame = instance.begin_frame(render::FrameFlags::SYNC_DRAW).unwrap();
+    /// let before = frame.buffer_info().unwrap().buffer_count();
+    /// frame.with_buffer_info(&ui_info, |frame| {
+    ///     // Draw the UI layer...
+    /// });
+    /// assert_eq!(frame.buffer_info().unwrap().buffer_count(), before);
+    /// ```
+    #[doc(alias = "C3D_GetBufInfo")]
+    #[doc(alias = "C3D_SetBufInfo")]
+    pub fn with_buffer_info<R>(
+        &mut self,
+        buffer_info: &buffer::Info,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let previous = self.buffer_info();
+        self.set_buffer_info(buffer_info);
+
+        let result = f(self);
+
+        if let Some(previous) = previous {
+            self.set_buffer_info(&previous);
+        }
+        result
+    }
+
+    /// Keep `resource` alive until the GPU has finished this frame.
+    pub(crate) fn keep_alive(&mut self, resource: Rc<dyn Any>) {
+        self.instance.frame_resources.push(resource);
+    }
+
+    /// Bind a uniform to the given `index` in the vertex shader for the next draw call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform;
+    /// # use citro3d::math::Matrix4;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let idx = uniform::Index::from(0);
+    /// let mtx = Matrix4::identity();
+    /// instance.render_frame_with(|frame| {
+    ///     frame.bind_vertex_uniform(idx, &mtx);
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the uniform can't be bound at `index`; see [`try_bind_uniform`](Self::try_bind_uniform).
+    pub fn bind_vertex_uniform(&mut self, index: uniform::Index, uniform: impl Into<Uniform>) {
+        self.try_bind_uniform(shader::Type::Vertex, index, uniform)
+            .unwrap_or_else(|err| panic!("failed to bind vertex uniform: {err}"));
+    }
+
+    /// Bind a uniform to the given `index` in the geometry shader for the next draw call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform;
+    /// # use citro3d::math::Matrix4;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let idx = uniform::Index::from(0);
+    /// let mtx = Matrix4::identity();
+    /// instance.render_frame_with(|frame| {
+    ///     frame.bind_geometry_uniform(idx, &mtx);
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the uniform can't be bound at `index`; see [`try_bind_uniform`](Self::try_bind_uniform).
+    pub fn bind_geometry_uniform(&mut self, index: uniform::Index, uniform: impl Into<Uniform>) {
+        self.try_bind_uniform(shader::Type::Geometry, index, uniform)
+            .unwrap_or_else(|err| panic!("failed to bind geometry uniform: {err}"));
+    }
+
+    /// Bind a matrix or vector uniform to the given `index` in the vertex shader
+    /// for the next draw call, directly from a reference. Unlike
+    /// [`bind_vertex_uniform`](Self::bind_vertex_uniform), this doesn't copy the
+    /// value into a [`Uniform`] first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform;
+    /// # use citro3d::math::Matrix4;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let idx = uniform::Index::from(0);
+    /// let mtx = Matrix4::identity();
+    /// instance.render_frame_with(|frame| {
+    ///     frame.bind_vertex_uniform_ref(idx, &mtx);
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the uniform doesn't fit in the float uniform registers after `index`.
+    pub fn bind_vertex_uniform_ref<T: uniform::ArrayElement>(
+        &mut self,
+        index: uniform::Index,
+        uniform: &T,
+    ) {
+        self.bind_vertex_uniforms(index, std::slice::from_ref(uniform));
+    }
+
+    /// Bind a matrix or vector uniform to the given `index` in the geometry
+    /// shader for the next draw call, directly from a reference. See
+    /// [`bind_vertex_uniform_ref`](Self::bind_vertex_uniform_ref).
+    ///
+    /// # Panics
+    ///
+    /// If the uniform doesn't fit in the float uniform registers after `index`,
+    /// or overlaps the registers reserved by the geometry shader.
+    pub fn bind_geometry_uniform_ref<T: uniform::ArrayElement>(
+        &mut self,
+        index: uniform::Index,
+        uniform: &T,
+    ) {
+        self.bind_geometry_uniforms(index, std::slice::from_ref(uniform));
+    }
+
+    /// Bind an array of uniforms to consecutive registers in the vertex shader,
+    /// starting at `index`, for the next draw call. Each [`Matrix4`](crate::math::Matrix4)
+    /// takes up 4 registers and each [`FVec4`](crate::math::FVec4) takes up 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform;
+    /// # use citro3d::math::Matrix4;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let bones_index = uniform::Index::from(8);
+    /// let bones = [Matrix4::identity(); 16];
+    /// instance.render_frame_with(|frame| {
+    ///     frame.bind_vertex_uniforms(bones_index, &bones);
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the array doesn't fit in the 96 float uniform registers after `index`.
+    pub fn bind_vertex_uniforms<T: uniform::ArrayElement>(
+        &mut self,
+        index: uniform::Index,
+        uniforms: &[T],
+    ) {
+        self.try_bind_uniforms(shader::Type::Vertex, index, uniforms)
+            .unwrap_or_else(|err| panic!("failed to bind vertex uniforms: {err}"));
+    }
+
+    /// Bind an array of uniforms to consecutive registers in the geometry
+    /// shader, starting at `index`, for the next draw call. See
+    /// [`bind_vertex_uniforms`](Self::bind_vertex_uniforms).
+    ///
+    /// # Panics
+    ///
+    /// If the array doesn't fit in the 96 float uniform registers after `index`,
+    /// or overlaps the registers reserved by the geometry shader; see
+    /// [`try_bind_uniform`](Self::try_bind_uniform).
+    pub fn bind_geometry_uniforms<T: uniform::ArrayElement>(
+        &mut self,
+        index: uniform::Index,
+        uniforms: &[T],
+    ) {
+        self.try_bind_uniforms(shader::Type::Geometry, index, uniforms)
+            .unwrap_or_else(|err| panic!("failed to bind geometry uniforms: {err}"));
+    }
+
+    /// Bind a uniform to the given `index` in the vertex or geometry shader for
+    /// the next draw call, returning an error instead of panicking if it can't
+    /// be bound there.
+    ///
+    /// Vertex and geometry shaders have the same uniform registers, but a
+    /// geometry shader in fixed primitive mode (`.gsh fixed`) receives its
+    /// input vertices in some of its float registers, so uniforms can't be
+    /// bound to those while a program with such a shader is bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::{shader, uniform};
+    /// # use citro3d::math::Matrix4;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// instance.render_frame_with(|frame| {
+    ///     let overflowing = uniform::Index::from(0x5E);
+    ///     assert!(
+    ///         frame
+    ///             .try_bind_uniform(shader::Type::Geometry, overflowing, Matrix4::identity())
+    ///             .is_err()
+    ///     );
+    /// });
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidUniformIndex`](crate::Error::InvalidUniformIndex)
+    /// if the uniform would not fit in the registers for its kind of uniform
+    /// after `index`, or would overlap the registers reserved by the geometry
+    /// shader.
+    pub fn try_bind_uniform(
+        &mut self,
+        ty: shader::Type,
+        index: uniform::Index,
+        uniform: impl Into<Uniform>,
+    ) -> crate::Result<()> {
+        uniform.into().bind(self.instance, ty, index)
+    }
+
+    /// Bind an array of uniforms to consecutive registers in the vertex or
+    /// geometry shader, starting at `index`, for the next draw call. See
+    /// [`bind_vertex_uniforms`](Self::bind_vertex_uniforms).
+    ///
+    /// # Errors
+    ///
+    /// As for [`try_bind_uniform`](Self::try_bind_uniform).
+    pub fn try_bind_uniforms<T: uniform::ArrayElement>(
+        &mut self,
+        ty: shader::Type,
+        index: uniform::Index,
+        uniforms: &[T],
+    ) -> crate::Result<()> {
+        uniform::bind_array(self.instance, ty, index, uniforms)
+    }
+
+    /// Retrieve the [`TexEnv`] for the given stage, initializing it first if necessary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use citro3d::texenv;
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// let stage0 = texenv::Stage::new(0).unwrap();
+    /// instance.render_frame_with(|frame| {
+    ///     let texenv0 = frame.texenv(stage0);
+    /// });
+    /// ```
+    #[doc(alias = "C3D_GetTexEnv")]
+    #[doc(alias = "C3D_TexEnvInit")]
+    pub fn texenv(&mut self, stage: texenv::Stage) -> &mut texenv::TexEnv {
+        let texenv = &mut self.instance.texenvs[stage.0];
+        texenv.get_or_init(|| texenv::TexEnv::new(stage));
+        // We have to do this weird unwrap to get a mutable reference,
+        // since there is no `get_mut_or_init` or equivalent
+        texenv.get_mut().unwrap()
+    }
+}
+
+// Instance methods which can be used during a frame. Only these are forwarded,
+// since others (e.g. beginning another frame) must not be called while the
+// frame is in progress.
+impl Frame<'_> {
+    /// Select the given render target for drawing the frame.
+    /// See [`Instance::select_render_target`](crate::Instance::select_render_target).
+    #[doc(alias = "C3D_FrameDrawOn")]
+    pub fn select_render_target(&mut self, target: &(impl RenderTarget + ?Sized)) -> Result<()> {
+        self.instance.select_render_target(target)
+    }
+
+    /// Clear the target of the given render `pass` and select it for drawing.
+    /// See [`Instance::select_render_pass`](crate::Instance::select_render_pass).
+    pub fn select_render_pass(&mut self, pass: &RenderPass<'_>) -> Result<()> {
+        self.instance.select_render_pass(pass)
+    }
+
+    /// Submit all drawing done so far to the GPU, so later draws see the
+    /// completed render target.
+    /// See [`Instance::split_frame`](crate::Instance::split_frame).
+    #[doc(alias = "C3D_FrameSplit")]
+    pub fn split_frame(&mut self) {
+        self.instance.split_frame();
+    }
+
+    /// Set how fragments are written to the render target.
+    /// See [`Instance::set_fragment_operation_mode`](crate::Instance::set_fragment_operation_mode).
+    #[doc(alias = "C3D_FragOpMode")]
+    pub fn set_fragment_operation_mode(&mut self, mode: effect::FragmentOperationMode) {
+        self.instance.set_fragment_operation_mode(mode);
+    }
+
+    /// Set the scale and bias applied to penumbra values in shadow mode.
+    /// See [`Instance::set_shadow_penumbra`](crate::Instance::set_shadow_penumbra).
+    #[doc(alias = "C3D_FragOpShadow")]
+    pub fn set_shadow_penumbra(&mut self, scale: f32, bias: f32) {
+        self.instance.set_shadow_penumbra(scale, bias);
+    }
+
+    /// Set how bound shadow maps are sampled.
+    /// See [`Instance::set_shadow_sampling`](crate::Instance::set_shadow_sampling).
+    #[doc(alias = "C3D_TexShadowParams")]
+    pub fn set_shadow_sampling(&mut self, perspective: bool, bias: f32) {
+        self.instance.set_shadow_sampling(perspective, bias);
+    }
+
+    /// Set how clip space depths are mapped to values in the depth buffer.
+    /// See [`Instance::set_depth_map`](crate::Instance::set_depth_map).
+    #[doc(alias = "C3D_DepthMap")]
+    pub fn set_depth_map(&mut self, z_buffer: bool, scale: f32, offset: f32) {
+        self.instance.set_depth_map(z_buffer, scale, offset);
+    }
+
+    /// Configure the depth test.
+    /// See [`Instance::set_depth_test`](crate::Instance::set_depth_test).
+    #[doc(alias = "C3D_DepthTest")]
+    pub fn set_depth_test(
+        &mut self,
+        enabled: bool,
+        function: effect::TestFunction,
+        write_mask: effect::WriteMask,
+    ) {
+        self.instance.set_depth_test(enabled, function, write_mask);
+    }
+
+    /// Configure alpha blending.
+    /// See [`Instance::set_blend`](crate::Instance::set_blend).
+    #[doc(alias = "C3D_AlphaBlend")]
+    pub fn set_blend(
+        &mut self,
+        color_equation: effect::BlendEquation,
+        alpha_equation: effect::BlendEquation,
+        src_color: effect::BlendFactor,
+        dst_color: effect::BlendFactor,
+        src_alpha: effect::BlendFactor,
+        dst_alpha: effect::BlendFactor,
+    ) {
+        self.instance.set_blend(
+            color_equation,
+            alpha_equation,
+            src_color,
+            dst_color,
+            src_alpha,
+            dst_alpha,
+        );
+    }
+
+    /// Combine fragment colors with the render target using a logic operation.
+    /// See [`Instance::set_color_logic_op`](crate::Instance::set_color_logic_op).
+    #[doc(alias = "C3D_ColorLogicOp")]
+    pub fn set_color_logic_op(&mut self, operation: effect::LogicOperation) {
+        self.instance.set_color_logic_op(operation);
+    }
+
+    /// Set the constant color used by the `Constant*` blend factors.
+    /// See [`Instance::set_blend_color`](crate::Instance::set_blend_color).
+    #[doc(alias = "C3D_BlendingColor")]
+    pub fn set_blend_color(&mut self, rgba_color: u32) {
+        self.instance.set_blend_color(rgba_color);
+    }
+
+    /// Configure the stencil test.
+    /// See [`Instance::set_stencil_test`](crate::Instance::set_stencil_test).
+    #[doc(alias = "C3D_StencilTest")]
+    pub fn set_stencil_test(
+        &mut self,
+        enabled: bool,
+        function: effect::TestFunction,
+        reference: u8,
+        input_mask: u8,
+        write_mask: u8,
+    ) {
+        self.instance
+            .set_stencil_test(enabled, function, reference, input_mask, write_mask);
+    }
+
+    /// Set how the stencil buffer is updated.
+    /// See [`Instance::set_stencil_op`](crate::Instance::set_stencil_op).
+    #[doc(alias = "C3D_StencilOp")]
+    pub fn set_stencil_op(
+        &mut self,
+        stencil_fail: effect::StencilOperation,
+        depth_fail: effect::StencilOperation,
+        pass: effect::StencilOperation,
+    ) {
+        self.instance.set_stencil_op(stencil_fail, depth_fail, pass);
+    }
+
+    /// Configure the alpha test.
+    /// See [`Instance::set_alpha_test`](crate::Instance::set_alpha_test).
+    #[doc(alias = "C3D_AlphaTest")]
+    pub fn set_alpha_test(&mut self, enabled: bool, function: effect::TestFunction, reference: u8) {
+        self.instance.set_alpha_test(enabled, function, reference);
+    }
+
+    /// Configure the early depth test.
+    /// See [`Instance::set_early_depth_test`](crate::Instance::set_early_depth_test).
+    #[doc(alias = "C3D_EarlyDepthTest")]
+    pub fn set_early_depth_test(
+        &mut self,
+        enabled: bool,
+        function: effect::EarlyDepthFunction,
+        reference: f32,
+    ) {
+        self.instance
+            .set_early_depth_test(enabled, function, reference);
+    }
+
+    /// Get the buffer info being used, if it exists.
+    /// See [`Instance::buffer_info`](crate::Instance::buffer_info).
+    #[doc(alias = "C3D_GetBufInfo")]
+    pub fn buffer_info(&self) -> Option<buffer::Info> {
+        self.instance.buffer_info()
+    }
+
+    /// Set the buffer info to use for any following draw calls.
+    #[doc(alias = "C3D_SetBufInfo")]
+    pub fn set_buffer_info(&mut self, buffer_info: &buffer::Info) {
+        self.instance.set_buffer_info(buffer_info);
+    }
+
+    /// Get the attribute info being used, if it exists.
+    /// See [`Instance::attr_info`](crate::Instance::attr_info).
+    #[doc(alias = "C3D_GetAttrInfo")]
+    pub fn attr_info(&self) -> Option<attrib::Info> {
+        self.instance.attr_info()
+    }
+
+    /// Set the attribute info to use for any following draw calls.
+    #[doc(alias = "C3D_SetAttrInfo")]
+    pub fn set_attr_info(&mut self, attr_info: &attrib::Info) {
+        self.instance.set_attr_info(attr_info);
+    }
+
+    /// Use the given [`shader::Program`] for subsequent draw calls.
+    pub fn bind_program(&mut self, program: &Rc<shader::Program>) {
+        self.instance.bind_program(program);
+    }
+
+    /// Get the currently bound [`shader::Program`], if any.
+    pub fn program(&self) -> Option<&Rc<shader::Program>> {
+        self.instance.program()
+    }
+
+    /// Take a snapshot of the state used for drawing.
+    /// See [`Instance::save_state`](crate::Instance::save_state).
+    pub fn save_state(&self) -> State {
+        self.instance.save_state()
+    }
+
+    /// Restore a snapshot of the state taken with [`save_state`](Self::save_state).
+    /// See [`Instance::restore_state`](crate::Instance::restore_state).
+    #[doc(alias = "C3D_SetTexEnv")]
+    pub fn restore_state(&mut self, state: &State) {
+        self.instance.restore_state(state);
+    }
+
+    /// Send the effect settings made through the instance to `citro3d` again.
+    /// See [`Instance::reapply_effects`](crate::Instance::reapply_effects).
+    pub fn reapply_effects(&self) {
+        self.instance.reapply_effects();
+    }
+
+    /// Bind a texture to the given texture unit, to be sampled by subsequent
+    /// draw calls. See [`Instance::bind_texture`](crate::Instance::bind_texture).
+    #[doc(alias = "C3D_TexBind")]
+    pub fn bind_texture(
+        &mut self,
+        unit: texture::Unit,
+        texture: &Rc<texture::Texture>,
+    ) -> Option<Rc<texture::Texture>> {
+        self.instance.bind_texture(unit, texture)
+    }
+
+    /// Unbind the texture from the given texture unit, returning it (if any).
+    /// See [`Instance::unbind_texture`](crate::Instance::unbind_texture).
+    #[doc(alias = "C3D_TexBind")]
+    pub fn unbind_texture(&mut self, unit: texture::Unit) -> Option<Rc<texture::Texture>> {
+        self.instance.unbind_texture(unit)
+    }
+
+    /// The texture bound to the given texture unit, if any.
+    pub fn bound_texture(&self, unit: texture::Unit) -> Option<&Rc<texture::Texture>> {
+        self.instance.bound_texture(unit)
+    }
+
+    /// Bind a new [`LightEnv`](crate::light::LightEnv), returning the previous
+    /// one (if present).
+    pub fn bind_light_env(
+        &mut self,
+        new_env: Option<Pin<Box<crate::light::LightEnv>>>,
+    ) -> Option<Pin<Box<crate::light::LightEnv>>> {
+        self.instance.bind_light_env(new_env)
+    }
+
+    /// The bound [`LightEnv`](crate::light::LightEnv), if any.
+    pub fn light_env(&self) -> Option<Pin<&crate::light::LightEnv>> {
+        self.instance.light_env()
+    }
+
+    /// The bound [`LightEnv`](crate::light::LightEnv), if any, e.g. to move
+    /// lights between draw calls.
+    pub fn light_env_mut(&mut self) -> Option<Pin<&mut crate::light::LightEnv>> {
+        self.instance.light_env_mut()
+    }
+
+    /// Write raw GPU register commands into the current command buffer.
+    ///
+    /// # Safety
+    ///
+    /// See [`Instance::raw_commands`](crate::Instance::raw_commands).
+    #[doc(alias = "GPUCMD_Add")]
+    pub unsafe fn raw_commands(
+        &mut self,
+        invalidate: crate::command::Invalidate,
+        f: impl FnOnce(&mut crate::command::RawCommands),
+    ) {
+        unsafe { self.instance.raw_commands(invalidate, f) };
+    }
+}
+
+impl Drop for Frame<'_> {
+    #[doc(alias = "C3D_FrameEnd")]
+    fn drop(&mut self) {
+        unsafe {
+            citro3d_sys::C3D_FrameEnd(0);
+        }
+    }
+}
+
+/// Supersampling anti-aliasing for screen render targets, see
+/// [`Instance::render_target_antialiased`](crate::Instance::render_target_antialiased).
+///
+/// The target is rendered at a multiple of the screen's resolution, and
+/// downscaled by averaging neighbouring pixels when it is transferred to the
+/// screen. This costs more VRAM and fill rate, e.g. a 4x target for the top
+/// screen uses four times the memory of a regular one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AaMode {
+    /// No anti-aliasing.
+    #[default]
+    None,
+    /// Render at twice the resolution along the target's width, which is the
+    /// vertical axis of the screen (see
+    /// [`Instance::render_target`](crate::Instance::render_target)).
+    X2,
+    /// Render at twice the resolution along both axes.
+    X4,
+}
+
+impl AaMode {
+    /// The size of the buffer rendered to, for an output of the given size.
+    pub fn scaled_size(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Self::None => (width, height),
+            Self::X2 => (width * 2, height),
+            Self::X4 => (width * 2, height * 2),
+        }
+    }
+
+    /// The scaling used to downscale the rendered image to its output size.
+    pub fn scaling(self) -> transfer::Scaling {
+        match self {
+            Self::None => transfer::Scaling::None,
+            Self::X2 => transfer::Scaling::X,
+            Self::X4 => transfer::Scaling::XY,
+        }
+    }
+}
+
+/// Timing and resource usage of the last rendered frame, returned by
+/// [`Instance::frame_stats`](crate::Instance::frame_stats), e.g. for a
+/// performance overlay.
+///
+/// If the drawing time is close to the length of a frame (about 16.7ms at
+/// 60 FPS) the game is limited by the GPU, while a high processing time means
+/// it's limited by the CPU.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStats {
+    /// The time the GPU spent drawing the frame.
+    #[doc(alias = "C3D_GetDrawingTime")]
+    pub drawing_time: Duration,
+    /// The time the CPU spent building the frame, between the start of the
+    /// frame and submitting it to the GPU.
+    #[doc(alias = "C3D_GetProcessingTime")]
+    pub processing_time: Duration,
+    /// The fraction of the command buffer used by the frame, from `0.0` to
+    /// `1.0`. If this gets close to `1.0`, the instance should be created with
+    /// a larger buffer with [`Instance::with_cmdbuf_size`](crate::Instance::with_cmdbuf_size).
+    #[doc(alias = "C3D_GetCmdBufUsage")]
+    pub cmd_buf_usage: f32,
+}
+
+/// A snapshot of the state used for drawing, taken with
+/// [`Instance::save_state`](crate::Instance::save_state) and restored with
+/// [`Instance::restore_state`](crate::Instance::restore_state).
+pub struct State {
+    pub(crate) attr_info: Option<crate::attrib::Info>,
+    pub(crate) buffer_info: Option<crate::buffer::Info>,
+    pub(crate) program: Option<Rc<shader::Program>>,
+    pub(crate) texenvs: [citro3d_sys::C3D_TexEnv; crate::texenv::TEXENV_COUNT],
+    pub(crate) effect: effect::State,
+}
+
+impl std::fmt::Debug for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("State")
+            .field("attr_info", &self.attr_info)
+            .field("buffer_info", &self.buffer_info)
+            .field("effect", &self.effect)
+            .finish_non_exhaustive()
+    }
+}
+
+/// One of the two physical screens of the 3DS.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[doc(alias = "gfxScreen_t")]
+pub enum ScreenKind {
+    /// The top screen.
+    Top = ctru_sys::GFX_TOP,
+    /// The bottom (touch) screen.
+    Bottom = ctru_sys::GFX_BOTTOM,
+}
+
 /// The color format to use when rendering on the GPU.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug)]
@@ -128,6 +1700,41 @@ pub enum ColorFormat {
     RGBA4 = ctru_sys::GPU_RB_RGBA4,
 }
 
+impl ColorFormat {
+    /// Pack `color` (with full alpha) into the raw value used to clear a color
+    /// buffer of this format.
+    pub(crate) fn clear_value(self, color: Color) -> u32 {
+        let [r, g, b] = color.to_parts_rgb().map(|c| c.clamp(0.0, 1.0));
+        let scale = |c: f32, bits: u32| (c * ((1 << bits) - 1) as f32).round() as u32;
+        match self {
+            Self::RGBA8 => color.to_rgba8(),
+            Self::RGB8 => color.to_rgba8() >> 8,
+            Self::RGBA5551 => (scale(r, 5) << 11) | (scale(g, 5) << 6) | (scale(b, 5) << 1) | 1,
+            Self::RGB565 => (scale(r, 5) << 11) | (scale(g, 6) << 5) | scale(b, 5),
+            Self::RGBA4 => (scale(r, 4) << 12) | (scale(g, 4) << 8) | (scale(b, 4) << 4) | 0xF,
+        }
+    }
+
+    fn fill_value(self, color: Color) -> transfer::FillValue {
+        let bytes = transfer::Format::from(self).bytes_per_pixel();
+        transfer::FillValue::with_size(bytes, self.clear_value(color))
+    }
+}
+
+impl TryFrom<u8> for ColorFormat {
+    type Error = String;
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ctru_sys::GPU_RB_RGBA8 => Ok(Self::RGBA8),
+            ctru_sys::GPU_RB_RGB8 => Ok(Self::RGB8),
+            ctru_sys::GPU_RB_RGBA5551 => Ok(Self::RGBA5551),
+            ctru_sys::GPU_RB_RGB565 => Ok(Self::RGB565),
+            ctru_sys::GPU_RB_RGBA4 => Ok(Self::RGBA4),
+            _ => Err("invalid value for ColorFormat".to_string()),
+        }
+    }
+}
+
 impl From<FramebufferFormat> for ColorFormat {
     fn from(format: FramebufferFormat) -> Self {
         match format {
@@ -141,6 +1748,18 @@ impl From<FramebufferFormat> for ColorFormat {
     }
 }
 
+impl From<ColorFormat> for texture::ColorFormat {
+    fn from(format: ColorFormat) -> Self {
+        match format {
+            ColorFormat::RGBA8 => Self::Rgba8,
+            ColorFormat::RGB8 => Self::Rgb8,
+            ColorFormat::RGBA5551 => Self::Rgba5551,
+            ColorFormat::RGB565 => Self::Rgb565,
+            ColorFormat::RGBA4 => Self::Rgba4,
+        }
+    }
+}
+
 /// The depth buffer format to use when rendering.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug)]
@@ -161,4 +1780,142 @@ impl DepthFormat {
             __e: self as GPU_DEPTHBUF,
         }
     }
+
+    /// Pack a `depth` value in [0, 1] and a `stencil` value into the raw value
+    /// used to clear a depth buffer of this format.
+    pub(crate) fn clear_value(self, depth: f32, stencil: u8) -> u32 {
+        let depth = depth.clamp(0.0, 1.0);
+        match self {
+            Self::Depth16 => (depth * 0xFFFF as f32) as u32,
+            Self::Depth24 => (depth * 0xFF_FFFF as f32) as u32,
+            Self::Depth24Stencil8 => (u32::from(stencil) << 24) | (depth * 0xFF_FFFF as f32) as u32,
+        }
+    }
+}
+
+impl TryFrom<u8> for DepthFormat {
+    type Error = String;
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ctru_sys::GPU_RB_DEPTH16 => Ok(Self::Depth16),
+            ctru_sys::GPU_RB_DEPTH24 => Ok(Self::Depth24),
+            ctru_sys::GPU_RB_DEPTH24_STENCIL8 => Ok(Self::Depth24Stencil8),
+            _ => Err("invalid value for DepthFormat".to_string()),
+        }
+    }
+}
+
+/// A depth buffer which can be shared between several render targets of the
+/// same size, e.g. the left and right eye of the top screen, to save VRAM.
+///
+/// Targets using a shared depth buffer are created with
+/// [`Instance::render_target_with_depth`](crate::Instance::render_target_with_depth).
+/// The buffer is freed once it is dropped and no target uses it anymore.
+#[derive(Debug)]
+pub struct DepthBuffer {
+    raw: *mut c_void,
+    width: usize,
+    height: usize,
+    format: DepthFormat,
+}
+
+impl DepthBuffer {
+    /// Allocate a new depth buffer in VRAM.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if `width` or `height` is zero or larger than 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the buffer.
+    #[doc(alias = "C3D_CalcDepthBufSize")]
+    #[doc(alias = "vramAllocAt")]
+    pub fn new(width: usize, height: usize, format: DepthFormat) -> Result<Rc<Self>> {
+        if !(1..=1024).contains(&width) || !(1..=1024).contains(&height) {
+            return Err(Error::InvalidSize);
+        }
+
+        let raw = unsafe {
+            let size = citro3d_sys::C3D_CalcDepthBufSize(
+                width.try_into()?,
+                height.try_into()?,
+                format as GPU_DEPTHBUF,
+            );
+            // Like `citro3d`, keep depth buffers in the second VRAM bank so
+            // they can be accessed in parallel with color buffers.
+            ctru_sys::vramAllocAt(size.try_into()?, ctru_sys::VRAM_ALLOC_B)
+        };
+
+        if raw.is_null() {
+            return Err(Error::OutOfMemory);
+        }
+
+        Ok(Rc::new(Self {
+            raw,
+            width,
+            height,
+            format,
+        }))
+    }
+
+    /// The width and height of the buffer.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The format of the buffer.
+    pub fn format(&self) -> DepthFormat {
+        self.format
+    }
+}
+
+impl Drop for DepthBuffer {
+    #[doc(alias = "vramFree")]
+    fn drop(&mut self) {
+        unsafe { ctru_sys::vramFree(self.raw) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_clear_value() {
+        assert_eq!(DepthFormat::Depth16.clear_value(1.0, 0xAB), 0xFFFF);
+        assert_eq!(DepthFormat::Depth24.clear_value(1.0, 0xAB), 0xFF_FFFF);
+        assert_eq!(DepthFormat::Depth24.clear_value(0.0, 0xAB), 0);
+        assert_eq!(
+            DepthFormat::Depth24Stencil8.clear_value(1.0, 0xAB),
+            0xABFF_FFFF
+        );
+        // Out of range depths are clamped.
+        assert_eq!(DepthFormat::Depth16.clear_value(2.0, 0), 0xFFFF);
+        assert_eq!(DepthFormat::Depth16.clear_value(-1.0, 0), 0);
+    }
+
+    #[test]
+    fn color_clear_value() {
+        let color = Color::new(1.0, 0.0, 1.0);
+        assert_eq!(ColorFormat::RGBA8.clear_value(color), 0xFF00_FFFF);
+        assert_eq!(ColorFormat::RGB8.clear_value(color), 0xFF_00FF);
+        assert_eq!(ColorFormat::RGB565.clear_value(color), 0xF81F);
+        assert_eq!(ColorFormat::RGBA5551.clear_value(color), 0xF83F);
+        assert_eq!(ColorFormat::RGBA4.clear_value(color), 0xF0FF);
+    }
+
+    #[test]
+    fn tile_rows() {
+        // A rectangle in the middle of a buffer covers part of each row of tiles.
+        let ranges = tile_row_ranges(Rect::new(8, 8, 16, 16), 32);
+        assert_eq!(ranges, [320..448, 576..704]);
+
+        // Full-width rows are contiguous.
+        let ranges = tile_row_ranges(Rect::new(0, 8, 32, 16), 32);
+        assert_eq!(ranges, [256..768]);
+
+        // A rectangle touching the right and bottom edges ends with the buffer.
+        let (width, height) = (32, 16);
+        let ranges = tile_row_ranges(Rect::new(16, 0, 16, 16), width);
+        assert_eq!(ranges, [128..256, 384..512]);
+        assert!(ranges.iter().all(|range| range.end <= width * height));
+    }
 }
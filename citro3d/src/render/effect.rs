@@ -1,5 +1,142 @@
 //! Render effects and behaviour used by the GPU.
 
+/// The effect settings made through the [`Instance`](crate::Instance). `citro3d`
+/// has no way to read them back, so they're tracked here to be able to restore
+/// them, see [`Instance::save_state`](crate::Instance::save_state).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct State {
+    pub(crate) fragment_operation_mode: FragmentOperationMode,
+    pub(crate) shadow_penumbra: (f32, f32),
+    pub(crate) shadow_sampling: (bool, f32),
+    pub(crate) depth_map: (bool, f32, f32),
+    pub(crate) depth_test: (bool, TestFunction, WriteMask),
+    pub(crate) color_operation: ColorOperation,
+    pub(crate) blend_color: u32,
+    pub(crate) stencil_test: (bool, TestFunction, u8, u8, u8),
+    pub(crate) stencil_op: [StencilOperation; 3],
+    pub(crate) alpha_test: (bool, TestFunction, u8),
+    pub(crate) early_depth_test: (bool, EarlyDepthFunction, u32),
+}
+
+/// How fragment colors are combined with the colors in the render target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColorOperation {
+    Blend {
+        color_equation: BlendEquation,
+        alpha_equation: BlendEquation,
+        src_color: BlendFactor,
+        dst_color: BlendFactor,
+        src_alpha: BlendFactor,
+        dst_alpha: BlendFactor,
+    },
+    Logic(LogicOperation),
+}
+
+impl ColorOperation {
+    pub(crate) fn apply(self) {
+        match self {
+            Self::Blend {
+                color_equation,
+                alpha_equation,
+                src_color,
+                dst_color,
+                src_alpha,
+                dst_alpha,
+            } => unsafe {
+                citro3d_sys::C3D_AlphaBlend(
+                    color_equation as ctru_sys::GPU_BLENDEQUATION,
+                    alpha_equation as ctru_sys::GPU_BLENDEQUATION,
+                    src_color as ctru_sys::GPU_BLENDFACTOR,
+                    dst_color as ctru_sys::GPU_BLENDFACTOR,
+                    src_alpha as ctru_sys::GPU_BLENDFACTOR,
+                    dst_alpha as ctru_sys::GPU_BLENDFACTOR,
+                );
+            },
+            Self::Logic(operation) => unsafe {
+                citro3d_sys::C3D_ColorLogicOp(operation as ctru_sys::GPU_LOGICOP);
+            },
+        }
+    }
+}
+
+impl Default for State {
+    /// The state set up by `C3D_Init`.
+    fn default() -> Self {
+        Self {
+            fragment_operation_mode: FragmentOperationMode::Gl,
+            shadow_penumbra: (0.0, 1.0),
+            shadow_sampling: (false, 0.0),
+            depth_map: (true, -1.0, 0.0),
+            depth_test: (true, TestFunction::Greater, WriteMask::All),
+            color_operation: ColorOperation::Blend {
+                color_equation: BlendEquation::Add,
+                alpha_equation: BlendEquation::Add,
+                src_color: BlendFactor::SrcAlpha,
+                dst_color: BlendFactor::OneMinusSrcAlpha,
+                src_alpha: BlendFactor::SrcAlpha,
+                dst_alpha: BlendFactor::OneMinusSrcAlpha,
+            },
+            blend_color: 0,
+            stencil_test: (false, TestFunction::Always, 0, 0xFF, 0),
+            stencil_op: [StencilOperation::Keep; 3],
+            alpha_test: (false, TestFunction::Always, 0),
+            early_depth_test: (false, EarlyDepthFunction::Greater, 0),
+        }
+    }
+}
+
+impl State {
+    /// Send all of the settings to `citro3d`.
+    pub(crate) fn apply(&self) {
+        let (scale, bias) = self.shadow_penumbra;
+        let (perspective, shadow_bias) = self.shadow_sampling;
+        let (z_buffer, depth_scale, depth_offset) = self.depth_map;
+        let (depth_test, depth_function, write_mask) = self.depth_test;
+        let (stencil_test, stencil_function, reference, input_mask, stencil_write_mask) =
+            self.stencil_test;
+        let [stencil_fail, depth_fail, pass] = self.stencil_op;
+        let (alpha_test, alpha_function, alpha_reference) = self.alpha_test;
+        let (early_depth_test, early_depth_function, early_depth_reference) = self.early_depth_test;
+
+        unsafe {
+            citro3d_sys::C3D_FragOpMode(self.fragment_operation_mode as ctru_sys::GPU_FRAGOPMODE);
+            citro3d_sys::C3D_FragOpShadow(scale, bias);
+            citro3d_sys::C3D_TexShadowParams(perspective, shadow_bias);
+            citro3d_sys::C3D_DepthMap(z_buffer, depth_scale, depth_offset);
+            citro3d_sys::C3D_DepthTest(
+                depth_test,
+                depth_function as ctru_sys::GPU_TESTFUNC,
+                write_mask as ctru_sys::GPU_WRITEMASK,
+            );
+            citro3d_sys::C3D_BlendingColor(self.blend_color);
+            citro3d_sys::C3D_StencilTest(
+                stencil_test,
+                stencil_function as ctru_sys::GPU_TESTFUNC,
+                reference.into(),
+                input_mask.into(),
+                stencil_write_mask.into(),
+            );
+            citro3d_sys::C3D_StencilOp(
+                stencil_fail as ctru_sys::GPU_STENCILOP,
+                depth_fail as ctru_sys::GPU_STENCILOP,
+                pass as ctru_sys::GPU_STENCILOP,
+            );
+            citro3d_sys::C3D_AlphaTest(
+                alpha_test,
+                alpha_function as ctru_sys::GPU_TESTFUNC,
+                alpha_reference.into(),
+            );
+            citro3d_sys::C3D_EarlyDepthTest(
+                early_depth_test,
+                early_depth_function as ctru_sys::GPU_EARLYDEPTHFUNC,
+                early_depth_reference,
+            );
+        }
+
+        self.color_operation.apply();
+    }
+}
+
 /// Test functions.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,149 @@
+//! Immediate-mode drawing, which sends vertices straight to the GPU instead of
+//! reading them from a vertex buffer.
+
+use crate::{Error, Instance, Result, buffer};
+
+/// An immediate-mode draw call, started with
+/// [`Frame::draw_immediate`](super::Frame::draw_immediate). This is handy for
+/// small amounts of geometry (e.g. debug lines) which aren't worth setting up
+/// vertex buffers for.
+///
+/// Each vertex is made up of one value per attribute in the current
+/// [`attrib::Info`](crate::attrib::Info), in the order the attributes were
+/// added. Attributes are always sent as floats. The draw call ends when this is
+/// dropped.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::{attrib, buffer, render};
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// let mut attr_info = attrib::Info::new();
+/// let position = attrib::Register::new(0).unwrap();
+/// let color = attrib::Register::new(1).unwrap();
+/// attr_info
+///     .add_loader(position, attrib::Format::Float, 3)
+///     .unwrap();
+/// attr_info
+///     .add_loader(color, attrib::Format::Float, 3)
+///     .unwrap();
+///
+/// let mut frame = instance.begin_frame(render::FrameFlags::SYNC_DRAW).unwrap();
+/// frame.set_attr_info(&attr_info);
+///
+/// let mut draw = frame.draw_immediate(buffer::Primitive::Triangles).unwrap();
+/// draw.vertex(&[&[0.0, 0.5, 0.5], &[1.0, 0.0, 0.0]]).unwrap();
+/// draw.vertex(&[&[-0.5, -0.5, 0.5], &[0.0, 1.0, 0.0]])
+///     .unwrap();
+/// // The wrong number of elements for the color attribute.
+/// assert!(draw.vertex(&[&[0.5, -0.5, 0.5], &[0.0, 0.0]]).is_err());
+/// draw.vertex(&[&[0.5, -0.5, 0.5], &[0.0, 0.0, 1.0]]).unwrap();
+/// draw.end();
+/// ```
+#[must_use = "the draw call ends as soon as it is dropped"]
+#[doc(alias = "C3D_ImmDrawBegin")]
+#[doc(alias = "C3D_ImmDrawEnd")]
+pub struct ImmediateDraw<'frame> {
+    _instance: &'frame mut Instance,
+    element_counts: Vec<u8>,
+    vertices: usize,
+}
+
+impl<'frame> ImmediateDraw<'frame> {
+    pub(super) fn begin(
+        instance: &'frame mut Instance,
+        primitive: buffer::Primitive,
+    ) -> Result<Self> {
+        let element_counts: Vec<u8> = instance
+            .attr_info()
+            .map(|info| info.element_counts().collect())
+            .unwrap_or_default();
+        if element_counts.is_empty() {
+            return Err(Error::InvalidSize);
+        }
+
+        unsafe {
+            citro3d_sys::C3D_ImmDrawBegin(primitive as ctru_sys::GPU_Primitive_t);
+        }
+
+        Ok(Self {
+            _instance: instance,
+            element_counts,
+            vertices: 0,
+        })
+    }
+
+    /// Send a vertex, made up of the elements of each of its attributes.
+    /// Missing `y` and `z` elements default to 0 and `w` to 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if the number of attributes, or of
+    /// elements in any attribute, doesn't match the current attribute info.
+    /// Nothing is sent in that case.
+    #[doc(alias = "C3D_ImmSendAttrib")]
+    pub fn vertex(&mut self, attributes: &[&[f32]]) -> Result<()> {
+        check_vertex(&self.element_counts, attributes)?;
+
+        for attribute in attributes {
+            let mut xyzw = [0.0, 0.0, 0.0, 1.0];
+            xyzw[..attribute.len()].copy_from_slice(attribute);
+            let [x, y, z, w] = xyzw;
+            unsafe {
+                citro3d_sys::C3D_ImmSendAttrib(x, y, z, w);
+            }
+        }
+
+        self.vertices += 1;
+        Ok(())
+    }
+
+    /// The number of vertices sent so far.
+    pub fn vertex_count(&self) -> usize {
+        self.vertices
+    }
+
+    /// End the draw call. This is the same as dropping it, but makes the end of
+    /// the draw call explicit.
+    pub fn end(self) {
+        drop(self);
+    }
+}
+
+impl Drop for ImmediateDraw<'_> {
+    #[doc(alias = "C3D_ImmDrawEnd")]
+    fn drop(&mut self) {
+        unsafe {
+            citro3d_sys::C3D_ImmDrawEnd();
+        }
+    }
+}
+
+fn check_vertex(element_counts: &[u8], attributes: &[&[f32]]) -> Result<()> {
+    let matches = attributes.len() == element_counts.len()
+        && attributes
+            .iter()
+            .zip(element_counts)
+            .all(|(attribute, &count)| attribute.len() == usize::from(count));
+
+    if matches {
+        Ok(())
+    } else {
+        Err(Error::InvalidSize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_layout() {
+        let counts = [3, 2];
+        assert!(check_vertex(&counts, &[&[0.0; 3], &[0.0; 2]]).is_ok());
+        assert!(check_vertex(&counts, &[&[0.0; 3]]).is_err());
+        assert!(check_vertex(&counts, &[&[0.0; 2], &[0.0; 3]]).is_err());
+        assert!(check_vertex(&counts, &[&[0.0; 3], &[0.0; 2], &[0.0]]).is_err());
+    }
+}
@@ -1,29 +1,353 @@
-use citro3d_sys::{GX_TRANSFER_IN_FORMAT, GX_TRANSFER_OUT_FORMAT};
-use ctru_sys::GX_TRANSFER_FORMAT;
+//! GX data transfers, which copy images in and out of the GPU's tiled layout,
+//! e.g. to display a rendered image in a screen's framebuffer.
+//!
+//! Render targets are transferred to the screen automatically when a frame
+//! ends, but the functions in this module can be used to build
+//! post-processing chains or load images without going through the CPU.
+
+use citro3d_sys::{
+    GX_TRANSFER_FLIP_VERT, GX_TRANSFER_IN_FORMAT, GX_TRANSFER_OUT_FORMAT, GX_TRANSFER_OUT_TILED,
+    GX_TRANSFER_RAW_COPY, GX_TRANSFER_SCALING,
+};
+use ctru_sys::{GX_TRANSFER_FORMAT, GX_TRANSFER_SCALE};
 
 use super::ColorFormat;
+use crate::error::ctru_result;
+use crate::{Error, Result};
+
+/// Convert an image with the GPU's display transfer engine, e.g. to copy a
+/// rendered image into a framebuffer.
+///
+/// `input` holds a `width` by `height` image in the input format of `flags`,
+/// and the converted image is written to `output` in the output format,
+/// downscaled according to [`Flags::scaling`]. By default, the input is in the
+/// GPU's tiled layout and the output is linear; [`Flags::out_tiled`] converts
+/// the other way around.
+///
+/// Any drawing submitted before this call is finished first, and the transfer
+/// is complete once this returns.
+///
+/// # Errors
+///
+/// * [`Error::InvalidSize`] if `width` or `height` is not a multiple of 8 from
+///   8 to 1024, or either buffer is too small for the image.
+/// * [`Error::InvalidMemoryLocation`] if either buffer is not in linear memory
+///   or VRAM, which the GPU can't access.
+/// * [`Error::Ctru`] if the buffers couldn't be synchronized with the CPU's cache.
+#[doc(alias = "GX_DisplayTransfer")]
+#[doc(alias = "C3D_SyncDisplayTransfer")]
+pub fn display_transfer(
+    input: &[u8],
+    output: &mut [u8],
+    width: usize,
+    height: usize,
+    flags: Flags,
+) -> Result<()> {
+    let valid_dimension = |d: usize| d.is_multiple_of(8) && (8..=1024).contains(&d);
+    if !valid_dimension(width) || !valid_dimension(height) {
+        return Err(Error::InvalidSize);
+    }
+
+    let (out_width, out_height) = match flags.scaling_mode() {
+        Scaling::None => (width, height),
+        Scaling::X => (width / 2, height),
+        Scaling::XY => (width / 2, height / 2),
+    };
+    let (in_format, out_format) = flags.formats()?;
+    if input.len() < width * height * in_format.bytes_per_pixel()
+        || output.len() < out_width * out_height * out_format.bytes_per_pixel()
+    {
+        return Err(Error::InvalidSize);
+    }
+
+    prepare(input, output)?;
+
+    unsafe {
+        citro3d_sys::C3D_SyncDisplayTransfer(
+            input.as_ptr().cast_mut().cast(),
+            citro3d_sys::GX_BUFFER_DIM(width.try_into()?, height.try_into()?),
+            output.as_mut_ptr().cast(),
+            citro3d_sys::GX_BUFFER_DIM(out_width.try_into()?, out_height.try_into()?),
+            flags.bits(),
+        );
+    }
+
+    finish(output)
+}
+
+/// Copy the contents of `input` to the start of `output` with the GPU, e.g.
+/// to copy between VRAM buffers. No format conversion is done.
+///
+/// Any drawing submitted before this call is finished first, and the copy is
+/// complete once this returns.
+///
+/// # Errors
+///
+/// * [`Error::InvalidSize`] if `input` is empty, its length is not a multiple
+///   of 16, or `output` is shorter than it.
+/// * [`Error::InvalidMemoryLocation`] if either buffer is not in linear memory
+///   or VRAM, which the GPU can't access.
+/// * [`Error::Ctru`] if the buffers couldn't be synchronized with the CPU's cache.
+#[doc(alias = "GX_TextureCopy")]
+#[doc(alias = "C3D_SyncTextureCopy")]
+pub fn texture_copy(input: &[u8], output: &mut [u8]) -> Result<()> {
+    if input.is_empty() || !input.len().is_multiple_of(16) || output.len() < input.len() {
+        return Err(Error::InvalidSize);
+    }
+
+    prepare(input, output)?;
+
+    unsafe {
+        citro3d_sys::C3D_SyncTextureCopy(
+            input.as_ptr().cast_mut().cast(),
+            0,
+            output.as_mut_ptr().cast(),
+            0,
+            input.len().try_into()?,
+            Flags::default().raw_copy(true).bits(),
+        );
+    }
+
+    finish(output)
+}
+
+/// A value to fill memory with using [`memory_fill`], e.g. a clear color in
+/// the format of the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[doc(alias = "GX_FILL_CONTROL")]
+pub enum FillValue {
+    /// A 16-bit value, e.g. an RGB565 color or 16-bit depth.
+    #[doc(alias = "GX_FILL_16BIT_DEPTH")]
+    U16(u16),
+    /// A 24-bit value stored in the low bits, e.g. an RGB8 color or 24-bit depth.
+    #[doc(alias = "GX_FILL_24BIT_DEPTH")]
+    U24(u32),
+    /// A 32-bit value, e.g. an RGBA8 color or 24-bit depth with 8-bit stencil.
+    #[doc(alias = "GX_FILL_32BIT_DEPTH")]
+    U32(u32),
+}
+
+impl FillValue {
+    /// The fill value for a buffer with `bytes` per element, using the low
+    /// bits of `value`.
+    pub(super) fn with_size(bytes: usize, value: u32) -> Self {
+        match bytes {
+            2 => Self::U16(value as u16),
+            3 => Self::U24(value),
+            _ => Self::U32(value),
+        }
+    }
+
+    pub(super) fn size(self) -> usize {
+        match self {
+            Self::U16(_) => 2,
+            Self::U24(_) => 3,
+            Self::U32(_) => 4,
+        }
+    }
+
+    pub(super) fn as_raw(self) -> (u32, u16) {
+        let (value, width) = match self {
+            Self::U16(value) => (value.into(), ctru_sys::GX_FILL_16BIT_DEPTH),
+            Self::U24(value) => (value & 0xFF_FFFF, ctru_sys::GX_FILL_24BIT_DEPTH),
+            Self::U32(value) => (value, ctru_sys::GX_FILL_32BIT_DEPTH),
+        };
+        (value, (width | ctru_sys::GX_FILL_TRIGGER) as u16)
+    }
+}
+
+/// Fill a buffer with a repeated value using the GPU, e.g. to clear a
+/// manually managed framebuffer or depth buffer. This is much faster than
+/// filling large buffers with the CPU.
+///
+/// Any drawing submitted before this call is finished first, and the fill is
+/// complete once this returns.
+///
+/// # Errors
+///
+/// * [`Error::InvalidSize`] if `buffer` is empty, its start is not aligned to
+///   8 bytes, or its length is not a multiple of 8 bytes and of the size of
+///   `value`.
+/// * [`Error::InvalidMemoryLocation`] if `buffer` is not in linear memory or
+///   VRAM, which the GPU can't access.
+/// * [`Error::Ctru`] if the buffer couldn't be synchronized with the CPU's cache.
+#[doc(alias = "GX_MemoryFill")]
+#[doc(alias = "C3D_SyncMemoryFill")]
+pub fn memory_fill(buffer: &mut [u8], value: FillValue) -> Result<()> {
+    let len = buffer.len();
+    if len == 0
+        || !(buffer.as_ptr() as usize).is_multiple_of(8)
+        || !len.is_multiple_of(8)
+        || !len.is_multiple_of(value.size())
+    {
+        return Err(Error::InvalidSize);
+    }
+
+    // Write back any cached data first, so it can't overwrite the filled memory later.
+    prepare(buffer, buffer)?;
+
+    let (raw_value, control) = value.as_raw();
+    let start = buffer.as_mut_ptr();
+    unsafe {
+        citro3d_sys::C3D_SyncMemoryFill(
+            start.cast(),
+            raw_value,
+            start.add(len).cast(),
+            control,
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            0,
+        );
+    }
+
+    finish(buffer)
+}
+
+/// Whether the GPU can access `data` directly, i.e. it has a physical address.
+pub(crate) fn is_gpu_accessible(data: &[u8]) -> bool {
+    unsafe { ctru_sys::osConvertVirtToPhys(data.as_ptr().cast()) != 0 }
+}
+
+fn is_vram(data: &[u8]) -> bool {
+    let start = data.as_ptr() as u32;
+    (ctru_sys::OS_VRAM_VADDR..ctru_sys::OS_VRAM_VADDR + ctru_sys::OS_VRAM_SIZE).contains(&start)
+}
+
+/// Check that the buffers can be used by the GPU, and write the input back
+/// from the CPU's cache so the GPU sees its current contents.
+fn prepare(input: &[u8], output: &[u8]) -> Result<()> {
+    if !is_gpu_accessible(input) || !is_gpu_accessible(output) {
+        return Err(Error::InvalidMemoryLocation);
+    }
+    if is_vram(input) {
+        // VRAM isn't cached by the CPU.
+        return Ok(());
+    }
+
+    let result =
+        unsafe { ctru_sys::GSPGPU_FlushDataCache(input.as_ptr().cast(), input.len().try_into()?) };
+    ctru_result(result)
+}
+
+/// Drop any stale copy of the output from the CPU's cache.
+fn finish(output: &[u8]) -> Result<()> {
+    if is_vram(output) {
+        return Ok(());
+    }
+
+    let result = unsafe {
+        ctru_sys::GSPGPU_InvalidateDataCache(output.as_ptr().cast(), output.len().try_into()?)
+    };
+    ctru_result(result)
+}
 
 /// Control flags for a GX data transfer.
-#[derive(Default, Clone, Copy)]
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::render::transfer::{Flags, Format, Scaling};
+/// let flags = Flags::default()
+///     .in_format(Format::RGBA8)
+///     .out_format(Format::RGB565)
+///     .scaling(Scaling::X);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[doc(alias = "GX_TRANSFER_FLAGS")]
 pub struct Flags(u32);
 
 impl Flags {
+    const FLIP_VERT_MASK: u32 = GX_TRANSFER_FLIP_VERT(true);
+    const OUT_TILED_MASK: u32 = GX_TRANSFER_OUT_TILED(true);
+    const RAW_COPY_MASK: u32 = GX_TRANSFER_RAW_COPY(true);
+    const IN_FORMAT_MASK: u32 = GX_TRANSFER_IN_FORMAT(0b111);
+    const OUT_FORMAT_MASK: u32 = GX_TRANSFER_OUT_FORMAT(0b111);
+    const SCALING_MASK: u32 = GX_TRANSFER_SCALING(0b11);
+
     /// Set the input format of the data transfer.
     #[must_use]
+    #[doc(alias = "GX_TRANSFER_IN_FORMAT")]
     pub fn in_format(self, fmt: Format) -> Self {
-        Self(self.0 | GX_TRANSFER_IN_FORMAT(fmt as GX_TRANSFER_FORMAT))
+        self.with(
+            Self::IN_FORMAT_MASK,
+            GX_TRANSFER_IN_FORMAT(fmt as GX_TRANSFER_FORMAT),
+        )
     }
 
     /// Set the output format of the data transfer.
     #[must_use]
+    #[doc(alias = "GX_TRANSFER_OUT_FORMAT")]
     pub fn out_format(self, fmt: Format) -> Self {
-        Self(self.0 | GX_TRANSFER_OUT_FORMAT(fmt as GX_TRANSFER_FORMAT))
+        self.with(
+            Self::OUT_FORMAT_MASK,
+            GX_TRANSFER_OUT_FORMAT(fmt as GX_TRANSFER_FORMAT),
+        )
+    }
+
+    /// Set whether the image is flipped vertically during the transfer.
+    #[must_use]
+    #[doc(alias = "GX_TRANSFER_FLIP_VERT")]
+    pub fn flip_vertical(self, flip: bool) -> Self {
+        self.with(Self::FLIP_VERT_MASK, GX_TRANSFER_FLIP_VERT(flip))
+    }
+
+    /// Set whether linear input is converted into the GPU's tiled layout (e.g.
+    /// to load an image into a texture), instead of converting tiled input into
+    /// linear rows (e.g. to display it).
+    #[must_use]
+    #[doc(alias = "GX_TRANSFER_OUT_TILED")]
+    pub fn out_tiled(self, tiled: bool) -> Self {
+        self.with(Self::OUT_TILED_MASK, GX_TRANSFER_OUT_TILED(tiled))
     }
 
+    /// Set whether the data is copied as-is, without any format conversion
+    /// or tiling.
+    #[must_use]
+    #[doc(alias = "GX_TRANSFER_RAW_COPY")]
+    pub fn raw_copy(self, raw_copy: bool) -> Self {
+        self.with(Self::RAW_COPY_MASK, GX_TRANSFER_RAW_COPY(raw_copy))
+    }
+
+    /// Set how the image is downscaled during the transfer.
+    #[must_use]
+    #[doc(alias = "GX_TRANSFER_SCALING")]
+    pub fn scaling(self, scaling: Scaling) -> Self {
+        self.with(
+            Self::SCALING_MASK,
+            GX_TRANSFER_SCALING(scaling as GX_TRANSFER_SCALE),
+        )
+    }
+
+    /// The raw value of the flags, as passed to `libctru`.
     #[must_use]
     pub fn bits(self) -> u32 {
         self.0
     }
+
+    pub(crate) fn scaling_mode(self) -> Scaling {
+        match (self.0 & Self::SCALING_MASK) >> Self::SCALING_MASK.trailing_zeros() {
+            1 => Scaling::X,
+            2 => Scaling::XY,
+            _ => Scaling::None,
+        }
+    }
+
+    fn formats(self) -> Result<(Format, Format)> {
+        let field = |mask: u32| ((self.0 & mask) >> mask.trailing_zeros()) as u8;
+        match (
+            Format::try_from(field(Self::IN_FORMAT_MASK)),
+            Format::try_from(field(Self::OUT_FORMAT_MASK)),
+        ) {
+            (Ok(in_format), Ok(out_format)) => Ok((in_format, out_format)),
+            _ => Err(Error::InvalidSize),
+        }
+    }
+
+    fn with(self, mask: u32, bits: u32) -> Self {
+        Self((self.0 & !mask) | bits)
+    }
 }
 
 /// The color format to use when transferring data to/from the GPU.
@@ -32,20 +356,46 @@ impl Flags {
 /// convertible to one another. Use [`From::from`] to get the [`Format`] corresponding
 /// to a given [`ColorFormat`].
 #[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[doc(alias = "GX_TRANSFER_FORMAT")]
 pub enum Format {
     /// 8-bit Red + 8-bit Green + 8-bit Blue + 8-bit Alpha.
     RGBA8 = ctru_sys::GX_TRANSFER_FMT_RGBA8,
     /// 8-bit Red + 8-bit Green + 8-bit Blue.
     RGB8 = ctru_sys::GX_TRANSFER_FMT_RGB8,
-    /// 5-bit Red + 5-bit Green + 5-bit Blue + 1-bit Alpha.
-    RGB565 = ctru_sys::GX_TRANSFER_FMT_RGB565,
     /// 5-bit Red + 6-bit Green + 5-bit Blue.
+    RGB565 = ctru_sys::GX_TRANSFER_FMT_RGB565,
+    /// 5-bit Red + 5-bit Green + 5-bit Blue + 1-bit Alpha.
     RGB5A1 = ctru_sys::GX_TRANSFER_FMT_RGB5A1,
     /// 4-bit Red + 4-bit Green + 4-bit Blue + 4-bit Alpha.
     RGBA4 = ctru_sys::GX_TRANSFER_FMT_RGBA4,
 }
 
+impl Format {
+    /// The number of bytes used to store a single pixel in this format.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::RGBA8 => 4,
+            Self::RGB8 => 3,
+            Self::RGB565 | Self::RGB5A1 | Self::RGBA4 => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Format {
+    type Error = String;
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ctru_sys::GX_TRANSFER_FMT_RGBA8 => Ok(Self::RGBA8),
+            ctru_sys::GX_TRANSFER_FMT_RGB8 => Ok(Self::RGB8),
+            ctru_sys::GX_TRANSFER_FMT_RGB565 => Ok(Self::RGB565),
+            ctru_sys::GX_TRANSFER_FMT_RGB5A1 => Ok(Self::RGB5A1),
+            ctru_sys::GX_TRANSFER_FMT_RGBA4 => Ok(Self::RGBA4),
+            _ => Err("invalid value for Format".to_string()),
+        }
+    }
+}
+
 impl From<ColorFormat> for Format {
     fn from(color_fmt: ColorFormat) -> Self {
         match color_fmt {
@@ -57,3 +407,52 @@ impl From<ColorFormat> for Format {
         }
     }
 }
+
+/// Downscaling applied during a transfer, e.g. to resolve a supersampled
+/// (anti-aliased) render target into a framebuffer.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[doc(alias = "GX_TRANSFER_SCALE")]
+pub enum Scaling {
+    /// No scaling.
+    #[default]
+    #[doc(alias = "GX_TRANSFER_SCALE_NO")]
+    None = ctru_sys::GX_TRANSFER_SCALE_NO,
+    /// Halve the width, averaging pairs of horizontal pixels.
+    #[doc(alias = "GX_TRANSFER_SCALE_X")]
+    X = ctru_sys::GX_TRANSFER_SCALE_X,
+    /// Halve the width and height, averaging 2x2 blocks of pixels.
+    #[doc(alias = "GX_TRANSFER_SCALE_XY")]
+    XY = ctru_sys::GX_TRANSFER_SCALE_XY,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_replace_previous() {
+        let flags = Flags::default()
+            .in_format(Format::RGBA4)
+            .in_format(Format::RGB8)
+            .scaling(Scaling::XY)
+            .scaling(Scaling::X);
+
+        assert_eq!(
+            flags,
+            Flags::default().in_format(Format::RGB8).scaling(Scaling::X)
+        );
+    }
+
+    #[test]
+    fn decode_flags() {
+        let flags = Flags::default()
+            .in_format(Format::RGB565)
+            .out_format(Format::RGBA4)
+            .scaling(Scaling::XY)
+            .flip_vertical(true);
+
+        assert_eq!(flags.scaling_mode(), Scaling::XY);
+        assert_eq!(flags.formats().unwrap(), (Format::RGB565, Format::RGBA4));
+    }
+}
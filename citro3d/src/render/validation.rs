@@ -0,0 +1,173 @@
+//! Checks run before each draw call with the `validation` feature, to panic
+//! with a clear message instead of hanging the GPU.
+
+use crate::{Instance, attrib, buffer, shader};
+
+/// Check that the draw call described by `primitive` and `vbo_data` can be
+/// issued with the instance's current state.
+///
+/// # Panics
+///
+/// If no shader program is bound, `primitive` doesn't match whether the
+/// program has a geometry shader, the attribute info doesn't match the inputs
+/// of the vertex shader, the vertex buffers don't match the current attribute
+/// info, or `vbo_data` reads past the end of the vertex buffers.
+#[track_caller]
+pub(super) fn check_draw(
+    instance: &Instance,
+    primitive: buffer::Primitive,
+    vbo_data: &buffer::Slice,
+) {
+    let program = instance
+        .program()
+        .expect("no shader program is bound; call `Instance::bind_program` before drawing");
+
+    let geometry_prim = primitive == buffer::Primitive::GeometryPrim;
+    assert!(
+        program.has_geometry_shader() == geometry_prim,
+        "programs with a geometry shader must draw `Primitive::GeometryPrim`, \
+        and programs without one must not (drawing {primitive:?})"
+    );
+
+    check_vertex_range(vbo_data);
+
+    let attr_info = instance
+        .attr_info()
+        .expect("no attribute info is set; call `Instance::set_attr_info` before drawing");
+    check_program_inputs(program, &attr_info);
+    check_buffers(vbo_data.info(), &attr_info);
+}
+
+/// Check that every vertex in `vbo_data` is within all of the vertex buffers
+/// registered in its [`buffer::Info`].
+#[track_caller]
+fn check_vertex_range(vbo_data: &buffer::Slice) {
+    let Some(vertex_count) = vbo_data.info().vertex_count() else {
+        return;
+    };
+
+    let (index, len) = (vbo_data.index(), vbo_data.len());
+    assert!(
+        index >= 0
+            && len >= 0
+            && index
+                .checked_add(len)
+                .is_some_and(|end| end <= vertex_count),
+        "invalid vertex range: {len} vertices starting at {index}, but the vertex buffers only \
+        have {vertex_count} vertices"
+    );
+}
+
+/// Check that `attr_info` loads exactly the input registers of the vertex
+/// shader of `program`.
+#[track_caller]
+fn check_program_inputs(program: &shader::Program, attr_info: &attrib::Info) {
+    if let Err(crate::Error::AttributeMismatch { register }) = program.validate_attrs(attr_info) {
+        panic!(
+            "the attribute info doesn't match the inputs of the vertex shader: register \
+            v{register} is either a shader input which is not loaded, or loaded but not a \
+            shader input"
+        );
+    }
+}
+
+/// Check that the vertex buffers in `buf_info` load every attribute of
+/// `attr_info`, and have room for them.
+#[track_caller]
+fn check_buffers(buf_info: &buffer::Info, attr_info: &attrib::Info) {
+    let sizes: Vec<usize> = attr_info.sizes().collect();
+    assert!(!sizes.is_empty(), "the attribute info has no attributes");
+
+    let mut loaded = vec![false; sizes.len()];
+    for (i, buffer) in buf_info.buffers().enumerate() {
+        let mut size = 0;
+        for attribute in buffer.attributes() {
+            let attribute = usize::from(u8::from(attribute));
+            let Some(&attribute_size) = sizes.get(attribute) else {
+                panic!(
+                    "vertex buffer {i} loads attribute {attribute}, but the attribute info \
+                    only has {} attributes",
+                    sizes.len()
+                );
+            };
+            loaded[attribute] = true;
+            size += attribute_size;
+        }
+
+        let stride = buffer.stride();
+        assert!(
+            size <= usize::from(stride),
+            "vertex buffer {i} has a stride of {stride} bytes, but its attributes take up \
+            {size} bytes"
+        );
+    }
+
+    if let Some(attribute) = loaded.iter().position(|&loaded| !loaded) {
+        panic!("attribute {attribute} is not loaded from any vertex buffer");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::LinearBuffer;
+    use crate::macros::include_shader;
+
+    static SHADER_BYTES: &[u8] = include_shader!("../../examples/assets/frag-shader.pica");
+
+    fn attr_info(count: u16) -> attrib::Info {
+        let mut attr_info = attrib::Info::new();
+        for register in 0..count {
+            attr_info
+                .add_loader(
+                    attrib::Register::new(register).unwrap(),
+                    attrib::Format::Float,
+                    3,
+                )
+                .unwrap();
+        }
+        attr_info
+    }
+
+    #[test]
+    fn vertex_range_in_bounds() {
+        let vertices = LinearBuffer::from([[0.0_f32; 3]; 6].as_slice());
+        let mut buf_info = buffer::Info::new();
+        let slice = buf_info.add(&vertices, &attr_info(1)).unwrap();
+
+        check_vertex_range(&slice);
+        check_vertex_range(&slice.subslice(3..).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid vertex range")]
+    fn vertex_range_past_shorter_buffer() {
+        let short = LinearBuffer::from([[0.0_f32; 3]; 3].as_slice());
+        let long = LinearBuffer::from([[0.0_f32; 3]; 6].as_slice());
+        let mut buf_info = buffer::Info::new();
+        buf_info.add(&short, &attr_info(1)).unwrap();
+
+        // The slice covers all of `long`, but `short` only has 3 vertices.
+        let slice = buf_info.add(&long, &attr_info(1)).unwrap();
+        assert_eq!(slice.info().vertex_count(), Some(3));
+        check_vertex_range(&slice);
+    }
+
+    #[test]
+    fn program_inputs_match() {
+        let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+        let program = shader::Program::new(library.get(0).unwrap()).unwrap();
+
+        // The shader declares `inpos`, `innrm` and `intex` as inputs.
+        check_program_inputs(&program, &attr_info(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "register v2")]
+    fn program_inputs_missing_attribute() {
+        let library = shader::Library::from_bytes(SHADER_BYTES).unwrap();
+        let program = shader::Program::new(library.get(0).unwrap()).unwrap();
+
+        check_program_inputs(&program, &attr_info(2));
+    }
+}
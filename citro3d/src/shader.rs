@@ -4,11 +4,10 @@
 //! For more details about the PICA200 compiler / shader language, see
 //! documentation for <https://github.com/devkitPro/picasso>.
 
-use std::error::Error;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::mem::MaybeUninit;
 
-use crate::uniform;
+use crate::{Error, attrib, uniform};
 
 /// A PICA200 shader program. It may have one or both of:
 ///
@@ -27,54 +26,53 @@ impl Program {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
+    /// Returns [`Error::Ctru`] if:
     /// * the shader program cannot be initialized
     /// * the input shader is not a vertex shader or is otherwise invalid
     #[doc(alias = "shaderProgramInit")]
     #[doc(alias = "shaderProgramSetVsh")]
-    pub fn new(vertex_shader: Entrypoint) -> Result<Self, ctru::Error> {
+    pub fn new(vertex_shader: Entrypoint) -> crate::Result<Self> {
         let mut program = unsafe {
             let mut program = MaybeUninit::uninit();
-            let result = ctru_sys::shaderProgramInit(program.as_mut_ptr());
-            if result != 0 {
-                return Err(ctru::Error::from(result));
-            }
+            crate::error::ctru_result(ctru_sys::shaderProgramInit(program.as_mut_ptr()))?;
             program.assume_init()
         };
 
         let ret = unsafe { ctru_sys::shaderProgramSetVsh(&mut program, vertex_shader.as_raw()) };
-
-        if ret == 0 {
-            Ok(Self { program })
-        } else {
-            Err(ctru::Error::from(ret))
+        if let Err(err) = crate::error::ctru_result(ret) {
+            let _ = unsafe { ctru_sys::shaderProgramFree(&mut program) };
+            return Err(err);
         }
+
+        Ok(Self { program })
     }
 
     /// Set the geometry shader for a given program.
     ///
     /// # Errors
     ///
-    /// Returns an error if the input shader is not a geometry shader or is
-    /// otherwise invalid.
+    /// Returns [`Error::Ctru`] if the input shader is not a geometry shader or
+    /// is otherwise invalid.
     #[doc(alias = "shaderProgramSetGsh")]
     pub fn set_geometry_shader(
         &mut self,
         geometry_shader: Entrypoint,
         stride: u8,
-    ) -> Result<(), ctru::Error> {
+    ) -> crate::Result<()> {
         let ret = unsafe {
             ctru_sys::shaderProgramSetGsh(&mut self.program, geometry_shader.as_raw(), stride)
         };
+        crate::error::ctru_result(ret)
+    }
 
-        if ret == 0 {
-            Ok(())
-        } else {
-            Err(ctru::Error::from(ret))
-        }
+    /// Whether the program has a geometry shader. If so, vertices must be
+    /// drawn as [`Primitive::GeometryPrim`](crate::buffer::Primitive::GeometryPrim).
+    pub fn has_geometry_shader(&self) -> bool {
+        !self.program.geometryShader.is_null()
     }
 
-    /// Get the index of a uniform by name.
+    /// Get the index of a vertex shader uniform by name. This is the same as
+    /// [`uniform_index`](Self::uniform_index) with [`Type::Vertex`].
     ///
     /// # Errors
     ///
@@ -82,16 +80,34 @@ impl Program {
     /// * If a uniform with the given `name` could not be found
     #[doc(alias = "shaderInstanceGetUniformLocation")]
     pub fn get_uniform(&self, name: &str) -> crate::Result<uniform::Index> {
-        let vertex_instance = unsafe { (*self.as_raw()).vertexShader };
-        assert!(
-            !vertex_instance.is_null(),
-            "vertex shader should never be null!"
-        );
+        self.uniform_index(Type::Vertex, name)
+    }
 
+    /// Get the index of a uniform of the vertex or geometry shader by name, so
+    /// it can be bound without hardcoding its register.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::shader;
+    /// # fn example(program: &shader::Program) -> citro3d::Result<()> {
+    /// let projection = program.uniform_index(shader::Type::Vertex, "projection")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * If the given `name` contains a null byte
+    /// * If the program has no shader of the given type
+    /// * If a uniform with the given `name` could not be found
+    #[doc(alias = "shaderInstanceGetUniformLocation")]
+    pub fn uniform_index(&self, ty: Type, name: &str) -> crate::Result<uniform::Index> {
+        let instance = self.instance(ty).ok_or(Error::NotFound)?;
         let name = CString::new(name)?;
 
-        let idx =
-            unsafe { ctru_sys::shaderInstanceGetUniformLocation(vertex_instance, name.as_ptr()) };
+        let idx = unsafe { ctru_sys::shaderInstanceGetUniformLocation(instance, name.as_ptr()) };
 
         if idx < 0 {
             Err(crate::Error::NotFound)
@@ -100,6 +116,95 @@ impl Program {
         }
     }
 
+    /// Get the index, size and kind of a uniform of the vertex or geometry
+    /// shader by name.
+    ///
+    /// # Errors
+    ///
+    /// * If the program has no shader of the given type
+    /// * If a uniform with the given `name` could not be found
+    pub fn uniform_info(&self, ty: Type, name: &str) -> crate::Result<uniform::Info> {
+        let instance = self.instance(ty).ok_or(Error::NotFound)?;
+        let dvle = unsafe { &*(*instance).dvle };
+        if dvle.uniformTableSize == 0 {
+            return Err(Error::NotFound);
+        }
+
+        let uniforms = unsafe {
+            std::slice::from_raw_parts(dvle.uniformTableData, dvle.uniformTableSize as usize)
+        };
+        let entry = uniforms
+            .iter()
+            .find(|uniform| {
+                let symbol = unsafe {
+                    CStr::from_ptr(dvle.symbolTableData.add(uniform.symbolOffset.into()))
+                };
+                symbol.to_bytes() == name.as_bytes()
+            })
+            .ok_or(Error::NotFound)?;
+
+        // The table numbers the input registers 0x0 to 0xF first, but those
+        // aren't uniforms.
+        let start = entry.startReg.checked_sub(0x10).ok_or(Error::NotFound)?;
+        let index = uniform::Index::from(start as u8);
+        let kind = uniform::Kind::of(index).ok_or(Error::NotFound)?;
+
+        Ok(uniform::Info {
+            index,
+            len: usize::from(entry.endReg - entry.startReg) + 1,
+            kind,
+        })
+    }
+
+    /// The shader instance of the given type, if the program has one.
+    fn instance(&self, ty: Type) -> Option<*mut ctru_sys::shaderInstance_s> {
+        let instance = match ty {
+            Type::Vertex => self.program.vertexShader,
+            Type::Geometry => self.program.geometryShader,
+        };
+        (!instance.is_null()).then_some(instance)
+    }
+
+    /// Check that `attr_info` loads exactly the input registers of the vertex
+    /// shader, so mismatches are caught up front instead of showing up as
+    /// garbage rendering.
+    ///
+    /// Only inputs declared with `.in` are recorded in the shader binary. If
+    /// the shader only uses `.alias` for its inputs, nothing can be checked and
+    /// this always succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AttributeMismatch`] if a shader input is not loaded by
+    /// any attribute, or an attribute is loaded into a register which is not a
+    /// shader input (or is already loaded by another attribute).
+    pub fn validate_attrs(&self, attr_info: &attrib::Info) -> crate::Result<()> {
+        let inputs = self.vertex_inputs();
+        if inputs == 0 {
+            return Ok(());
+        }
+
+        check_attrs(inputs, attr_info.registers())
+    }
+
+    /// A bitmask of the input registers declared by the vertex shader.
+    fn vertex_inputs(&self) -> u16 {
+        let dvle = unsafe { &*(*self.program.vertexShader).dvle };
+        if dvle.uniformTableSize == 0 {
+            return 0;
+        }
+
+        // Inputs are listed in the uniform table, using registers 0x0 to 0xF.
+        let uniforms = unsafe {
+            std::slice::from_raw_parts(dvle.uniformTableData, dvle.uniformTableSize as usize)
+        };
+        uniforms
+            .iter()
+            .filter(|uniform| uniform.startReg < 0x10)
+            .flat_map(|uniform| uniform.startReg..=uniform.endReg.min(0xF))
+            .fold(0, |inputs, register| inputs | 1 << register)
+    }
+
     pub(crate) fn as_raw(&self) -> *const ctru_sys::shaderProgram_s {
         &self.program
     }
@@ -114,9 +219,27 @@ impl Drop for Program {
     }
 }
 
+fn check_attrs(inputs: u16, registers: impl Iterator<Item = u8>) -> crate::Result<()> {
+    let mut loaded = 0_u16;
+    for register in registers {
+        let bit = 1 << register;
+        if inputs & bit == 0 || loaded & bit != 0 {
+            return Err(Error::AttributeMismatch { register });
+        }
+        loaded |= bit;
+    }
+
+    match inputs & !loaded {
+        0 => Ok(()),
+        missing => Err(Error::AttributeMismatch {
+            register: missing.trailing_zeros() as u8,
+        }),
+    }
+}
+
 /// The type of a shader.
 #[repr(u8)]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub enum Type {
     /// A vertex shader.
     Vertex = ctru_sys::GPU_VERTEX_SHADER,
@@ -147,8 +270,12 @@ impl Library {
     /// An error is returned if the input data does not have an alignment of 4
     /// (cannot be safely converted to `&[u32]`).
     #[doc(alias = "DVLB_ParseFile")]
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let aligned: &[u32] = bytemuck::try_cast_slice(bytes)?;
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        // SAFETY: any bit pattern is a valid `u32`.
+        let (prefix, aligned, suffix) = unsafe { bytes.align_to::<u32>() };
+        if !prefix.is_empty() || !suffix.is_empty() {
+            return Err("shader data must be 4-byte aligned".into());
+        }
         Ok(Self(unsafe {
             ctru_sys::DVLB_ParseFile(
                 // SAFETY: we're trusting the parse implementation doesn't mutate
@@ -213,3 +340,24 @@ impl<'lib> Entrypoint<'lib> {
         self.ptr
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attrs_match_inputs() {
+        // v0 and v2
+        let inputs = 0b101;
+        assert!(check_attrs(inputs, [0, 2].into_iter()).is_ok());
+        assert!(check_attrs(inputs, [2, 0].into_iter()).is_ok());
+
+        let mismatch = |result| match result {
+            Err(Error::AttributeMismatch { register }) => register,
+            _ => panic!("expected a mismatch"),
+        };
+        assert_eq!(mismatch(check_attrs(inputs, [0].into_iter())), 2);
+        assert_eq!(mismatch(check_attrs(inputs, [0, 1, 2].into_iter())), 1);
+        assert_eq!(mismatch(check_attrs(inputs, [0, 0, 2].into_iter())), 0);
+    }
+}
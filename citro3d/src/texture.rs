@@ -1,5 +1,568 @@
+//! Textures which can be sampled by the GPU, and the parameters used to
+//! configure them.
+
+use ctru::linear::LinearAllocator;
 use ctru_sys;
 
+use crate::render::transfer;
+use crate::{Error, Result};
+
+pub mod tiling;
+
+/// A texture, stored in memory the GPU can read from.
+///
+/// Texture data is expected in the [tiled](tiling) layout used by the GPU,
+/// rather than in rows of pixels.
+#[doc(alias = "C3D_Tex")]
+pub struct Texture {
+    // Boxed so the texture's address is stable, since `citro3d` keeps a
+    // pointer to it while it is bound.
+    raw: Box<citro3d_sys::C3D_Tex>,
+    width: usize,
+    height: usize,
+    format: ColorFormat,
+    placement: Placement,
+    sub_textures: Vec<SubTexture>,
+    // The faces of a cube map, which `citro3d` keeps a pointer to.
+    _cube: Option<Box<citro3d_sys::C3D_TexCube>>,
+}
+
+impl Texture {
+    /// Create a new, uninitialized texture in linear memory.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if `width` or `height` is not a power of two
+    ///   from 8 to 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough linear memory for the
+    ///   texture.
+    #[doc(alias = "C3D_TexInit")]
+    pub fn new(width: usize, height: usize, format: ColorFormat) -> Result<Self> {
+        Self::with_placement(width, height, format, Placement::Linear)
+    }
+
+    /// Create a new, uninitialized texture stored in the given kind of memory.
+    /// See [`Placement`] for the tradeoffs.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if `width` or `height` is not a power of two
+    ///   from 8 to 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough memory of the requested
+    ///   kind for the texture.
+    #[doc(alias = "C3D_TexInitVRAM")]
+    pub fn with_placement(
+        width: usize,
+        height: usize,
+        format: ColorFormat,
+        placement: Placement,
+    ) -> Result<Self> {
+        let init = match placement {
+            Placement::Linear => citro3d_sys::C3D_TexInit,
+            Placement::Vram => citro3d_sys::C3D_TexInitVRAM,
+        };
+
+        Self::init(
+            width,
+            height,
+            format,
+            placement,
+            None,
+            |raw, width, height| unsafe {
+                init(raw, width, height, format as ctru_sys::GPU_TEXCOLOR)
+            },
+        )
+    }
+
+    /// Create a new shadow map in VRAM, which can be rendered to with
+    /// [`FragmentOperationMode::Shadow`](crate::render::effect::FragmentOperationMode::Shadow)
+    /// and then sampled to test whether fragments are in shadow.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if `width` or `height` is not a power of two
+    ///   from 8 to 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the texture.
+    #[doc(alias = "C3D_TexInitShadow")]
+    pub fn new_shadow(width: usize, height: usize) -> Result<Self> {
+        Self::init(
+            width,
+            height,
+            ColorFormat::Rgba8,
+            Placement::Vram,
+            None,
+            |raw, width, height| unsafe { citro3d_sys::C3D_TexInitShadow(raw, width, height) },
+        )
+    }
+
+    /// Create a new cube shadow map in VRAM, e.g. for shadows cast by a point
+    /// light. Each [`Face`] can be rendered to separately, see
+    /// [`Instance::texture_target_from`](crate::Instance::texture_target_from).
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if `width` or `height` is not a power of two
+    ///   from 8 to 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough VRAM for the texture.
+    #[doc(alias = "C3D_TexInitShadowCube")]
+    pub fn new_shadow_cube(width: usize, height: usize) -> Result<Self> {
+        let mut cube = Box::new(unsafe { std::mem::zeroed::<citro3d_sys::C3D_TexCube>() });
+        let cube_ptr: *mut _ = &mut *cube;
+
+        Self::init(
+            width,
+            height,
+            ColorFormat::Rgba8,
+            Placement::Vram,
+            Some(cube),
+            |raw, width, height| unsafe {
+                citro3d_sys::C3D_TexInitShadowCube(raw, cube_ptr, width, height)
+            },
+        )
+    }
+
+    fn init(
+        width: usize,
+        height: usize,
+        format: ColorFormat,
+        placement: Placement,
+        cube: Option<Box<citro3d_sys::C3D_TexCube>>,
+        init: impl FnOnce(*mut citro3d_sys::C3D_Tex, u16, u16) -> bool,
+    ) -> Result<Self> {
+        let valid_dimension = |d: usize| d.is_power_of_two() && (8..=1024).contains(&d);
+        if !valid_dimension(width) || !valid_dimension(height) {
+            return Err(Error::InvalidSize);
+        }
+
+        let mut raw = Box::new(unsafe { std::mem::zeroed::<citro3d_sys::C3D_Tex>() });
+
+        if init(&mut *raw, width.try_into()?, height.try_into()?) {
+            Ok(Self {
+                raw,
+                width,
+                height,
+                format,
+                placement,
+                sub_textures: Vec::new(),
+                _cube: cube,
+            })
+        } else {
+            Err(Error::OutOfMemory)
+        }
+    }
+
+    /// Load a texture from the `.t3x` format produced by the `tex3ds` tool.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FailedToInitialize`] if `data` is not a valid `.t3x`
+    /// 2D texture, or there wasn't enough memory to load it.
+    #[doc(alias = "Tex3DS_TextureImport")]
+    #[doc(alias = "Tex3DS_TextureFree")]
+    pub fn from_t3x(data: &[u8]) -> Result<Self> {
+        let mut raw = Box::new(unsafe { std::mem::zeroed::<citro3d_sys::C3D_Tex>() });
+
+        let t3x = unsafe {
+            citro3d_sys::Tex3DS_TextureImport(
+                data.as_ptr().cast(),
+                data.len(),
+                &mut *raw,
+                std::ptr::null_mut(),
+                false,
+            )
+        };
+        if t3x.is_null() {
+            return Err(Error::FailedToInitialize);
+        }
+
+        let sub_textures = unsafe {
+            let count = citro3d_sys::Tex3DS_GetNumSubTextures(t3x);
+            let sub_textures = (0..count)
+                .map(|i| SubTexture::from_raw(&*citro3d_sys::Tex3DS_GetSubTexture(t3x, i)))
+                .collect();
+            citro3d_sys::Tex3DS_TextureFree(t3x);
+            sub_textures
+        };
+
+        let (width, height) = unsafe {
+            let dim = raw.__bindgen_anon_2.__bindgen_anon_1;
+            (dim.width.into(), dim.height.into())
+        };
+        let Ok(format) = ColorFormat::try_from(raw.fmt() as u8) else {
+            unsafe { citro3d_sys::C3D_TexDelete(&mut *raw) };
+            return Err(Error::FailedToInitialize);
+        };
+
+        Ok(Self {
+            raw,
+            width,
+            height,
+            format,
+            placement: Placement::Linear,
+            sub_textures,
+            _cube: None,
+        })
+    }
+
+    /// The sub-textures (e.g. sprites in an atlas) described by the `.t3x`
+    /// file the texture was [loaded from](Self::from_t3x). Empty for other textures.
+    #[doc(alias = "Tex3DS_GetSubTexture")]
+    pub fn sub_textures(&self) -> &[SubTexture] {
+        &self.sub_textures
+    }
+
+    /// The width of the texture, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the texture, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixel format of the texture.
+    pub fn format(&self) -> ColorFormat {
+        self.format
+    }
+
+    /// The kind of texture, e.g. a regular 2D texture or a shadow map.
+    pub fn mode(&self) -> Mode {
+        // Stored as GPU_TEXTURE_MODE in bits 28-30 of the texture parameters.
+        Mode::try_from(((self.raw.param >> 28) & 0b111) as u8).unwrap()
+    }
+
+    /// Whether the texture is a cube map with six faces.
+    pub fn is_cube(&self) -> bool {
+        matches!(self.mode(), Mode::CubeMap | Mode::ShadowCube)
+    }
+
+    /// The kind of memory the texture is stored in.
+    pub fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    /// The size of the texture's image data, in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.format.data_size(self.width, self.height)
+    }
+
+    /// Upload new image data for the texture. The data must already be tiled.
+    ///
+    /// Textures in [VRAM](Placement::Vram) are written by the GPU, which can
+    /// only read from linear memory, so the data is first copied there if needed.
+    ///
+    /// For [compressed](ColorFormat::is_compressed) formats, the data is made of
+    /// 4x4 pixel blocks, in the same tiled order and with each 64-bit block
+    /// stored little-endian (as output by `tex3ds`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSize`] if the length of `data` doesn't match
+    /// [`Texture::size_bytes`].
+    #[doc(alias = "C3D_TexUpload")]
+    pub fn upload(&mut self, data: &[u8]) -> Result<()> {
+        if data.len() != self.size_bytes() {
+            return Err(Error::InvalidSize);
+        }
+
+        if self.placement == Placement::Vram && !transfer::is_gpu_accessible(data) {
+            let mut staging = Vec::with_capacity_in(data.len(), LinearAllocator);
+            staging.extend_from_slice(data);
+            // The copy into VRAM finishes before this returns, so the staging
+            // buffer can be freed right after.
+            unsafe { citro3d_sys::C3D_TexUpload(&mut *self.raw, staging.as_ptr().cast()) };
+        } else {
+            unsafe { citro3d_sys::C3D_TexUpload(&mut *self.raw, data.as_ptr().cast()) };
+        }
+        Ok(())
+    }
+
+    /// Update a `width` by `height` region of the texture, with its top-left
+    /// corner at `(x, y)`, e.g. to add an image to a dynamic atlas or stream
+    /// video frames. Unlike [`upload`](Self::upload), `data` holds linear rows
+    /// of pixels as described in [`tiling`], and is tiled while it is copied.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if the region is not within the texture, the
+    ///   length of `data` doesn't match the region, or the texture's format is
+    ///   not supported by [`tiling`].
+    /// * [`Error::InvalidMemoryLocation`] if the texture is in
+    ///   [VRAM](Placement::Vram), which can only be written by the GPU.
+    #[doc(alias = "C3D_TexFlush")]
+    pub fn upload_region(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        data: &[u8],
+    ) -> Result<()> {
+        if self.placement == Placement::Vram {
+            return Err(Error::InvalidMemoryLocation);
+        }
+
+        let pixel_size = tiling::pixel_size(self.format)?;
+        let in_bounds = |start: usize, len: usize, max: usize| {
+            start.checked_add(len).is_some_and(|end| end <= max)
+        };
+        if !in_bounds(x, width, self.width)
+            || !in_bounds(y, height, self.height)
+            || data.len() != width * height * pixel_size
+        {
+            return Err(Error::InvalidSize);
+        }
+
+        let tiled = unsafe {
+            std::slice::from_raw_parts_mut(
+                self.raw.__bindgen_anon_1.data.cast::<u8>(),
+                self.size_bytes(),
+            )
+        };
+        let reverse = tiling::reverses_components(self.format);
+
+        for row in 0..height {
+            for column in 0..width {
+                let src = &data[(row * width + column) * pixel_size..][..pixel_size];
+                let index = tiling::tiled_index(x + column, y + row, self.width, self.height);
+                tiling::copy_pixel(src, &mut tiled[index * pixel_size..][..pixel_size], reverse);
+            }
+        }
+
+        unsafe { citro3d_sys::C3D_TexFlush(&mut *self.raw) };
+        Ok(())
+    }
+
+    /// Upload linear image data with the GPU, which converts it to the tiled
+    /// layout and the texture's format. This is faster than tiling the data
+    /// with the CPU, but only supports some formats.
+    ///
+    /// `data` holds rows of pixels in the given `format`, from top to bottom.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if the texture is a cube map, its format is
+    ///   not one of [`Rgba8`](ColorFormat::Rgba8), [`Rgb8`](ColorFormat::Rgb8),
+    ///   [`Rgba5551`](ColorFormat::Rgba5551), [`Rgb565`](ColorFormat::Rgb565)
+    ///   or [`Rgba4`](ColorFormat::Rgba4), or `data` is too small.
+    /// * [`Error::InvalidMemoryLocation`] if `data` is not in linear memory.
+    /// * [`Error::Ctru`] if the data couldn't be synchronized with the CPU's cache.
+    #[doc(alias = "GX_DisplayTransfer")]
+    pub fn upload_linear(&mut self, data: &[u8], format: transfer::Format) -> Result<()> {
+        let out_format = match self.format {
+            ColorFormat::Rgba8 => transfer::Format::RGBA8,
+            ColorFormat::Rgb8 => transfer::Format::RGB8,
+            ColorFormat::Rgba5551 => transfer::Format::RGB5A1,
+            ColorFormat::Rgb565 => transfer::Format::RGB565,
+            ColorFormat::Rgba4 => transfer::Format::RGBA4,
+            _ => return Err(Error::InvalidSize),
+        };
+        if self.is_cube() {
+            return Err(Error::InvalidSize);
+        }
+
+        let flags = transfer::Flags::default()
+            .in_format(format)
+            .out_format(out_format)
+            .out_tiled(true)
+            // Tiled images start from the bottom row.
+            .flip_vertical(true);
+
+        let tiled = unsafe {
+            std::slice::from_raw_parts_mut(
+                self.raw.__bindgen_anon_1.data.cast::<u8>(),
+                self.size_bytes(),
+            )
+        };
+
+        transfer::display_transfer(data, tiled, self.width, self.height, flags)
+    }
+
+    /// Set the filters used when the texture is magnified and minified.
+    /// Textures use [`Filter::Nearest`] for both by default.
+    #[doc(alias = "C3D_TexSetFilter")]
+    pub fn set_filter(&mut self, mag: Filter, min: Filter) {
+        unsafe {
+            citro3d_sys::C3D_TexSetFilter(
+                &mut *self.raw,
+                mag as ctru_sys::GPU_TEXTURE_FILTER_PARAM,
+                min as ctru_sys::GPU_TEXTURE_FILTER_PARAM,
+            );
+        }
+    }
+
+    /// Set the wrap modes used for texture coordinates outside `0.0..=1.0`,
+    /// along the S (horizontal) and T (vertical) axes. Textures use
+    /// [`Wrap::ClampToEdge`] for both by default.
+    #[doc(alias = "C3D_TexSetWrap")]
+    pub fn set_wrap(&mut self, s: Wrap, t: Wrap) {
+        unsafe {
+            citro3d_sys::C3D_TexSetWrap(
+                &mut *self.raw,
+                s as ctru_sys::GPU_TEXTURE_WRAP_PARAM,
+                t as ctru_sys::GPU_TEXTURE_WRAP_PARAM,
+            );
+        }
+    }
+
+    /// Set the wrap mode along the S (horizontal) axis, keeping the current
+    /// mode along the T axis.
+    pub fn set_wrap_s(&mut self, s: Wrap) {
+        self.set_wrap(s, self.wrap_t());
+    }
+
+    /// Set the wrap mode along the T (vertical) axis, keeping the current
+    /// mode along the S axis.
+    pub fn set_wrap_t(&mut self, t: Wrap) {
+        self.set_wrap(self.wrap_s(), t);
+    }
+
+    /// The current wrap mode along the S (horizontal) axis.
+    pub fn wrap_s(&self) -> Wrap {
+        // Stored as GPU_TEXTURE_WRAP_S in bits 12-13 of the texture parameters.
+        Wrap::try_from(((self.raw.param >> 12) & 0b11) as u8).unwrap()
+    }
+
+    /// The current wrap mode along the T (vertical) axis.
+    pub fn wrap_t(&self) -> Wrap {
+        // Stored as GPU_TEXTURE_WRAP_T in bits 8-9 of the texture parameters.
+        Wrap::try_from(((self.raw.param >> 8) & 0b11) as u8).unwrap()
+    }
+
+    /// Set the color sampled outside the texture when using
+    /// [`Wrap::ClampToBorder`], as `0xRRGGBBAA`.
+    pub fn set_border_color(&mut self, rgba_color: u32) {
+        // The GPU stores the border color with red in the lowest byte.
+        self.raw.border = rgba_color.swap_bytes();
+    }
+
+    /// The color sampled outside the texture when using [`Wrap::ClampToBorder`].
+    pub fn border_color(&self) -> u32 {
+        self.raw.border.swap_bytes()
+    }
+
+    pub(crate) fn as_raw(&self) -> *mut citro3d_sys::C3D_Tex {
+        std::ptr::from_ref(&*self.raw).cast_mut()
+    }
+}
+
+#[cfg(feature = "image")]
+#[doc(cfg(feature = "image"))]
+impl Texture {
+    /// Create a texture from an image, e.g. a PNG decoded with the `image`
+    /// crate. The image is converted to the closest supported format, tiled,
+    /// and uploaded.
+    ///
+    /// RGBA, RGB, luminance-alpha and luminance images with 8-bit channels are
+    /// uploaded as-is, and other images are converted to RGBA first.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidSize`] if the image's width or height is not a power
+    ///   of two from 8 to 1024.
+    /// * [`Error::OutOfMemory`] if there is not enough linear memory for the
+    ///   texture.
+    pub fn from_image(image: &image::DynamicImage) -> Result<Self> {
+        use image::DynamicImage;
+
+        let converted;
+        let (format, data): (ColorFormat, &[u8]) = match image {
+            DynamicImage::ImageRgba8(image) => (ColorFormat::Rgba8, image.as_raw()),
+            DynamicImage::ImageRgb8(image) => (ColorFormat::Rgb8, image.as_raw()),
+            DynamicImage::ImageLumaA8(image) => (ColorFormat::La8, image.as_raw()),
+            DynamicImage::ImageLuma8(image) => (ColorFormat::L8, image.as_raw()),
+            image => {
+                converted = image.to_rgba8();
+                (ColorFormat::Rgba8, converted.as_raw())
+            }
+        };
+
+        let width = image.width().try_into()?;
+        let height = image.height().try_into()?;
+
+        let mut texture = Self::new(width, height, format)?;
+        let mut tiled = vec![0; texture.size_bytes()];
+        tiling::tile(data, &mut tiled, width, height, format)?;
+        texture.upload(&tiled)?;
+
+        Ok(texture)
+    }
+}
+
+impl Drop for Texture {
+    #[doc(alias = "C3D_TexDelete")]
+    fn drop(&mut self) {
+        unsafe { citro3d_sys::C3D_TexDelete(&mut *self.raw) };
+    }
+}
+
+/// The kind of memory a [`Texture`] is stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placement {
+    /// The linear heap, which is shared with the CPU and much larger than
+    /// VRAM. Texture data can be written to directly.
+    #[default]
+    Linear,
+    /// Dedicated video memory, which is faster for the GPU to sample from but
+    /// only 6 MiB in total, shared with framebuffers and depth buffers.
+    /// Texture data is copied in with a GPU transfer.
+    Vram,
+}
+
+impl Placement {
+    /// The number of bytes currently free in this kind of memory.
+    ///
+    /// Memory can be fragmented, so it may not be possible to allocate a
+    /// single texture of this size.
+    #[doc(alias = "linearSpaceFree")]
+    #[doc(alias = "vramSpaceFree")]
+    pub fn space_free(self) -> usize {
+        let free = match self {
+            Self::Linear => unsafe { ctru_sys::linearSpaceFree() },
+            Self::Vram => unsafe { ctru_sys::vramSpaceFree() },
+        };
+        free as usize
+    }
+}
+
+/// A region of a [`Texture`] loaded from a `.t3x` file, e.g. one image in an atlas.
+#[doc(alias = "Tex3DS_SubTexture")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubTexture {
+    /// The width of the image, in pixels.
+    pub width: u16,
+    /// The height of the image, in pixels.
+    pub height: u16,
+    /// The texture coordinate of the image's left edge.
+    pub left: f32,
+    /// The texture coordinate of the image's top edge.
+    pub top: f32,
+    /// The texture coordinate of the image's right edge.
+    pub right: f32,
+    /// The texture coordinate of the image's bottom edge.
+    pub bottom: f32,
+}
+
+impl SubTexture {
+    fn from_raw(raw: &citro3d_sys::Tex3DS_SubTexture) -> Self {
+        Self {
+            width: raw.width,
+            height: raw.height,
+            left: raw.left,
+            top: raw.top,
+            right: raw.right,
+            bottom: raw.bottom,
+        }
+    }
+
+    /// Whether the image was rotated by 90° to pack it into the texture.
+    #[doc(alias = "Tex3DS_SubTextureRotated")]
+    pub fn is_rotated(&self) -> bool {
+        self.top < self.bottom
+    }
+}
+
 /// Texture filters.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -109,6 +672,14 @@ pub enum Unit {
     Unit2 = ctru_sys::GPU_TEXUNIT2,
 }
 
+impl Unit {
+    /// The index of the unit (`0..=2`). The variants' values are bit flags
+    /// rather than indices, since that's how the GPU selects units.
+    pub(crate) fn index(self) -> usize {
+        (self as u8).trailing_zeros() as usize
+    }
+}
+
 impl TryFrom<u8> for Unit {
     type Error = String;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -182,6 +753,39 @@ pub enum ColorFormat {
     Etc1A4 = ctru_sys::GPU_ETC1A4,
 }
 
+impl ColorFormat {
+    /// Whether this is a block-compressed format (i.e. ETC1).
+    pub fn is_compressed(self) -> bool {
+        matches!(self, Self::Etc1 | Self::Etc1A4)
+    }
+
+    /// The size in bytes of a `width` by `height` image in this format.
+    ///
+    /// Compressed formats are stored in blocks of 4x4 pixels, so their size
+    /// is rounded up to a whole number of blocks.
+    pub fn data_size(self, width: usize, height: usize) -> usize {
+        let (width, height) = if self.is_compressed() {
+            (width.next_multiple_of(4), height.next_multiple_of(4))
+        } else {
+            (width, height)
+        };
+
+        width * height * self.bits_per_pixel() / 8
+    }
+
+    /// The number of bits used to store a single pixel in this format. For
+    /// compressed formats, this is the average over a block.
+    pub fn bits_per_pixel(self) -> usize {
+        match self {
+            Self::Rgba8 => 32,
+            Self::Rgb8 => 24,
+            Self::Rgba5551 | Self::Rgb565 | Self::Rgba4 | Self::La8 | Self::Hilo8 => 16,
+            Self::L8 | Self::A8 | Self::La4 | Self::Etc1A4 => 8,
+            Self::L4 | Self::A4 | Self::Etc1 => 4,
+        }
+    }
+}
+
 impl TryFrom<u8> for ColorFormat {
     type Error = String;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -668,3 +1272,19 @@ impl TryFrom<u8> for Scale {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_size() {
+        assert_eq!(ColorFormat::Rgba8.data_size(8, 8), 256);
+        assert_eq!(ColorFormat::Rgb565.data_size(16, 8), 256);
+        assert_eq!(ColorFormat::L4.data_size(8, 8), 32);
+        // A single 4x4 block is 8 bytes, plus another 8 for 4-bit alpha.
+        assert_eq!(ColorFormat::Etc1.data_size(4, 4), 8);
+        assert_eq!(ColorFormat::Etc1A4.data_size(4, 4), 16);
+        assert_eq!(ColorFormat::Etc1.data_size(2, 6), 16);
+    }
+}
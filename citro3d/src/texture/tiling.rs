@@ -0,0 +1,171 @@
+//! Conversion between linear image data and the tiled layout textures use on
+//! the GPU, e.g. to generate textures procedurally at runtime.
+//!
+//! The GPU stores textures in 8x8 pixel tiles, with the pixels of each tile in
+//! Morton (Z-order) order. Tiles start from the bottom of the image (`v = 0`),
+//! while linear data uses the usual top-to-bottom row order, so these
+//! functions also flip the image vertically.
+//!
+//! The components of each pixel are stored in reverse order on the GPU
+//! (e.g. ABGR for [`Rgba8`](ColorFormat::Rgba8)). For formats with one byte per
+//! component, linear data is expected in the usual order (e.g. RGBA) and the
+//! bytes are reversed during conversion. Formats packed into 16 bits (e.g.
+//! [`Rgb565`](ColorFormat::Rgb565)) are expected as little-endian `u16`s.
+
+use super::ColorFormat;
+use crate::{Error, Result};
+
+/// Convert a `width` by `height` image from `linear` data into the tiled
+/// layout used by the GPU, writing it to `tiled`.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidSize`] if:
+/// * `width` or `height` is not a multiple of 8.
+/// * `format` is compressed or uses less than 8 bits per pixel.
+/// * `linear` or `tiled` is not the right size for the image.
+pub fn tile(
+    linear: &[u8],
+    tiled: &mut [u8],
+    width: usize,
+    height: usize,
+    format: ColorFormat,
+) -> Result<()> {
+    let pixel_size = validate(linear.len(), tiled.len(), width, height, format)?;
+    let reverse = reverses_components(format);
+
+    for (y, x, offset) in tiled_offsets(width, height) {
+        let src = &linear[(y * width + x) * pixel_size..][..pixel_size];
+        let dst = &mut tiled[offset * pixel_size..][..pixel_size];
+        copy_pixel(src, dst, reverse);
+    }
+
+    Ok(())
+}
+
+/// Convert a `width` by `height` image from the `tiled` layout used by the GPU
+/// into `linear` data. This is the inverse of [`tile`].
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidSize`] under the same conditions as [`tile`].
+pub fn untile(
+    tiled: &[u8],
+    linear: &mut [u8],
+    width: usize,
+    height: usize,
+    format: ColorFormat,
+) -> Result<()> {
+    let pixel_size = validate(linear.len(), tiled.len(), width, height, format)?;
+    let reverse = reverses_components(format);
+
+    for (y, x, offset) in tiled_offsets(width, height) {
+        let src = &tiled[offset * pixel_size..][..pixel_size];
+        let dst = &mut linear[(y * width + x) * pixel_size..][..pixel_size];
+        copy_pixel(src, dst, reverse);
+    }
+
+    Ok(())
+}
+
+/// The index of the pixel at `(x, y)` (counting from the top-left) in a tiled
+/// `width` by `height` image.
+pub fn tiled_index(x: usize, y: usize, width: usize, height: usize) -> usize {
+    let y = height - 1 - y;
+    let tile = (y / 8) * (width / 8) + x / 8;
+    tile * 64 + morton(x % 8, y % 8)
+}
+
+fn morton(x: usize, y: usize) -> usize {
+    (x & 1) | ((y & 1) << 1) | ((x & 2) << 1) | ((y & 2) << 2) | ((x & 4) << 2) | ((y & 4) << 3)
+}
+
+fn tiled_offsets(width: usize, height: usize) -> impl Iterator<Item = (usize, usize, usize)> {
+    (0..height).flat_map(move |y| (0..width).map(move |x| (y, x, tiled_index(x, y, width, height))))
+}
+
+fn validate(
+    linear_len: usize,
+    tiled_len: usize,
+    width: usize,
+    height: usize,
+    format: ColorFormat,
+) -> Result<usize> {
+    let pixel_size = pixel_size(format)?;
+    if !width.is_multiple_of(8) || !height.is_multiple_of(8) {
+        return Err(Error::InvalidSize);
+    }
+
+    let size = format.data_size(width, height);
+    if linear_len != size || tiled_len != size {
+        return Err(Error::InvalidSize);
+    }
+
+    Ok(pixel_size)
+}
+
+/// The size of a pixel in bytes, for formats which can be converted.
+pub(super) fn pixel_size(format: ColorFormat) -> Result<usize> {
+    let bits = format.bits_per_pixel();
+    if format.is_compressed() || bits < 8 {
+        return Err(Error::InvalidSize);
+    }
+    Ok(bits / 8)
+}
+
+pub(super) fn reverses_components(format: ColorFormat) -> bool {
+    matches!(
+        format,
+        ColorFormat::Rgba8 | ColorFormat::Rgb8 | ColorFormat::La8 | ColorFormat::Hilo8
+    )
+}
+
+pub(super) fn copy_pixel(src: &[u8], dst: &mut [u8], reverse: bool) {
+    dst.copy_from_slice(src);
+    if reverse {
+        dst.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiled_index_layout() {
+        // The bottom-left pixel comes first, followed by its Morton neighbours.
+        assert_eq!(tiled_index(0, 15, 16, 16), 0);
+        assert_eq!(tiled_index(1, 15, 16, 16), 1);
+        assert_eq!(tiled_index(0, 14, 16, 16), 2);
+        assert_eq!(tiled_index(7, 8, 16, 16), 63);
+        // Then the tile to its right.
+        assert_eq!(tiled_index(8, 15, 16, 16), 64);
+        // The top-right pixel is last.
+        assert_eq!(tiled_index(15, 0, 16, 16), 255);
+    }
+
+    #[test]
+    fn round_trip() {
+        let (width, height) = (16, 8);
+        let linear: Vec<u8> = (0..width * height * 4).map(|i| i as u8).collect();
+        let mut tiled = vec![0; linear.len()];
+        let mut back = vec![0; linear.len()];
+
+        tile(&linear, &mut tiled, width, height, ColorFormat::Rgba8).unwrap();
+        assert_ne!(linear, tiled);
+
+        // RGBA is stored as ABGR.
+        let first = tiled_index(0, 0, width, height) * 4;
+        assert_eq!(tiled[first..first + 4], [3, 2, 1, 0]);
+
+        untile(&tiled, &mut back, width, height, ColorFormat::Rgba8).unwrap();
+        assert_eq!(linear, back);
+    }
+
+    #[test]
+    fn rejects_unsupported() {
+        let mut tiled = [0; 32];
+        assert!(tile(&[0; 32], &mut tiled, 8, 8, ColorFormat::L4).is_err());
+        assert!(tile(&[0; 32], &mut tiled, 4, 8, ColorFormat::L8).is_err());
+    }
+}
@@ -16,12 +16,85 @@ impl From<u8> for Index {
     }
 }
 
+impl Index {
+    /// The index of the integer uniform register `i0` to `i3`, for binding a
+    /// [`Uniform::Int`]. Returns [`None`] if `register` is greater than 3.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use citro3d::uniform;
+    /// # use citro3d::math::IVec;
+    /// #
+    /// # let mut instance = citro3d::Instance::new().unwrap();
+    /// // Loop 8 times, with the loop counter starting at 0 and incrementing by 1.
+    /// let loop_params = IVec::new(7, 0, 1, 0);
+    /// instance.render_frame_with(|frame| {
+    ///     frame.bind_vertex_uniform(uniform::Index::int(0).unwrap(), loop_params);
+    /// });
+    /// ```
+    pub fn int(register: u8) -> Option<Self> {
+        let index = Self(INT_INDEXES.start.0.checked_add(register)?);
+        INT_INDEXES.contains(&index).then_some(index)
+    }
+}
+
 impl From<Index> for i32 {
     fn from(value: Index) -> Self {
         value.0.into()
     }
 }
 
+/// The indexes of the float uniform registers.
+const FLOAT_INDEXES: Range<Index> = Index(0)..Index(0x60);
+
+/// The indexes of the integer uniform registers.
+const INT_INDEXES: Range<Index> = Index(0x60)..Index(0x64);
+
+/// The indexes of the bool uniform registers. The gap after the integer
+/// registers is intentional.
+const BOOL_INDEXES: Range<Index> = Index(0x68)..Index(0x79);
+
+/// The kind of registers a uniform is stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Float vector registers (`.fvec`), see [`Uniform::Float`].
+    Float,
+    /// Integer vector registers (`.ivec`), see [`Uniform::Int`].
+    Int,
+    /// Bool registers (`.bool`), see [`Uniform::Bool`].
+    Bool,
+}
+
+impl Kind {
+    /// The kind of register at `index`, if any.
+    pub(crate) fn of(index: Index) -> Option<Self> {
+        if FLOAT_INDEXES.contains(&index) {
+            Some(Self::Float)
+        } else if INT_INDEXES.contains(&index) {
+            Some(Self::Int)
+        } else if BOOL_INDEXES.contains(&index) {
+            Some(Self::Bool)
+        } else {
+            None
+        }
+    }
+}
+
+/// Information about a uniform declared by a shader. See
+/// [`shader::Program::uniform_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Info {
+    /// The index of the uniform's first register.
+    pub index: Index,
+    /// The number of registers the uniform takes up, e.g. 4 for a matrix
+    /// declared as `.fvec projection[4]`.
+    pub len: usize,
+    /// The kind of registers the uniform is stored in.
+    pub kind: Kind,
+}
+
 /// A uniform which may be bound as input to a shader program
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -41,7 +114,9 @@ pub enum Uniform {
     /// Bool uniform (`.bool name`)
     #[doc(alias = "C3D_BoolUnifSet")]
     Bool(bool),
-    /// Integer uniform (`.ivec name`)
+    /// Integer uniform (`.ivec name`), e.g. the parameters of a `loop`
+    /// instruction. These can only be bound to the integer registers; see
+    /// [`Index::int`].
     #[doc(alias = "C3D_IVUnifSet")]
     Int(IVec),
 }
@@ -51,12 +126,9 @@ impl Uniform {
         // these indexes are from the uniform table in the shader see: https://www.3dbrew.org/wiki/SHBIN#Uniform_Table_Entry
         // the input registers then are excluded by libctru, see: https://github.com/devkitPro/libctru/blob/0da8705527f03b4b08ff7fee4dd1b7f28df37905/libctru/source/gpu/shbin.c#L93
         match self {
-            Self::Float(_) | Self::Float2(_) | Self::Float3(_) | Self::Float4(_) => {
-                Index(0)..Index(0x60)
-            }
-            Self::Int(_) => Index(0x60)..Index(0x64),
-            // this gap is intentional
-            Self::Bool(_) => Index(0x68)..Index(0x79),
+            Self::Float(_) | Self::Float2(_) | Self::Float3(_) | Self::Float4(_) => FLOAT_INDEXES,
+            Self::Int(_) => INT_INDEXES,
+            Self::Bool(_) => BOOL_INDEXES,
         }
     }
     /// Get length of uniform, i.e. how many registers it will write to
@@ -73,36 +145,17 @@ impl Uniform {
 
     /// Bind a uniform
     ///
-    /// Note: `_instance` is here to ensure unique access to the global uniform buffers
+    /// Note: `instance` is here to ensure unique access to the global uniform buffers
     /// otherwise we could race and/or violate aliasing
-    pub(crate) fn bind(self, _instance: &mut Instance, ty: shader::Type, index: Index) {
-        assert!(
-            self.index_range().contains(&index),
-            "tried to bind uniform to an invalid index (index: {:?}, valid range: {:?})",
-            index,
-            self.index_range(),
-        );
-        assert!(
-            self.index_range().end.0 as usize >= self.len() + index.0 as usize,
-            "tried to bind a uniform that would overflow the uniform buffer. index was {:?}, size was {} max is {:?}",
-            index,
-            self.len(),
-            self.index_range().end
-        );
-        let set_fvs = |fs: &[FVec4]| {
-            for (off, f) in fs.iter().enumerate() {
-                unsafe {
-                    citro3d_sys::C3D_FVUnifSet(
-                        ty.into(),
-                        (index.0 as usize + off) as i32,
-                        f.x(),
-                        f.y(),
-                        f.z(),
-                        f.w(),
-                    );
-                }
-            }
-        };
+    pub(crate) fn bind(
+        self,
+        instance: &mut Instance,
+        ty: shader::Type,
+        index: Index,
+    ) -> crate::Result<()> {
+        check_registers(instance, ty, index, self.len(), self.index_range())?;
+
+        let set_fvs = |fs: &[FVec4]| set_float_registers(ty, index, fs.iter().copied());
         match self {
             Self::Bool(b) => unsafe {
                 citro3d_sys::C3D_BoolUnifSet(ty.into(), index.into(), b);
@@ -126,6 +179,171 @@ impl Uniform {
                 set_fvs(&m.rows_wzyx());
             }
         }
+
+        Ok(())
+    }
+}
+
+/// A type which can be bound as an array to consecutive float uniform
+/// registers, e.g. a palette of bone matrices for skeletal animation (`.fvec
+/// bones[4 * N]`). See [`Frame::bind_vertex_uniforms`](crate::render::Frame::bind_vertex_uniforms).
+pub trait ArrayElement: crate::private::Sealed {
+    /// The number of registers each element takes up.
+    const REGISTERS: usize;
+
+    /// The element's registers, in order.
+    fn registers(&self) -> impl Iterator<Item = FVec4>;
+}
+
+impl crate::private::Sealed for FVec4 {}
+
+impl ArrayElement for FVec4 {
+    const REGISTERS: usize = 1;
+
+    fn registers(&self) -> impl Iterator<Item = FVec4> {
+        std::iter::once(*self)
+    }
+}
+
+impl crate::private::Sealed for Matrix4 {}
+
+impl ArrayElement for Matrix4 {
+    const REGISTERS: usize = 4;
+
+    fn registers(&self) -> impl Iterator<Item = FVec4> {
+        self.rows_wzyx().into_iter()
+    }
+}
+
+/// A matrix which is bound as its first 3 rows only (`.fvec name[3]`), for
+/// affine transforms whose last row is always `(0, 0, 0, 1)`. This saves a
+/// uniform register per matrix, e.g. for each bone of a skinning palette, but
+/// the shader has to treat the `w` component of the result as `1.0`.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use citro3d::uniform::{self, Matrix3x4};
+/// # use citro3d::math::Matrix4;
+/// #
+/// # let mut instance = citro3d::Instance::new().unwrap();
+/// let bones = [Matrix3x4(Matrix4::identity()); 24];
+/// instance.render_frame_with(|frame| {
+///     frame.bind_vertex_uniforms(uniform::Index::from(16), &bones);
+/// });
+/// ```
+#[doc(alias = "C3D_FVUnifMtx3x4")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3x4(pub Matrix4);
+
+impl crate::private::Sealed for Matrix3x4 {}
+
+impl ArrayElement for Matrix3x4 {
+    const REGISTERS: usize = 3;
+
+    fn registers(&self) -> impl Iterator<Item = FVec4> {
+        self.0.registers().take(3)
+    }
+}
+
+impl From<Matrix3x4> for Uniform {
+    fn from(value: Matrix3x4) -> Self {
+        let [x, y, z, _] = value.0.rows_wzyx();
+        Self::Float3([x, y, z])
+    }
+}
+
+/// Bind an array of elements to consecutive float registers, starting at
+/// `index`.
+///
+/// Note: `instance` is here to ensure unique access to the global uniform buffers,
+/// as for [`Uniform::bind`].
+pub(crate) fn bind_array<T: ArrayElement>(
+    instance: &mut Instance,
+    ty: shader::Type,
+    index: Index,
+    elements: &[T],
+) -> crate::Result<()> {
+    let len = elements.len() * T::REGISTERS;
+    check_registers(instance, ty, index, len, FLOAT_INDEXES)?;
+
+    set_float_registers(ty, index, elements.iter().flat_map(T::registers));
+    Ok(())
+}
+
+/// Check that `len` registers starting at `index` are all in `range`, and
+/// aren't reserved by the bound geometry shader (see [`reserved_registers`]).
+fn check_registers(
+    instance: &Instance,
+    ty: shader::Type,
+    index: Index,
+    len: usize,
+    range: Range<Index>,
+) -> crate::Result<()> {
+    let registers = usize::from(index.0)..usize::from(index.0) + len;
+    let in_range = range.contains(&index) && registers.end <= usize::from(range.end.0);
+    let reserved = reserved_registers(instance, ty)
+        .is_some_and(|reserved| registers.start < reserved.end && reserved.start < registers.end);
+
+    if in_range && !reserved {
+        Ok(())
+    } else {
+        Err(crate::Error::InvalidUniformIndex {
+            index: index.0,
+            len,
+        })
+    }
+}
+
+/// The float registers which the bound program's shader of type `ty` doesn't
+/// allow uniforms in.
+///
+/// Vertex and geometry shaders both have 96 float registers, but a geometry
+/// shader in fixed primitive mode (`GSH_FIXED_PRIM`) receives its input
+/// vertices in float registers starting at the one given by its `.gsh`
+/// directive, which would silently overwrite any uniforms bound there.
+fn reserved_registers(instance: &Instance, ty: shader::Type) -> Option<Range<usize>> {
+    if !matches!(ty, shader::Type::Geometry) {
+        return None;
+    }
+
+    // SAFETY: the instance keeps the bound program alive, and it is only
+    // mutated through `&mut Program`, which can't exist while it is shared.
+    let program = unsafe { &*instance.program()?.as_raw() };
+    if program.geometryShader.is_null() {
+        return None;
+    }
+
+    let dvle = unsafe { &*(*program.geometryShader).dvle };
+    if dvle.gshMode != ctru_sys::GSH_FIXED_PRIM {
+        return None;
+    }
+
+    let start = usize::from(dvle.gshFixedVtxStart);
+    let len = usize::from(dvle.gshFixedVtxNum) * usize::from(program.geoShaderInputStride);
+    Some(start..start + len)
+}
+
+/// Set consecutive float registers starting at `index`. The caller must check
+/// that the registers are valid with [`check_registers`].
+///
+/// `citro3d` keeps a copy of every register and uploads the ones which were
+/// set to the GPU before the next draw call. Registers which already hold the
+/// same value are skipped, so binding e.g. the same projection matrix for
+/// every draw call doesn't upload it again each time.
+fn set_float_registers(ty: shader::Type, index: Index, registers: impl Iterator<Item = FVec4>) {
+    let ty_index = usize::from(u8::from(ty));
+    for (off, f) in registers.enumerate() {
+        let id = index.0 as usize + off;
+        let current = unsafe { (*(&raw const citro3d_sys::C3D_FVUnif))[ty_index][id] };
+        if FVec4::from_raw(current) == f {
+            continue;
+        }
+
+        unsafe {
+            citro3d_sys::C3D_FVUnifSet(ty.into(), id as i32, f.x(), f.y(), f.z(), f.w());
+        }
     }
 }
 
@@ -150,6 +368,27 @@ impl From<FVec4> for Uniform {
         Self::Float(value)
     }
 }
+impl From<f32> for Uniform {
+    /// Bind a scalar as the `x` component of a float uniform, with the other
+    /// components set to `0.0`.
+    fn from(value: f32) -> Self {
+        Self::Float(FVec4::new(value, 0.0, 0.0, 0.0))
+    }
+}
+impl From<[f32; 2]> for Uniform {
+    /// Bind a 2-vector as the `x` and `y` components of a float uniform, with
+    /// the other components set to `0.0`.
+    fn from([x, y]: [f32; 2]) -> Self {
+        Self::Float(FVec4::new(x, y, 0.0, 0.0))
+    }
+}
+impl From<[f32; 3]> for Uniform {
+    /// Bind a 3-vector as the `x`, `y` and `z` components of a float uniform,
+    /// with `w` set to `0.0`.
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Self::Float(FVec4::new(x, y, z, 0.0))
+    }
+}
 impl From<IVec> for Uniform {
     fn from(value: IVec) -> Self {
         Self::Int(value)
@@ -179,3 +418,99 @@ impl From<glam::Mat4> for Uniform {
         Self::Float4(value.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn int_index() {
+        let int = Uniform::Int(IVec::new(1, 2, 3, 4));
+        assert_eq!(Index::int(0), Some(Index(0x60)));
+        assert!(int.index_range().contains(&Index::int(3).unwrap()));
+        assert_eq!(Index::int(4), None);
+        assert_eq!(Index::int(u8::MAX), None);
+        assert!(!int.index_range().contains(&Index(0)));
+    }
+
+    #[test]
+    fn skip_redundant_binds() {
+        let mut instance = Instance::new().unwrap();
+        let index = Index::from(0);
+        let dirty = || unsafe { (*(&raw const citro3d_sys::C3D_FVUnifDirty))[0][0] };
+
+        instance.render_frame_with(|frame| {
+            frame.bind_vertex_uniform(index, FVec4::splat(1.0));
+
+            // Pretend the register was uploaded.
+            unsafe { (*(&raw mut citro3d_sys::C3D_FVUnifDirty))[0][0] = false };
+            frame.bind_vertex_uniform(index, FVec4::splat(1.0));
+            assert!(!dirty());
+
+            frame.bind_vertex_uniform(index, FVec4::splat(2.0));
+            assert!(dirty());
+        });
+    }
+
+    #[test]
+    fn small_floats() {
+        assert_eq!(
+            Uniform::from(0.5),
+            Uniform::Float(FVec4::new(0.5, 0.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            Uniform::from([400.0, 240.0]),
+            Uniform::Float(FVec4::new(400.0, 240.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            Uniform::from([1.0, 2.0, 3.0]),
+            Uniform::Float(FVec4::new(1.0, 2.0, 3.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn matrix_3x4() {
+        let matrix = Matrix4::identity().translated(1.0, 2.0, 3.0);
+        let rows = matrix.rows_wzyx();
+        assert_eq!(
+            Uniform::from(Matrix3x4(matrix)),
+            Uniform::Float3([rows[0], rows[1], rows[2]])
+        );
+        assert_eq!(Uniform::from(Matrix3x4(matrix)).len(), 3);
+        assert!(Matrix3x4(matrix).registers().eq(rows.into_iter().take(3)));
+    }
+
+    #[test]
+    fn invalid_index() {
+        let mut instance = Instance::new().unwrap();
+        let matrix = Uniform::Float4(Matrix4::identity());
+
+        for ty in [shader::Type::Vertex, shader::Type::Geometry] {
+            assert!(matrix.bind(&mut instance, ty, Index(0x5C)).is_ok());
+            assert!(matches!(
+                matrix.bind(&mut instance, ty, Index(0x5E)),
+                Err(crate::Error::InvalidUniformIndex {
+                    index: 0x5E,
+                    len: 4
+                })
+            ));
+            assert!(
+                Uniform::Int(IVec::new(0, 0, 0, 0))
+                    .bind(&mut instance, ty, Index(0))
+                    .is_err()
+            );
+            assert!(bind_array(&mut instance, ty, Index(0x50), &[Matrix4::identity(); 4]).is_ok());
+            assert!(bind_array(&mut instance, ty, Index(0x50), &[Matrix4::identity(); 5]).is_err());
+        }
+    }
+
+    #[test]
+    fn kind() {
+        assert_eq!(Kind::of(Index(0)), Some(Kind::Float));
+        assert_eq!(Kind::of(Index(0x5F)), Some(Kind::Float));
+        assert_eq!(Kind::of(Index(0x60)), Some(Kind::Int));
+        assert_eq!(Kind::of(Index(0x64)), None);
+        assert_eq!(Kind::of(Index(0x68)), Some(Kind::Bool));
+        assert_eq!(Kind::of(Index(0x80)), None);
+    }
+}